@@ -23,6 +23,13 @@ contract Authenticity is EIP712 {
 
     mapping(address manufacturer => IEri.Manufacturer) private manufacturers;
 
+    // a key signs for exactly one manufacturer and is never handed to another, even once revoked
+    mapping(address key => IEri.SigningKey) private signingKeys;
+
+    // block time a certificate was first seen on chain, by EIP-712 digest; the signer
+    // picks certificate.date, so key validity is judged against this instead
+    mapping(bytes32 digest => uint64) private issuedAt;
+
     //TODO-> TO REMOVE THIS COMPLETELY (DATABASE WILL CATER FOR THIS)
 //    mapping(string manufacturerName => address registeredAddress) private names;
 
@@ -30,6 +37,10 @@ contract Authenticity is EIP712 {
     // i added username to data instead of indexing it so that it can come out as a raw data and not hash
     event ManufacturerRegistered(address indexed manufacturerAddress, string username);
     event AuthenticityCreated(address indexed contractAddress, address indexed owner);
+    event SigningKeyAuthorized(address indexed manufacturer, address indexed key, uint64 validFrom, uint64 validUntil);
+    event SigningKeyRotated(address indexed manufacturer, address indexed oldKey, address indexed newKey, uint64 rotatedAt);
+    event SigningKeyRevoked(address indexed manufacturer, address indexed key, uint64 revokedAt);
+    event CertificatesRecorded(address indexed manufacturer, uint256 count, uint64 recordedAt);
 //    event ItemCreated(string indexed itemId, address indexed owner);
 
     modifier addressZeroCheck(address _user) {
@@ -37,6 +48,13 @@ contract Authenticity is EIP712 {
         _;
     }
 
+    modifier onlyManufacturer() {
+        if (manufacturers[msg.sender].manufacturerAddress == address(0)) {
+            revert EriErrors.NOT_REGISTERED(msg.sender);
+        }
+        _;
+    }
+
     constructor (
         address ownershipAdd,
         string memory certificate,
@@ -99,6 +117,126 @@ contract Authenticity is EIP712 {
         return manufacturers[userAddress];
     }
 
+    //the manufacturer's own address always signs; these are the extra (factory) keys
+    function authorizeSigningKey(address key, uint64 validFrom, uint64 validUntil) public onlyManufacturer addressZeroCheck(key) {
+        if (key == msg.sender || manufacturers[key].manufacturerAddress != address(0)) {
+            revert EriErrors.INVALID_SIGNING_KEY(key);
+        }
+
+        if (signingKeys[key].manufacturer != address(0)) {
+            revert EriErrors.ALREADY_REGISTERED(key);
+        }
+
+        if (validUntil != 0 && validUntil <= validFrom) {
+            revert EriErrors.INVALID_VALIDITY_WINDOW();
+        }
+
+        signingKeys[key] = IEri.SigningKey({
+            manufacturer: msg.sender,
+            validFrom: validFrom,
+            validUntil: validUntil,
+            revokedAt: 0
+        });
+
+        emit SigningKeyAuthorized(msg.sender, key, validFrom, validUntil);
+    }
+
+    //the new key takes over from now on, certificates of the old one recorded before that stay valid
+    function rotateSigningKey(address oldKey, address newKey, uint64 validUntil) external onlyManufacturer {
+        IEri.SigningKey storage old = signingKeys[oldKey];
+
+        if (old.manufacturer != msg.sender) {
+            revert EriErrors.ONLY_OWNER(msg.sender);
+        }
+
+        if (old.revokedAt != 0) {
+            revert EriErrors.SIGNING_KEY_REVOKED(oldKey);
+        }
+
+        uint64 rotatedAt = uint64(block.timestamp);
+        authorizeSigningKey(newKey, rotatedAt, validUntil);
+
+        if (old.validUntil == 0 || old.validUntil > rotatedAt) {
+            old.validUntil = rotatedAt;
+        }
+
+        emit SigningKeyRotated(msg.sender, oldKey, newKey, rotatedAt);
+    }
+
+    //a leak is usually noticed late, so the cut-off can be moved back in time but never forward
+    function revokeSigningKey(address key, uint64 revokedAt) external {
+        IEri.SigningKey storage signingKey = signingKeys[key];
+
+        if (signingKey.manufacturer != msg.sender) {
+            revert EriErrors.ONLY_OWNER(msg.sender);
+        }
+
+        if (signingKey.revokedAt != 0) {
+            revert EriErrors.SIGNING_KEY_REVOKED(key);
+        }
+
+        if (revokedAt == 0 || revokedAt > block.timestamp) {
+            revokedAt = uint64(block.timestamp);
+        }
+
+        signingKey.revokedAt = revokedAt;
+
+        emit SigningKeyRevoked(msg.sender, key, revokedAt);
+    }
+
+    function getSigningKey(address key) external view returns (IEri.SigningKey memory) {
+        if (signingKeys[key].manufacturer == address(0)) {
+            revert EriErrors.DOES_NOT_EXIST();
+        }
+        return signingKeys[key];
+    }
+
+    //anchors certificates before they are claimed, so they survive a later rotation or revocation
+    //of the key that signed them; a digest keeps the first time it was seen
+    function recordCertificates(bytes32[] calldata digests) external onlyManufacturer {
+        uint64 recordedAt = uint64(block.timestamp);
+
+        for (uint256 i = 0; i < digests.length; i++) {
+            if (issuedAt[digests[i]] == 0) {
+                issuedAt[digests[i]] = recordedAt;
+            }
+        }
+
+        emit CertificatesRecorded(msg.sender, digests.length, recordedAt);
+    }
+
+    //0 when the certificate was never recorded or claimed
+    function getIssuedAt(bytes32 digest) external view returns (uint64) {
+        return issuedAt[digest];
+    }
+
+    //whether signer could sign for manufacturer at `timestamp`, the time the certificate was issued
+    function isValidSigner(address manufacturer, address signer, uint256 timestamp) public view returns (bool) {
+        if (signer == manufacturer) {
+            return true;
+        }
+
+        IEri.SigningKey memory signingKey = signingKeys[signer];
+
+        if (signingKey.manufacturer == address(0) || signingKey.manufacturer != manufacturer) {
+            return false;
+        }
+
+        if (timestamp < signingKey.validFrom) {
+            return false;
+        }
+
+        if (signingKey.validUntil != 0 && timestamp >= signingKey.validUntil) {
+            return false;
+        }
+
+        if (signingKey.revokedAt != 0 && timestamp >= signingKey.revokedAt) {
+            return false;
+        }
+
+        return true;
+    }
+
     //TODO-> TO REMOVE THIS AND DO ON THE DATABASE
     //this will be used for off-chain verification
     // function getManufacturerAddress(address expectedManufacturer) public view returns (address) {
@@ -112,12 +250,7 @@ contract Authenticity is EIP712 {
     //     return manufacturer;
     // }
 
-    //TODO-> THIS STAYS ON THE SMART CONTRACT
-    function verifySignature(
-        IEri.Certificate memory certificate,
-        bytes memory signature
-    ) public view returns (bool)  {
-
+    function certificateDigest(IEri.Certificate memory certificate) internal view returns (bytes32) {
         // bytes32 metadataHash = keccak256(abi.encode(certificate.metadata));
         bytes32 structHash = keccak256(
             abi.encode(
@@ -131,7 +264,16 @@ contract Authenticity is EIP712 {
             )
         );
 
-        bytes32 digest = _hashTypedDataV4(structHash);
+        return _hashTypedDataV4(structHash);
+    }
+
+    //TODO-> THIS STAYS ON THE SMART CONTRACT
+    function verifySignature(
+        IEri.Certificate memory certificate,
+        bytes memory signature
+    ) public view returns (bool)  {
+
+        bytes32 digest = certificateDigest(certificate);
         address signer = digest.recover(signature);
 
         //very important, to make sure the owner is genuine and valid
@@ -145,8 +287,10 @@ contract Authenticity is EIP712 {
             revert EriErrors.DOES_NOT_EXIST();
         }
 
-        //check the signer against a genuine manufacturer
-        if (signer != manufacturerAddress) {
+        //check the signer against a genuine manufacturer or one of its keys, as of when the
+        //certificate was first seen (now, if it never was)
+        uint256 seenAt = issuedAt[digest] == 0 ? block.timestamp : issuedAt[digest];
+        if (!isValidSigner(manufacturerAddress, signer, seenAt)) {
             revert EriErrors.INVALID_SIGNATURE();
         }
 
@@ -168,6 +312,11 @@ contract Authenticity is EIP712 {
             revert EriErrors.INVALID_SIGNATURE();
         }
 
+        bytes32 digest = certificateDigest(certificate);
+        if (issuedAt[digest] == 0) {
+            issuedAt[digest] = uint64(block.timestamp);
+        }
+
         string memory manufacturerName = manufacturers[certificate.owner].name;
 
         OWNERSHIP.createItem(msg.sender, certificate, manufacturerName);
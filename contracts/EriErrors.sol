@@ -21,6 +21,11 @@ contract EriErrors {
     error USERNAME_MUST_BE_AT_LEAST_3_LETTERS();
     error INVALID_MANUFACTURER_NAME(string);
     error AUTHENTICITY_NOT_SET();
+    error INVALID_CODE();
+    error CODE_EXPIRED();
+    error INVALID_SIGNING_KEY(address);
+    error INVALID_VALIDITY_WINDOW();
+    error SIGNING_KEY_REVOKED(address);
 }
 
 
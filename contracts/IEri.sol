@@ -16,6 +16,14 @@ interface IEri {
         address manufacturerAddress;
     }
 
+    // an extra address allowed to sign certificates on behalf of a manufacturer
+    struct SigningKey {
+        address manufacturer;
+        uint64 validFrom; // certificates first seen before this are not accepted
+        uint64 validUntil; // 0 = open ended
+        uint64 revokedAt; // 0 = not revoked, otherwise certificates first seen from here on are rejected
+    }
+
     struct Certificate {
         string name;
         string uniqueId;
@@ -37,6 +45,13 @@ interface IEri {
         string[] metadata;
     }
 
+    // commitment for a change of ownership; the code itself is only revealed when claiming
+    struct TransferCode {
+        bytes32 codeHash; // keccak256 of the code
+        address tempOwner;
+        uint64 expiresAt; // the code cannot be claimed from here on
+    }
+
     struct Owner {
         string name;
         string itemId; // something very unique like the IMEI of a phone
@@ -17,6 +17,9 @@ contract Ownership {
     // this links itemId to the Item
     mapping(string => IEri.Item) private items;
 
+    // this links itemId to the pending change of ownership code (one per item)
+    mapping(string => IEri.TransferCode) private transferCodes;
+
 
     event OwnershipCreated(
         address indexed contractAddress,
@@ -24,13 +27,20 @@ contract Ownership {
     );
     //todo: to remove username and leave only the userAddress, or remove username indexing so it's emitted raw
     event UserRegistered(address indexed userAddress, string username);
+    event UsernameChanged(address indexed userAddress, string oldUsername, string newUsername);
     event ItemCreated(string itemId);
     event OwnershipTransferred(
         string itemId,
         address indexed newOnwer,
         address indexed oldOnwer
     );
-    // event CodeRevoked(bytes32 indexed itemHash);
+    event OwnershipCode(
+        string itemId,
+        bytes32 indexed codeHash,
+        address indexed owner,
+        address indexed tempOwner
+    );
+    event CodeRevoked(bytes32 indexed itemHash);
     event AuthenticitySet(address indexed authenticityAddress);
 
     constructor(address _owner) {
@@ -89,6 +99,27 @@ contract Ownership {
         emit UserRegistered(userAddress, username);
     }
 
+    //items keep pointing at the wallet, so only the name shown for it changes
+    function changeUsername(
+        string calldata newUsername
+    ) external isAuthenticitySet {
+
+        address userAddress = msg.sender;
+
+        if (!isRegistered(userAddress)) {
+            revert EriErrors.NOT_REGISTERED(userAddress);
+        }
+
+        if (bytes(newUsername).length < 3) {
+            revert EriErrors.USERNAME_MUST_BE_AT_LEAST_3_LETTERS();
+        }
+
+        string memory oldUsername = usernames[userAddress];
+        usernames[userAddress] = newUsername;
+
+        emit UsernameChanged(userAddress, oldUsername, newUsername);
+    }
+
     //this returns the username of a user instead of the whole user profile
     function getUsername(
         address userAddress
@@ -144,29 +175,104 @@ contract Ownership {
         emit ItemCreated(certificate.uniqueId);
     }
 
-    // function newOwnerClaimOwnership(bytes32 itemHash)
-    function newOwnerClaimOwnership(
-        string memory itemId
-    ) external isAuthenticitySet addressZeroCheck(msg.sender) {
+    //the owner commits to keccak256(code) for a temp owner, the code itself is shared off-chain
+    function generateCode(
+        string memory itemId,
+        bytes32 codeHash,
+        address tempOwner,
+        uint64 expiresAt
+    ) external isAuthenticitySet addressZeroCheck(msg.sender) addressZeroCheck(tempOwner) {
         address _caller = msg.sender;
-        if (!isRegistered(_caller)) {
-            revert EriErrors.NOT_REGISTERED(_caller);
-        }
-
-        address newOwner = _caller;
 
-        //item mus exist to change owner, else we'll be creating a new item
         if (items[itemId].owner == address(0)) {
             revert EriErrors.ITEM_DOESNT_EXIST(itemId);
         }
 
+        if (items[itemId].owner != _caller) {
+            revert EriErrors.ONLY_OWNER(_caller);
+        }
+
+        if (tempOwner == _caller) {
+            revert EriErrors.CANNOT_GENERATE_CODE_FOR_YOURSELF(_caller);
+        }
+
+        if (!isRegistered(tempOwner)) {
+            revert EriErrors.NOT_REGISTERED(tempOwner);
+        }
+
+        if (expiresAt <= block.timestamp) {
+            revert EriErrors.INVALID_VALIDITY_WINDOW();
+        }
+
+        //only one live code at a time, the owner has to revoke it or let it expire first
+        IEri.TransferCode memory current = transferCodes[itemId];
+        if (current.codeHash != bytes32(0) && current.expiresAt > block.timestamp) {
+            revert EriErrors.CODE_ALREADY_GENERATED();
+        }
+
+        transferCodes[itemId] = IEri.TransferCode({
+            codeHash: codeHash,
+            tempOwner: tempOwner,
+            expiresAt: expiresAt
+        });
+
+        emit OwnershipCode(itemId, codeHash, _caller, tempOwner);
+    }
+
+    function revokeCode(string memory itemId) external isAuthenticitySet {
+        if (items[itemId].owner != msg.sender) {
+            revert EriErrors.ONLY_OWNER(msg.sender);
+        }
+
+        bytes32 codeHash = transferCodes[itemId].codeHash;
+
+        if (codeHash == bytes32(0)) {
+            revert EriErrors.DOES_NOT_EXIST();
+        }
+
+        delete transferCodes[itemId];
+
+        emit CodeRevoked(codeHash);
+    }
+
+    //the temp owner reveals the code, only they can use it and only once
+    function claimWithCode(
+        string memory itemId,
+        string memory code
+    ) external isAuthenticitySet addressZeroCheck(msg.sender) {
+        IEri.TransferCode memory transferCode = transferCodes[itemId];
+
+        if (transferCode.codeHash == bytes32(0)) {
+            revert EriErrors.DOES_NOT_EXIST();
+        }
+
+        if (transferCode.tempOwner != msg.sender) {
+            revert EriErrors.UNAUTHORIZED(msg.sender);
+        }
+
+        if (block.timestamp >= transferCode.expiresAt) {
+            revert EriErrors.CODE_EXPIRED();
+        }
+
+        if (keccak256(bytes(code)) != transferCode.codeHash) {
+            revert EriErrors.INVALID_CODE();
+        }
+
         IEri.Item storage _item = items[itemId];
 
         address oldOwner = _item.owner;
 
-        _item.owner = newOwner;
+        _item.owner = msg.sender;
+
+        delete transferCodes[itemId];
 
-        emit OwnershipTransferred(itemId, newOwner, oldOwner);
+        emit OwnershipTransferred(itemId, msg.sender, oldOwner);
+    }
+
+    function getTransferCode(
+        string memory itemId
+    ) external view isAuthenticitySet returns (IEri.TransferCode memory) {
+        return transferCodes[itemId];
     }
 
     //this function is meant to verify the owner of an item
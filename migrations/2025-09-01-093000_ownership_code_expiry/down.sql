@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_ownership_codes_active_expiry;
+DROP INDEX IF EXISTS idx_ownership_codes_item_id;
+
+ALTER TABLE ownership_codes
+    DROP COLUMN IF EXISTS expires_at,
+    DROP COLUMN IF EXISTS is_active;
@@ -0,0 +1,6 @@
+ALTER TABLE ownership_codes
+    ADD COLUMN IF NOT EXISTS is_active  BOOLEAN     NOT NULL DEFAULT TRUE,
+    ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ NOT NULL DEFAULT (NOW() + INTERVAL '1 day');
+
+CREATE INDEX IF NOT EXISTS idx_ownership_codes_item_id ON ownership_codes (item_id);
+CREATE INDEX IF NOT EXISTS idx_ownership_codes_active_expiry ON ownership_codes (expires_at) WHERE is_active;
@@ -0,0 +1,2 @@
+ALTER TABLE ownership_codes DROP COLUMN IF EXISTS code_format;
+ALTER TABLE ownership_codes RENAME COLUMN code_hash TO ownership_code;
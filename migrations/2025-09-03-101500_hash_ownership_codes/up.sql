@@ -0,0 +1,6 @@
+-- Existing codes were derived from public inputs and are guessable, so they are retired
+-- before the column switches to holding only the keccak256 hash of the code.
+UPDATE ownership_codes SET is_active = FALSE WHERE is_active;
+
+ALTER TABLE ownership_codes RENAME COLUMN ownership_code TO code_hash;
+ALTER TABLE ownership_codes ADD COLUMN IF NOT EXISTS code_format TEXT NOT NULL DEFAULT 'hex';
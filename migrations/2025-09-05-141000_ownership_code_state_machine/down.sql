@@ -0,0 +1,13 @@
+DROP INDEX IF EXISTS idx_ownership_codes_offered_expiry;
+DROP INDEX IF EXISTS uq_ownership_codes_offered_item;
+
+ALTER TABLE ownership_codes DROP CONSTRAINT IF EXISTS ownership_codes_status_check;
+ALTER TABLE ownership_codes ADD COLUMN IF NOT EXISTS is_active BOOLEAN NOT NULL DEFAULT TRUE;
+
+UPDATE ownership_codes SET is_active = (status = 'offered');
+
+CREATE INDEX IF NOT EXISTS idx_ownership_codes_active_expiry ON ownership_codes (expires_at) WHERE is_active;
+
+ALTER TABLE ownership_codes
+    DROP COLUMN IF EXISTS status_changed_at,
+    DROP COLUMN IF EXISTS status;
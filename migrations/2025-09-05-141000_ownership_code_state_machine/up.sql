@@ -0,0 +1,30 @@
+-- Each ownership code is one transfer offer. An item is "idle" while it has no offered code,
+-- and an offer ends up claimed, cancelled or expired. is_active is folded into status.
+ALTER TABLE ownership_codes
+    ADD COLUMN IF NOT EXISTS status            TEXT        NOT NULL DEFAULT 'offered',
+    ADD COLUMN IF NOT EXISTS status_changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+UPDATE ownership_codes
+SET status = CASE WHEN is_active AND expires_at > NOW() THEN 'offered' ELSE 'expired' END;
+
+-- Only the newest offer per item survives; older duplicates are cancelled
+UPDATE ownership_codes oc
+SET status = 'cancelled'
+WHERE oc.status = 'offered'
+  AND EXISTS (SELECT 1
+              FROM ownership_codes newer
+              WHERE newer.item_id = oc.item_id
+                AND newer.status = 'offered'
+                AND newer.created_at > oc.created_at);
+
+DROP INDEX IF EXISTS idx_ownership_codes_active_expiry;
+ALTER TABLE ownership_codes DROP COLUMN IF EXISTS is_active;
+
+ALTER TABLE ownership_codes
+    ADD CONSTRAINT ownership_codes_status_check
+        CHECK (status IN ('offered', 'claimed', 'cancelled', 'expired'));
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_ownership_codes_offered_item
+    ON ownership_codes (item_id) WHERE status = 'offered';
+CREATE INDEX IF NOT EXISTS idx_ownership_codes_offered_expiry
+    ON ownership_codes (expires_at) WHERE status = 'offered';
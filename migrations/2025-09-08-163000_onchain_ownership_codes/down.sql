@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS uq_code_revokations_item_hash_tnx;
+
+ALTER TABLE ownership_codes DROP COLUMN IF EXISTS commit_tnx_hash;
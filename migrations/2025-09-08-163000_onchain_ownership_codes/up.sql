@@ -0,0 +1,4 @@
+-- Set once the owner's generateCode transaction has been indexed
+ALTER TABLE ownership_codes ADD COLUMN IF NOT EXISTS commit_tnx_hash TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_code_revokations_item_hash_tnx ON code_revokations (item_hash, tnx_hash);
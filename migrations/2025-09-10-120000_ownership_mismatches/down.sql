@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS uq_ownership_mismatches_open_item;
+DROP TABLE IF EXISTS ownership_mismatches;
@@ -0,0 +1,14 @@
+-- Items whose indexed owner disagreed with the Ownership contract when it was checked.
+-- A row stays open (resolved_at IS NULL) until the indexer catches up with the item.
+CREATE TABLE IF NOT EXISTS ownership_mismatches
+(
+    id          SERIAL PRIMARY KEY,
+    item_id     TEXT        NOT NULL,
+    db_owner    TEXT        NOT NULL,
+    chain_owner TEXT,
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_ownership_mismatches_open_item
+    ON ownership_mismatches (item_id) WHERE resolved_at IS NULL;
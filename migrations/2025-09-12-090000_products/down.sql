@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_items_product_id;
+ALTER TABLE items DROP COLUMN IF EXISTS product_id;
+
+DROP TABLE IF EXISTS certificate_products;
+
+DROP INDEX IF EXISTS uq_products_manufacturer_sku;
+DROP TABLE IF EXISTS products;
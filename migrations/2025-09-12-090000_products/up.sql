@@ -0,0 +1,26 @@
+-- Product templates a manufacturer issues units from. attribute_schema is a JSON array of
+-- {"name", "type": "string" | "number" | "boolean", "required", "allowed_values"}.
+CREATE TABLE IF NOT EXISTS products
+(
+    id                   SERIAL PRIMARY KEY,
+    manufacturer_address TEXT        NOT NULL,
+    sku                  TEXT        NOT NULL,
+    name                 TEXT        NOT NULL,
+    attribute_schema     JSONB       NOT NULL DEFAULT '[]',
+    images               TEXT[]      NOT NULL DEFAULT '{}',
+    created_at           TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at           TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_products_manufacturer_sku ON products (LOWER(manufacturer_address), sku);
+
+-- Certificates issued from a product, so the indexer can link the item once it is claimed
+CREATE TABLE IF NOT EXISTS certificate_products
+(
+    unique_id  TEXT PRIMARY KEY,
+    product_id INTEGER     NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE items ADD COLUMN IF NOT EXISTS product_id INTEGER;
+CREATE INDEX IF NOT EXISTS idx_items_product_id ON items (product_id);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS uq_item_flags_active_item;
+DROP INDEX IF EXISTS idx_item_flags_item;
+DROP TABLE IF EXISTS item_flags;
@@ -0,0 +1,22 @@
+-- Lifecycle flags raised on items by their owner or manufacturer (stolen, lost, recalled,
+-- destroyed). Rows are never deleted: clearing a flag only sets cleared_at, so the table
+-- doubles as the item's flag history. At most one flag is active per item.
+CREATE TABLE IF NOT EXISTS item_flags
+(
+    id         SERIAL PRIMARY KEY,
+    item_id    TEXT        NOT NULL,
+    status     TEXT        NOT NULL,
+    reason     TEXT        NOT NULL,
+    evidence   TEXT[]      NOT NULL DEFAULT '{}',
+    flagged_by TEXT        NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    cleared_by TEXT,
+    cleared_at TIMESTAMPTZ,
+    CONSTRAINT chk_item_flags_status
+        CHECK (status IN ('stolen', 'lost', 'recalled', 'destroyed'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_item_flags_item ON item_flags (item_id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_item_flags_active_item
+    ON item_flags (item_id) WHERE cleared_at IS NULL;
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_recall_items_item;
+DROP TABLE IF EXISTS recall_items;
+DROP INDEX IF EXISTS idx_recall_campaigns_manufacturer;
+DROP TABLE IF EXISTS recall_campaigns;
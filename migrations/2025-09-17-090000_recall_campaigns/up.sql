@@ -0,0 +1,34 @@
+-- Recalls opened by a manufacturer. A campaign selects its units with any combination of
+-- a serial range, a product (SKU) and a production date window; all given criteria must match.
+CREATE TABLE IF NOT EXISTS recall_campaigns
+(
+    id                   SERIAL PRIMARY KEY,
+    manufacturer_address TEXT        NOT NULL,
+    title                TEXT        NOT NULL,
+    reason               TEXT        NOT NULL,
+    remedy               TEXT,
+    serial_from          TEXT,
+    serial_to            TEXT,
+    product_id           INTEGER,
+    produced_from        BIGINT,
+    produced_to          BIGINT,
+    created_at           TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    closed_at            TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_recall_campaigns_manufacturer
+    ON recall_campaigns (LOWER(manufacturer_address));
+
+-- Units a campaign matched. The row is the owner's notice: it shows up for whoever
+-- currently owns the item until they acknowledge it.
+CREATE TABLE IF NOT EXISTS recall_items
+(
+    campaign_id     INTEGER     NOT NULL,
+    item_id         TEXT        NOT NULL,
+    matched_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    acknowledged_by TEXT,
+    acknowledged_at TIMESTAMPTZ,
+    PRIMARY KEY (campaign_id, item_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_recall_items_item ON recall_items (item_id);
@@ -0,0 +1,52 @@
+ALTER TABLE authenticity_settings
+    DROP COLUMN IF EXISTS block_time,
+    DROP COLUMN IF EXISTS log_index,
+    DROP COLUMN IF EXISTS block_number,
+    ALTER COLUMN created_at DROP DEFAULT,
+    ALTER COLUMN created_at TYPE TEXT USING to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US"+00:00"');
+
+ALTER TABLE code_revokations
+    DROP COLUMN IF EXISTS block_time,
+    DROP COLUMN IF EXISTS log_index,
+    DROP COLUMN IF EXISTS block_number,
+    ALTER COLUMN created_at DROP DEFAULT,
+    ALTER COLUMN created_at TYPE TEXT USING to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US"+00:00"');
+
+ALTER TABLE ownership_claims
+    DROP COLUMN IF EXISTS block_time,
+    DROP COLUMN IF EXISTS log_index,
+    DROP COLUMN IF EXISTS block_number,
+    ALTER COLUMN created_at DROP DEFAULT,
+    ALTER COLUMN created_at TYPE TEXT USING to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US"+00:00"');
+
+ALTER TABLE items
+    DROP COLUMN IF EXISTS block_time,
+    DROP COLUMN IF EXISTS log_index,
+    DROP COLUMN IF EXISTS block_number,
+    ALTER COLUMN created_at DROP DEFAULT,
+    ALTER COLUMN created_at TYPE TEXT USING to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US"+00:00"');
+
+ALTER TABLE manufacturers
+    DROP COLUMN IF EXISTS block_time,
+    DROP COLUMN IF EXISTS log_index,
+    DROP COLUMN IF EXISTS block_number,
+    ALTER COLUMN registered_at DROP DEFAULT,
+    ALTER COLUMN registered_at TYPE TEXT USING to_char(registered_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US"+00:00"');
+
+ALTER TABLE users_info
+    DROP COLUMN IF EXISTS block_time,
+    DROP COLUMN IF EXISTS log_index,
+    DROP COLUMN IF EXISTS block_number,
+    ALTER COLUMN created_at DROP DEFAULT,
+    ALTER COLUMN created_at TYPE TEXT USING to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US"+00:00"');
+
+ALTER TABLE contracts
+    DROP COLUMN IF EXISTS block_time,
+    DROP COLUMN IF EXISTS log_index,
+    DROP COLUMN IF EXISTS block_number,
+    ALTER COLUMN created_at DROP DEFAULT,
+    ALTER COLUMN created_at TYPE TEXT USING to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US"+00:00"');
+
+ALTER TABLE ownership_codes
+    ALTER COLUMN created_at DROP DEFAULT,
+    ALTER COLUMN created_at TYPE TEXT USING to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US"+00:00"');
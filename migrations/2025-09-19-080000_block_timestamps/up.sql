@@ -0,0 +1,58 @@
+-- Indexed rows were stamped with the time the log was processed, stored as TEXT.
+-- Timestamps become TIMESTAMPTZ, and every table the indexer writes records where its log
+-- sits on chain. created_at (registered_at for manufacturers) is the block time from now on;
+-- rows indexed before this migration keep their processing time and have no block data.
+
+ALTER TABLE contracts
+    ALTER COLUMN created_at TYPE TIMESTAMPTZ USING created_at::TIMESTAMPTZ,
+    ALTER COLUMN created_at SET DEFAULT NOW(),
+    ADD COLUMN IF NOT EXISTS block_number BIGINT,
+    ADD COLUMN IF NOT EXISTS log_index    INTEGER,
+    ADD COLUMN IF NOT EXISTS block_time   TIMESTAMPTZ;
+
+ALTER TABLE users_info
+    ALTER COLUMN created_at TYPE TIMESTAMPTZ USING created_at::TIMESTAMPTZ,
+    ALTER COLUMN created_at SET DEFAULT NOW(),
+    ADD COLUMN IF NOT EXISTS block_number BIGINT,
+    ADD COLUMN IF NOT EXISTS log_index    INTEGER,
+    ADD COLUMN IF NOT EXISTS block_time   TIMESTAMPTZ;
+
+ALTER TABLE manufacturers
+    ALTER COLUMN registered_at TYPE TIMESTAMPTZ USING registered_at::TIMESTAMPTZ,
+    ALTER COLUMN registered_at SET DEFAULT NOW(),
+    ADD COLUMN IF NOT EXISTS block_number BIGINT,
+    ADD COLUMN IF NOT EXISTS log_index    INTEGER,
+    ADD COLUMN IF NOT EXISTS block_time   TIMESTAMPTZ;
+
+ALTER TABLE items
+    ALTER COLUMN created_at TYPE TIMESTAMPTZ USING created_at::TIMESTAMPTZ,
+    ALTER COLUMN created_at SET DEFAULT NOW(),
+    ADD COLUMN IF NOT EXISTS block_number BIGINT,
+    ADD COLUMN IF NOT EXISTS log_index    INTEGER,
+    ADD COLUMN IF NOT EXISTS block_time   TIMESTAMPTZ;
+
+ALTER TABLE ownership_claims
+    ALTER COLUMN created_at TYPE TIMESTAMPTZ USING created_at::TIMESTAMPTZ,
+    ALTER COLUMN created_at SET DEFAULT NOW(),
+    ADD COLUMN IF NOT EXISTS block_number BIGINT,
+    ADD COLUMN IF NOT EXISTS log_index    INTEGER,
+    ADD COLUMN IF NOT EXISTS block_time   TIMESTAMPTZ;
+
+ALTER TABLE code_revokations
+    ALTER COLUMN created_at TYPE TIMESTAMPTZ USING created_at::TIMESTAMPTZ,
+    ALTER COLUMN created_at SET DEFAULT NOW(),
+    ADD COLUMN IF NOT EXISTS block_number BIGINT,
+    ADD COLUMN IF NOT EXISTS log_index    INTEGER,
+    ADD COLUMN IF NOT EXISTS block_time   TIMESTAMPTZ;
+
+ALTER TABLE authenticity_settings
+    ALTER COLUMN created_at TYPE TIMESTAMPTZ USING created_at::TIMESTAMPTZ,
+    ALTER COLUMN created_at SET DEFAULT NOW(),
+    ADD COLUMN IF NOT EXISTS block_number BIGINT,
+    ADD COLUMN IF NOT EXISTS log_index    INTEGER,
+    ADD COLUMN IF NOT EXISTS block_time   TIMESTAMPTZ;
+
+-- written by the API and the indexer alike, so it only changes type
+ALTER TABLE ownership_codes
+    ALTER COLUMN created_at TYPE TIMESTAMPTZ USING created_at::TIMESTAMPTZ,
+    ALTER COLUMN created_at SET DEFAULT NOW();
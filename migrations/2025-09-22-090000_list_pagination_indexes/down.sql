@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_ownership_claims_created_at;
+DROP INDEX IF EXISTS idx_users_info_created_at;
+DROP INDEX IF EXISTS idx_items_owner_created_at;
+DROP INDEX IF EXISTS idx_items_name;
+DROP INDEX IF EXISTS idx_items_created_at;
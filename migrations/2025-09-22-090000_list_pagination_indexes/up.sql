@@ -0,0 +1,6 @@
+-- keyset pagination: every list is ordered by (created_at | name, id)
+CREATE INDEX IF NOT EXISTS idx_items_created_at ON items (created_at, item_id);
+CREATE INDEX IF NOT EXISTS idx_items_name ON items (name, item_id);
+CREATE INDEX IF NOT EXISTS idx_items_owner_created_at ON items (owner, created_at, item_id);
+CREATE INDEX IF NOT EXISTS idx_users_info_created_at ON users_info (created_at, user_address);
+CREATE INDEX IF NOT EXISTS idx_ownership_claims_created_at ON ownership_claims (created_at, id);
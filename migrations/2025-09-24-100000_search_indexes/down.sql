@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS idx_items_search_tsv;
+DROP INDEX IF EXISTS idx_users_info_username_trgm;
+DROP INDEX IF EXISTS idx_manufacturers_name_trgm;
+DROP INDEX IF EXISTS idx_items_manufacturer_trgm;
+DROP INDEX IF EXISTS idx_items_item_id_trgm;
+DROP INDEX IF EXISTS idx_items_serial_trgm;
+DROP INDEX IF EXISTS idx_items_name_trgm;
+-- pg_trgm is left installed; other objects may depend on it
@@ -0,0 +1,14 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- trigram indexes serve both similarity (%) and substring ILIKE lookups
+CREATE INDEX IF NOT EXISTS idx_items_name_trgm ON items USING GIN (name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_items_serial_trgm ON items USING GIN (serial gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_items_item_id_trgm ON items USING GIN (item_id gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_items_manufacturer_trgm ON items USING GIN (manufacturer gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_manufacturers_name_trgm ON manufacturers USING GIN (manufacturer_name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_users_info_username_trgm ON users_info USING GIN (username gin_trgm_ops);
+
+-- full-text over the item's own searchable text; 'simple' so serials and ids aren't stemmed
+CREATE INDEX IF NOT EXISTS idx_items_search_tsv ON items USING GIN (
+    to_tsvector('simple', name || ' ' || serial || ' ' || item_id)
+);
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_verification_logs_unique_id;
+DROP INDEX IF EXISTS idx_verification_logs_manufacturer;
+DROP TABLE IF EXISTS verification_logs;
+DROP INDEX IF EXISTS idx_issued_certificates_manufacturer;
+DROP TABLE IF EXISTS issued_certificates;
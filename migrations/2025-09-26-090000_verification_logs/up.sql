@@ -0,0 +1,30 @@
+-- Certificates prepared for a manufacturer (single or bulk). An issued certificate is
+-- claimed once its unique_id shows up in items.
+CREATE TABLE IF NOT EXISTS issued_certificates
+(
+    unique_id            TEXT PRIMARY KEY,
+    manufacturer_address TEXT        NOT NULL,
+    created_at           TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_issued_certificates_manufacturer
+    ON issued_certificates (LOWER(manufacturer_address), created_at);
+
+-- One row per /verify_authenticity scan. claimed_manufacturer is the address the
+-- certificate names; signer is who actually signed it.
+CREATE TABLE IF NOT EXISTS verification_logs
+(
+    id                   SERIAL PRIMARY KEY,
+    unique_id            TEXT        NOT NULL,
+    cert_hash            TEXT        NOT NULL,
+    claimed_manufacturer TEXT        NOT NULL,
+    signer               TEXT,
+    result               TEXT        NOT NULL CHECK (result IN ('authentic', 'counterfeit', 'invalid')),
+    region               TEXT,
+    created_at           TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_verification_logs_manufacturer
+    ON verification_logs (LOWER(claimed_manufacturer), created_at);
+CREATE INDEX IF NOT EXISTS idx_verification_logs_unique_id
+    ON verification_logs (unique_id, created_at);
@@ -0,0 +1,12 @@
+DROP INDEX IF EXISTS uq_verification_alerts_open;
+DROP INDEX IF EXISTS idx_verification_alerts_manufacturer;
+DROP TABLE IF EXISTS verification_alerts;
+
+DELETE FROM verification_logs WHERE cert_hash IS NULL OR result = 'error';
+ALTER TABLE verification_logs
+    DROP CONSTRAINT IF EXISTS verification_logs_result_check,
+    ADD CONSTRAINT verification_logs_result_check
+        CHECK (result IN ('authentic', 'counterfeit', 'invalid')),
+    DROP COLUMN IF EXISTS api_key_hash,
+    DROP COLUMN IF EXISTS client_ip,
+    ALTER COLUMN cert_hash SET NOT NULL;
@@ -0,0 +1,29 @@
+-- Every attempt is kept now, including ones that never got as far as a digest
+ALTER TABLE verification_logs
+    ALTER COLUMN cert_hash DROP NOT NULL,
+    ADD COLUMN IF NOT EXISTS client_ip TEXT,
+    ADD COLUMN IF NOT EXISTS api_key_hash TEXT,
+    DROP CONSTRAINT IF EXISTS verification_logs_result_check,
+    ADD CONSTRAINT verification_logs_result_check
+        CHECK (result IN ('authentic', 'counterfeit', 'invalid', 'error'));
+
+-- Suspected cloned or forged certificates, one open alert per certificate and kind;
+-- repeated hits bump scan_count and last_seen until the manufacturer resolves it
+CREATE TABLE IF NOT EXISTS verification_alerts
+(
+    id                   SERIAL PRIMARY KEY,
+    unique_id            TEXT        NOT NULL,
+    manufacturer_address TEXT        NOT NULL,
+    kind                 TEXT        NOT NULL CHECK (kind IN ('many_locations', 'claimed_item_rescanned', 'forged_signature')),
+    details              TEXT        NOT NULL,
+    scan_count           INTEGER     NOT NULL DEFAULT 1,
+    first_seen           TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen            TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_by          TEXT,
+    resolved_at          TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_verification_alerts_manufacturer
+    ON verification_alerts (LOWER(manufacturer_address), first_seen);
+CREATE UNIQUE INDEX IF NOT EXISTS uq_verification_alerts_open
+    ON verification_alerts (unique_id, kind) WHERE resolved_at IS NULL;
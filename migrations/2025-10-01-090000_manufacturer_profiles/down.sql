@@ -0,0 +1,13 @@
+DROP INDEX IF EXISTS idx_manufacturers_kyb_status;
+
+ALTER TABLE manufacturers
+    DROP COLUMN kyb_reviewed_at,
+    DROP COLUMN kyb_reviewed_by,
+    DROP COLUMN kyb_note,
+    DROP COLUMN kyb_status,
+    DROP COLUMN profile_updated_at,
+    DROP COLUMN contact_phone,
+    DROP COLUMN contact_email,
+    DROP COLUMN website,
+    DROP COLUMN logo_url,
+    DROP COLUMN legal_name;
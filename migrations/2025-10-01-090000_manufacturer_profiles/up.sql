@@ -0,0 +1,15 @@
+-- Self-declared profile of a manufacturer, plus the KYB review set by admins
+ALTER TABLE manufacturers
+    ADD COLUMN legal_name TEXT,
+    ADD COLUMN logo_url TEXT,
+    ADD COLUMN website TEXT,
+    ADD COLUMN contact_email TEXT,
+    ADD COLUMN contact_phone TEXT,
+    ADD COLUMN profile_updated_at TIMESTAMPTZ,
+    ADD COLUMN kyb_status TEXT NOT NULL DEFAULT 'unverified'
+        CHECK (kyb_status IN ('unverified', 'pending', 'verified', 'rejected')),
+    ADD COLUMN kyb_note TEXT,
+    ADD COLUMN kyb_reviewed_by TEXT,
+    ADD COLUMN kyb_reviewed_at TIMESTAMPTZ;
+
+CREATE INDEX idx_manufacturers_kyb_status ON manufacturers (kyb_status);
@@ -0,0 +1,18 @@
+-- Extra addresses a manufacturer has authorized to sign certificates, mirrored from
+-- the SigningKeyAuthorized / SigningKeyRotated / SigningKeyRevoked events
+CREATE TABLE signing_keys (
+    key_address TEXT PRIMARY KEY,
+    manufacturer_address TEXT NOT NULL,
+    valid_from TIMESTAMPTZ NOT NULL,
+    valid_until TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    -- the key that replaced this one, when it was rotated out
+    rotated_to TEXT,
+    tnx_hash TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index INTEGER NOT NULL,
+    block_time TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_signing_keys_manufacturer ON signing_keys (LOWER(manufacturer_address));
@@ -0,0 +1,9 @@
+DELETE FROM verification_logs WHERE result = 'revoked';
+ALTER TABLE verification_logs
+    DROP CONSTRAINT IF EXISTS verification_logs_result_check,
+    ADD CONSTRAINT verification_logs_result_check
+        CHECK (result IN ('authentic', 'counterfeit', 'invalid', 'error'));
+
+DROP INDEX IF EXISTS idx_certificate_revocations_revoked_at;
+DROP INDEX IF EXISTS idx_certificate_revocations_manufacturer;
+DROP TABLE IF EXISTS certificate_revocations;
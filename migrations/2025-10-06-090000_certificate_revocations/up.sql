@@ -0,0 +1,21 @@
+-- Individually revoked certificates (defective or stolen units), keyed by the EIP-712
+-- digest so one reprint of a unique_id can be revoked without touching the others
+CREATE TABLE IF NOT EXISTS certificate_revocations
+(
+    cert_hash            TEXT PRIMARY KEY,
+    unique_id            TEXT        NOT NULL,
+    manufacturer_address TEXT        NOT NULL,
+    reason               TEXT        NOT NULL,
+    revoked_by           TEXT        NOT NULL,
+    revoked_at           TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_certificate_revocations_manufacturer
+    ON certificate_revocations (LOWER(manufacturer_address), revoked_at);
+CREATE INDEX IF NOT EXISTS idx_certificate_revocations_revoked_at
+    ON certificate_revocations (revoked_at);
+
+ALTER TABLE verification_logs
+    DROP CONSTRAINT IF EXISTS verification_logs_result_check,
+    ADD CONSTRAINT verification_logs_result_check
+        CHECK (result IN ('authentic', 'counterfeit', 'revoked', 'invalid', 'error'));
@@ -0,0 +1,7 @@
+ALTER TABLE users_info
+    DROP COLUMN profile_updated_at,
+    DROP COLUMN show_email,
+    DROP COLUMN profile_visibility,
+    DROP COLUMN email,
+    DROP COLUMN avatar_url,
+    DROP COLUMN display_name;
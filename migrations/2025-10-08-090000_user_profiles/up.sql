@@ -0,0 +1,9 @@
+-- Off-chain profile of a user; only the username lives on-chain
+ALTER TABLE users_info
+    ADD COLUMN display_name TEXT,
+    ADD COLUMN avatar_url TEXT,
+    ADD COLUMN email TEXT,
+    ADD COLUMN profile_visibility TEXT NOT NULL DEFAULT 'public'
+        CHECK (profile_visibility IN ('public', 'private')),
+    ADD COLUMN show_email BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN profile_updated_at TIMESTAMPTZ;
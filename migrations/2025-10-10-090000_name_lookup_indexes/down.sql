@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_manufacturers_lower_name;
+DROP INDEX IF EXISTS idx_users_info_lower_username;
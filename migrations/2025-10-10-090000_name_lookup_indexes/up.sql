@@ -0,0 +1,3 @@
+-- Usernames and manufacturer names share one case-insensitive namespace
+CREATE INDEX IF NOT EXISTS idx_users_info_lower_username ON users_info (LOWER(username));
+CREATE INDEX IF NOT EXISTS idx_manufacturers_lower_name ON manufacturers (LOWER(manufacturer_name));
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS gas_budgets;
+DROP INDEX IF EXISTS idx_gas_ledger_created_at;
+DROP INDEX IF EXISTS idx_gas_ledger_sponsor;
+DROP TABLE IF EXISTS gas_ledger;
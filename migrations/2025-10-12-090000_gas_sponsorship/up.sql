@@ -0,0 +1,33 @@
+-- Gas the backend wallet paid, one row per transaction, and who it was paid for
+CREATE TABLE IF NOT EXISTS gas_ledger
+(
+    id            SERIAL PRIMARY KEY,
+    tnx_hash      TEXT        NOT NULL UNIQUE,
+    operation     TEXT        NOT NULL
+        CHECK (operation IN ('create_item', 'claim_item', 'claim_ownership', 'register_user', 'set_authenticity')),
+    sponsor_kind  TEXT        NOT NULL
+        CHECK (sponsor_kind IN ('manufacturer', 'user', 'api_key', 'platform')),
+    sponsor       TEXT        NOT NULL,
+    gas_used      BIGINT      NOT NULL,
+    gas_price_wei BIGINT      NOT NULL,
+    fee_wei       BIGINT      NOT NULL,
+    -- reverted transactions still burn gas
+    succeeded     BOOLEAN     NOT NULL,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_gas_ledger_sponsor ON gas_ledger (sponsor_kind, sponsor, created_at);
+CREATE INDEX IF NOT EXISTS idx_gas_ledger_created_at ON gas_ledger (created_at);
+
+-- Monthly allowance per sponsor set by admins; sponsors without a row fall back to
+-- GAS_BUDGET_<KIND>_WEI, or are unlimited when that is unset
+CREATE TABLE IF NOT EXISTS gas_budgets
+(
+    sponsor_kind      TEXT        NOT NULL
+        CHECK (sponsor_kind IN ('manufacturer', 'user', 'api_key', 'platform')),
+    sponsor           TEXT        NOT NULL,
+    monthly_limit_wei BIGINT      NOT NULL CHECK (monthly_limit_wei >= 0),
+    updated_by        TEXT        NOT NULL,
+    updated_at        TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (sponsor_kind, sponsor)
+);
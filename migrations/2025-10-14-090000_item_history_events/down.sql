@@ -0,0 +1 @@
+DROP VIEW IF EXISTS item_history_events;
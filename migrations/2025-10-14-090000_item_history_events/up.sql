@@ -0,0 +1,62 @@
+-- Chain of custody of every item as one keyset-pageable sequence: creation, transfers,
+-- flags raised and flags cleared. event_id starts with the source's rank so that events
+-- recorded at the same instant keep a stable order (created before transferred before
+-- flagged before cleared).
+CREATE OR REPLACE VIEW item_history_events AS
+SELECT i.item_id,
+       '0:' || LPAD(i.id::TEXT, 10, '0') AS event_id,
+       'created'                          AS kind,
+       NULL::TEXT                         AS from_address,
+       -- the first transfer's sender is who the item was created for
+       COALESCE((SELECT c.old_owner
+                 FROM ownership_claims c
+                 WHERE c.item_id = i.item_id
+                 ORDER BY c.id
+                 LIMIT 1), i.owner)       AS to_address,
+       NULL::TEXT                         AS status,
+       NULL::TEXT                         AS reason,
+       i.tnx_hash,
+       i.block_number,
+       i.block_time,
+       i.created_at                       AS recorded_at
+FROM items i
+UNION ALL
+SELECT c.item_id,
+       '1:' || LPAD(c.id::TEXT, 10, '0'),
+       'transferred',
+       c.old_owner,
+       c.new_owner,
+       NULL,
+       NULL,
+       c.tnx_hash,
+       c.block_number,
+       c.block_time,
+       c.created_at
+FROM ownership_claims c
+UNION ALL
+SELECT f.item_id,
+       '2:' || LPAD(f.id::TEXT, 10, '0'),
+       'flagged',
+       f.flagged_by,
+       NULL,
+       f.status,
+       f.reason,
+       NULL,
+       NULL,
+       NULL,
+       f.created_at
+FROM item_flags f
+UNION ALL
+SELECT f.item_id,
+       '3:' || LPAD(f.id::TEXT, 10, '0'),
+       'flag_cleared',
+       f.cleared_by,
+       NULL,
+       f.status,
+       NULL,
+       NULL,
+       NULL,
+       NULL,
+       f.cleared_at
+FROM item_flags f
+WHERE f.cleared_at IS NOT NULL;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS issued_certificates_cert_hash_idx;
+
+ALTER TABLE issued_certificates DROP COLUMN cert_hash;
@@ -0,0 +1,5 @@
+-- EIP-712 digest of the certificate that was issued, the key Authenticity records issue
+-- times under. Rows recorded before this column existed stay NULL.
+ALTER TABLE issued_certificates ADD COLUMN cert_hash TEXT;
+
+CREATE UNIQUE INDEX issued_certificates_cert_hash_idx ON issued_certificates (cert_hash);
@@ -0,0 +1,24 @@
+-- Integrator keys issued by admins; only the keccak256 of a key is kept, the same hash
+-- X-API-Key is looked up (and logged) by
+CREATE TABLE IF NOT EXISTS api_keys
+(
+    key_hash   TEXT PRIMARY KEY,
+    name       TEXT        NOT NULL,
+    created_by TEXT        NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMPTZ
+);
+
+-- Estimated fee of a sponsored transaction that is sent but not yet booked in gas_ledger;
+-- counted against the sponsor's budget until the receipt replaces it
+CREATE TABLE IF NOT EXISTS gas_reservations
+(
+    id           SERIAL PRIMARY KEY,
+    sponsor_kind TEXT        NOT NULL
+        CHECK (sponsor_kind IN ('manufacturer', 'user', 'api_key', 'platform')),
+    sponsor      TEXT        NOT NULL,
+    fee_wei      BIGINT      NOT NULL CHECK (fee_wei >= 0),
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_gas_reservations_sponsor ON gas_reservations (sponsor_kind, sponsor, created_at);
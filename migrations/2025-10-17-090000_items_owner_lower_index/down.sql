@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_items_owner_created_at;
+CREATE INDEX IF NOT EXISTS idx_items_owner_created_at ON items (owner, created_at, item_id);
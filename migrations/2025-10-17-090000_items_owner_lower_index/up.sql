@@ -0,0 +1,3 @@
+-- owner filters compare lowercased addresses, so the owner pagination index has to as well
+DROP INDEX IF EXISTS idx_items_owner_created_at;
+CREATE INDEX IF NOT EXISTS idx_items_owner_created_at ON items (lower(owner), created_at, item_id);
@@ -0,0 +1,143 @@
+use crate::analytics::verification_log::VerificationResult;
+use crate::contract_models::{NewVerificationAlert, NewVerificationLog};
+use crate::schema::{items, verification_alerts};
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::{Int8, Text, Timestamptz};
+use diesel::upsert::excluded;
+use diesel::PgConnection;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Signals that a certificate has been copied. A genuine certificate is only ever in one
+// place at a time, so the same unique_id turning up in several regions or with many
+// different clients within the hour, or being scanned by a crowd after it was claimed,
+// points at printed copies of it.
+
+// scans of one certificate inside this window are compared with each other
+const LOCATION_WINDOW_MINUTES: i64 = 60;
+const MAX_REGIONS_PER_WINDOW: i64 = 2;
+const MAX_CLIENTS_PER_WINDOW: i64 = 10;
+// distinct clients that may check a claimed item (owner, buyer, repair shop...)
+const MAX_CLIENTS_AFTER_CLAIM: i64 = 3;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    ManyLocations,
+    ClaimedItemRescanned,
+    ForgedSignature,
+}
+
+impl AlertKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertKind::ManyLocations => "many_locations",
+            AlertKind::ClaimedItemRescanned => "claimed_item_rescanned",
+            AlertKind::ForgedSignature => "forged_signature",
+        }
+    }
+}
+
+#[derive(QueryableByName)]
+struct ScanSpread {
+    #[diesel(sql_type = Int8)]
+    regions: i64,
+    #[diesel(sql_type = Int8)]
+    clients: i64,
+}
+
+// Distinct regions and clients among the certificate's authentic scans since `since`
+fn scan_spread(conn: &mut PgConnection, unique_id: &str, since: DateTime<Utc>) -> Result<ScanSpread> {
+    diesel::sql_query(
+        "SELECT COUNT(DISTINCT region) AS regions, \
+                COUNT(DISTINCT COALESCE(api_key_hash, client_ip)) AS clients \
+         FROM verification_logs \
+         WHERE unique_id = $1 AND result = $2 AND created_at >= $3",
+    )
+    .bind::<Text, _>(unique_id)
+    .bind::<Text, _>(VerificationResult::Authentic.as_str())
+    .bind::<Timestamptz, _>(since)
+    .get_result::<ScanSpread>(conn)
+    .map_err(|e| eyre::eyre!("Failed to measure scan spread: {}", e))
+}
+
+// Opens the alert, or bumps the open one of the same kind for the certificate
+fn raise(conn: &mut PgConnection, log: &NewVerificationLog, kind: AlertKind, details: String) -> Result<()> {
+    diesel::insert_into(verification_alerts::table)
+        .values(NewVerificationAlert {
+            unique_id: log.unique_id.clone(),
+            manufacturer_address: log.claimed_manufacturer.clone(),
+            kind: kind.as_str().to_string(),
+            details,
+        })
+        // uq_verification_alerts_open
+        .on_conflict((verification_alerts::unique_id, verification_alerts::kind))
+        .filter_target(verification_alerts::resolved_at.is_null())
+        .do_update()
+        .set((
+            verification_alerts::scan_count.eq(verification_alerts::scan_count + 1),
+            verification_alerts::last_seen.eq(Utc::now()),
+            verification_alerts::details.eq(excluded(verification_alerts::details)),
+        ))
+        .execute(conn)
+        .map_err(|e| eyre::eyre!("Failed to raise {} alert: {}", kind.as_str(), e))?;
+
+    eprintln!("Verification alert {} for {}", kind.as_str(), log.unique_id);
+    Ok(())
+}
+
+// Runs the heuristics against a scan that has just been recorded
+pub fn detect_clones(conn: &mut PgConnection, log: &NewVerificationLog) -> Result<Vec<AlertKind>> {
+    let mut raised = Vec::new();
+
+    if log.result == VerificationResult::Counterfeit.as_str() {
+        let details = format!(
+            "Certificate names {} but was signed by {}",
+            log.claimed_manufacturer,
+            log.signer.as_deref().unwrap_or("an unknown key")
+        );
+        raise(conn, log, AlertKind::ForgedSignature, details)?;
+        raised.push(AlertKind::ForgedSignature);
+        return Ok(raised);
+    }
+    if log.result != VerificationResult::Authentic.as_str() {
+        return Ok(raised);
+    }
+
+    let spread = scan_spread(
+        conn,
+        &log.unique_id,
+        Utc::now() - Duration::minutes(LOCATION_WINDOW_MINUTES),
+    )?;
+    if spread.regions > MAX_REGIONS_PER_WINDOW || spread.clients > MAX_CLIENTS_PER_WINDOW {
+        let details = format!(
+            "Scanned from {} regions by {} clients within {} minutes",
+            spread.regions, spread.clients, LOCATION_WINDOW_MINUTES
+        );
+        raise(conn, log, AlertKind::ManyLocations, details)?;
+        raised.push(AlertKind::ManyLocations);
+    }
+
+    let claimed_at = items::table
+        .filter(items::item_id.eq(&log.unique_id))
+        .select(items::created_at)
+        .first::<DateTime<Utc>>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query item: {}", e))?;
+    if let Some(claimed_at) = claimed_at {
+        let spread = scan_spread(conn, &log.unique_id, claimed_at)?;
+        if spread.clients > MAX_CLIENTS_AFTER_CLAIM {
+            let details = format!(
+                "Scanned by {} different clients since it was claimed on {}",
+                spread.clients,
+                claimed_at.format("%Y-%m-%d")
+            );
+            raise(conn, log, AlertKind::ClaimedItemRescanned, details)?;
+            raised.push(AlertKind::ClaimedItemRescanned);
+        }
+    }
+
+    Ok(raised)
+}
@@ -0,0 +1,36 @@
+use crate::authenticity::certificate_revocation::certificate_hash;
+use crate::contract_models::NewIssuedCertificate;
+use crate::models::certificate_model::Certificate;
+use crate::schema::issued_certificates;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use eyre::Result;
+
+// Remembers certificates once their manufacturer signature has been verified (bulk upload,
+// item creation or a claim) so analytics can count issued versus claimed units.
+// Re-issuing the same unique_id keeps the first record. Signing keys are checked against the
+// issue time Authenticity records on chain, not this table (see chain_issued_at).
+pub fn record_issued_certificates(conn: &mut PgConnection, certificates: &[Certificate]) -> Result<usize> {
+    let rows = certificates
+        .iter()
+        .map(|certificate| {
+            Ok(NewIssuedCertificate {
+                unique_id: certificate.unique_id.clone(),
+                manufacturer_address: format!("{:?}", certificate.owner),
+                cert_hash: certificate_hash(certificate)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // chunked to stay well below Postgres' bind parameter limit on large batches
+    let mut recorded = 0;
+    for chunk in rows.chunks(1000) {
+        recorded += diesel::insert_into(issued_certificates::table)
+            .values(chunk)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .map_err(|e| eyre::eyre!("Failed to record issued certificates: {}", e))?;
+    }
+
+    Ok(recorded)
+}
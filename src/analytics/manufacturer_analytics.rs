@@ -0,0 +1,318 @@
+use crate::config::app_state::AppState;
+use crate::schema::manufacturers;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::{Int8, Text, Timestamptz};
+use diesel::PgConnection;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+// Dashboard numbers for one manufacturer. Issued certificates are keyed by the
+// manufacturer's address, items and their transfers by its name (that's what the
+// contract stores on the item), scans by the address the certificate names.
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Bucket {
+    Day,
+    Week,
+    #[default]
+    Month,
+}
+
+impl Bucket {
+    // date_trunc field name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Bucket::Day => "day",
+            Bucket::Week => "week",
+            Bucket::Month => "month",
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct AnalyticsQuery {
+    #[schema(value_type = Option<String>, example = "2025-01-01T00:00:00Z")]
+    pub from: Option<DateTime<Utc>>,
+    #[schema(value_type = Option<String>, example = "2026-01-01T00:00:00Z")]
+    pub to: Option<DateTime<Utc>>,
+    pub bucket: Option<Bucket>,
+}
+
+#[derive(QueryableByName, Serialize, ToSchema)]
+pub struct SeriesPoint {
+    #[diesel(sql_type = Timestamptz)]
+    #[schema(value_type = String, example = "2025-09-01T00:00:00Z")]
+    bucket_start: DateTime<Utc>,
+    #[diesel(sql_type = Int8)]
+    #[schema(example = 120)]
+    count: i64,
+}
+
+#[derive(QueryableByName, Serialize, ToSchema)]
+pub struct RegionScans {
+    // "unknown" when the scan carried no region
+    #[diesel(sql_type = Text)]
+    #[schema(example = "NG")]
+    region: String,
+    #[diesel(sql_type = Int8)]
+    #[schema(example = 310)]
+    scans: i64,
+    #[diesel(sql_type = Int8)]
+    #[schema(example = 4)]
+    counterfeit_attempts: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AnalyticsTotals {
+    // certificates issued in the window
+    #[schema(example = 1000)]
+    issued: i64,
+    // of those, how many have been claimed so far
+    #[schema(example = 640)]
+    claimed: i64,
+    #[schema(example = 360)]
+    unclaimed: i64,
+    #[schema(example = 85)]
+    transfers: i64,
+    #[schema(example = 2400)]
+    scans: i64,
+    #[schema(example = 12)]
+    counterfeit_attempts: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ManufacturerAnalytics {
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    manufacturer_address: String,
+    #[schema(example = "SAMSUNG")]
+    manufacturer_name: String,
+    bucket: Bucket,
+    #[schema(value_type = String, example = "2025-01-01T00:00:00Z")]
+    from: DateTime<Utc>,
+    #[schema(value_type = String, example = "2026-01-01T00:00:00Z")]
+    to: DateTime<Utc>,
+    totals: AnalyticsTotals,
+    // one point per non-empty bucket
+    issued: Vec<SeriesPoint>,
+    claims: Vec<SeriesPoint>,
+    transfers: Vec<SeriesPoint>,
+    scans: Vec<SeriesPoint>,
+    counterfeit_attempts: Vec<SeriesPoint>,
+    scans_by_region: Vec<RegionScans>,
+}
+
+// Range and granularity every series is computed over
+struct Window {
+    bucket: Bucket,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+}
+
+#[derive(QueryableByName)]
+struct Count {
+    #[diesel(sql_type = Int8)]
+    count: i64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/analytics/manufacturers/{manufacturer_address}",
+    params(
+        ("manufacturer_address" = String, Path, description = "Address of the manufacturer", example = "0x1234567890abcdef1234567890abcdef12345678"),
+        ("from" = Option<String>, Query, description = "Start of the window, inclusive (RFC 3339, default one year before `to`)", example = "2025-01-01T00:00:00Z"),
+        ("to" = Option<String>, Query, description = "End of the window, exclusive (RFC 3339, default now)", example = "2026-01-01T00:00:00Z"),
+        ("bucket" = Option<Bucket>, Query, description = "Series granularity: `day`, `week` or `month` (default)", example = "month")
+    ),
+    responses(
+        (status = 200, description = "Totals and time-bucketed series for the window", body = ManufacturerAnalytics),
+        (status = 400, description = "Invalid range"),
+        (status = 404, description = "Manufacturer not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Analytics"
+)]
+pub async fn get_manufacturer_analytics(
+    State(state): State<Arc<AppState>>,
+    Path(manufacturer_address): Path<String>,
+    Query(query): Query<AnalyticsQuery>,
+) -> impl IntoResponse {
+    match get_manufacturer_analytics_internal(&state, &manufacturer_address, &query).await {
+        Ok(analytics) => (StatusCode::OK, AxumJson(analytics)).into_response(),
+        Err(e) => {
+            eprintln!(
+                "Error computing analytics for manufacturer {}: {:?}",
+                manufacturer_address, e
+            );
+            let (status, message) = match e.to_string().as_str() {
+                s if s.contains("Invalid range") => (StatusCode::BAD_REQUEST, e.to_string()),
+                "Manufacturer not found" => (StatusCode::NOT_FOUND, e.to_string()),
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Internal server error: {}", e),
+                ),
+            };
+            (status, AxumJson(json!({"error": message}))).into_response()
+        }
+    }
+}
+
+// Counts per bucket of `time` over `source` rows matching `filter`, where the filter
+// compares against $2. Only ever called with the literals below.
+fn series(
+    conn: &mut PgConnection,
+    source: &str,
+    time: &str,
+    filter: &str,
+    key: &str,
+    window: &Window,
+) -> eyre::Result<Vec<SeriesPoint>> {
+    let sql = format!(
+        "SELECT date_trunc($1, {time}) AS bucket_start, COUNT(*) AS count \
+         FROM {source} \
+         WHERE {filter} AND {time} >= $3 AND {time} < $4 \
+         GROUP BY 1 ORDER BY 1"
+    );
+
+    diesel::sql_query(sql)
+        .bind::<Text, _>(window.bucket.as_str())
+        .bind::<Text, _>(key)
+        .bind::<Timestamptz, _>(window.from)
+        .bind::<Timestamptz, _>(window.to)
+        .load::<SeriesPoint>(conn)
+        .map_err(|e| eyre::eyre!("Failed to aggregate {}: {}", source, e))
+}
+
+fn total(points: &[SeriesPoint]) -> i64 {
+    points.iter().map(|point| point.count).sum()
+}
+
+async fn get_manufacturer_analytics_internal(
+    state: &Arc<AppState>,
+    manufacturer_address: &str,
+    query: &AnalyticsQuery,
+) -> eyre::Result<ManufacturerAnalytics> {
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query.from.unwrap_or(to - Duration::days(365));
+    if from >= to {
+        return Err(eyre::eyre!("Invalid range: from must be before to"));
+    }
+    let bucket = query.bucket.unwrap_or_default();
+    let window = Window { bucket, from, to };
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let (manufacturer_address, manufacturer_name) = manufacturers::table
+        .filter(manufacturers::manufacturer_address.ilike(manufacturer_address))
+        .select((manufacturers::manufacturer_address, manufacturers::manufacturer_name))
+        .first::<(String, String)>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query manufacturer: {}", e))?
+        .ok_or_else(|| eyre::eyre!("Manufacturer not found"))?;
+
+    let by_address = "LOWER(claimed_manufacturer) = LOWER($2)";
+    let issued = series(
+        conn,
+        "issued_certificates",
+        "created_at",
+        "LOWER(manufacturer_address) = LOWER($2)",
+        &manufacturer_address,
+        &window,
+    )?;
+    let claims = series(
+        conn,
+        "items",
+        "created_at",
+        "LOWER(manufacturer) = LOWER($2)",
+        &manufacturer_name,
+        &window,
+    )?;
+    let transfers = series(
+        conn,
+        "ownership_claims c JOIN items i ON i.item_id = c.item_id",
+        "c.created_at",
+        "LOWER(i.manufacturer) = LOWER($2)",
+        &manufacturer_name,
+        &window,
+    )?;
+    let scans = series(
+        conn,
+        "verification_logs",
+        "created_at",
+        by_address,
+        &manufacturer_address,
+        &window,
+    )?;
+    let counterfeit_attempts = series(
+        conn,
+        "verification_logs",
+        "created_at",
+        &format!("{} AND result = 'counterfeit'", by_address),
+        &manufacturer_address,
+        &window,
+    )?;
+
+    let claimed = diesel::sql_query(
+        "SELECT COUNT(*) AS count FROM issued_certificates c \
+         WHERE LOWER(c.manufacturer_address) = LOWER($1) \
+           AND c.created_at >= $2 AND c.created_at < $3 \
+           AND EXISTS (SELECT 1 FROM items i WHERE i.item_id = c.unique_id)",
+    )
+    .bind::<Text, _>(&manufacturer_address)
+    .bind::<Timestamptz, _>(from)
+    .bind::<Timestamptz, _>(to)
+    .get_result::<Count>(conn)
+    .map_err(|e| eyre::eyre!("Failed to count claimed certificates: {}", e))?
+    .count;
+
+    let scans_by_region = diesel::sql_query(
+        "SELECT COALESCE(region, 'unknown') AS region, COUNT(*) AS scans, \
+                COUNT(*) FILTER (WHERE result = 'counterfeit') AS counterfeit_attempts \
+         FROM verification_logs \
+         WHERE LOWER(claimed_manufacturer) = LOWER($1) \
+           AND created_at >= $2 AND created_at < $3 \
+         GROUP BY 1 ORDER BY 2 DESC, 1",
+    )
+    .bind::<Text, _>(&manufacturer_address)
+    .bind::<Timestamptz, _>(from)
+    .bind::<Timestamptz, _>(to)
+    .load::<RegionScans>(conn)
+    .map_err(|e| eyre::eyre!("Failed to aggregate scans by region: {}", e))?;
+
+    let issued_total = total(&issued);
+    let totals = AnalyticsTotals {
+        issued: issued_total,
+        claimed,
+        unclaimed: issued_total - claimed,
+        transfers: total(&transfers),
+        scans: total(&scans),
+        counterfeit_attempts: total(&counterfeit_attempts),
+    };
+
+    Ok(ManufacturerAnalytics {
+        manufacturer_address,
+        manufacturer_name,
+        bucket,
+        from,
+        to,
+        totals,
+        issued,
+        claims,
+        transfers,
+        scans,
+        counterfeit_attempts,
+        scans_by_region,
+    })
+}
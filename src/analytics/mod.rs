@@ -0,0 +1,5 @@
+pub mod issued_certificate;
+pub mod verification_log;
+pub mod clone_detection;
+pub mod manufacturer_analytics;
+pub mod verification_audit;
@@ -0,0 +1,335 @@
+use crate::analytics::verification_log::VerificationResult;
+use crate::config::app_state::AppState;
+use crate::contract_models::{VerificationAlert, VerificationLog};
+use crate::models::pagination::{keyset_page, Page, SortBy, SortOrder};
+use crate::models::wallet_auth::WalletAuth;
+use crate::schema::{verification_alerts, verification_logs};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use chrono::Utc;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use ethers::core::utils::to_checksum;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct VerificationLogsQuery {
+    // address the scanned certificates name
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub(crate) manufacturer: String,
+    #[schema(example = "item123")]
+    pub(crate) unique_id: Option<String>,
+    pub(crate) result: Option<VerificationResult>,
+    pub(crate) cursor: Option<String>,
+    #[schema(example = 20)]
+    pub(crate) limit: Option<i64>,
+    pub(crate) sort: Option<SortBy>,
+    pub(crate) order: Option<SortOrder>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct VerificationLogsResponse {
+    verifications: Vec<VerificationLog>,
+    #[schema(example = 42)]
+    total: i64,
+    #[schema(nullable = true)]
+    next_cursor: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct AlertsQuery {
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub(crate) manufacturer: String,
+    // also list alerts that were already resolved
+    #[serde(default)]
+    pub(crate) include_resolved: bool,
+    pub(crate) cursor: Option<String>,
+    #[schema(example = 20)]
+    pub(crate) limit: Option<i64>,
+    pub(crate) sort: Option<SortBy>,
+    pub(crate) order: Option<SortOrder>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AlertsResponse {
+    alerts: Vec<VerificationAlert>,
+    #[schema(example = 3)]
+    total: i64,
+    #[schema(nullable = true)]
+    next_cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ResolveAlertRequest {
+    // signed by the manufacturer the alert belongs to for `resolve-alert <alert_id>`
+    pub auth: WalletAuth,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/verifications",
+    params(
+        ("manufacturer" = String, Query, description = "Address the scanned certificates name", example = "0x1234567890abcdef1234567890abcdef12345678"),
+        ("unique_id" = Option<String>, Query, description = "Only scans of this certificate", example = "item123"),
+        ("result" = Option<VerificationResult>, Query, description = "Only scans with this outcome", example = "counterfeit"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<i64>, Query, description = "Scans per page (default 20, max 100)", example = 20),
+        ("sort" = Option<SortBy>, Query, description = "Sort field: `created_at` (default) or `name` (unique_id)", example = "created_at"),
+        ("order" = Option<SortOrder>, Query, description = "Sort order: `desc` (default) or `asc`", example = "desc")
+    ),
+    responses(
+        (status = 200, description = "Verification audit log", body = VerificationLogsResponse),
+        (status = 400, description = "Invalid pagination"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Analytics"
+)]
+pub async fn list_verifications(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<VerificationLogsQuery>,
+) -> impl IntoResponse {
+    match list_verifications_internal(&state, &query).await {
+        Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
+        Err(e) => {
+            eprintln!(
+                "Error listing verifications for manufacturer {}: {:?}",
+                query.manufacturer, e
+            );
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/alerts",
+    params(
+        ("manufacturer" = String, Query, description = "Address of the manufacturer", example = "0x1234567890abcdef1234567890abcdef12345678"),
+        ("include_resolved" = Option<bool>, Query, description = "Also return resolved alerts", example = false),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<i64>, Query, description = "Alerts per page (default 20, max 100)", example = 20),
+        ("sort" = Option<SortBy>, Query, description = "Sort field: `created_at` (first seen, default) or `name` (unique_id)", example = "created_at"),
+        ("order" = Option<SortOrder>, Query, description = "Sort order: `desc` (default) or `asc`", example = "desc")
+    ),
+    responses(
+        (status = 200, description = "Suspected cloned or forged certificates", body = AlertsResponse),
+        (status = 400, description = "Invalid pagination"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Analytics"
+)]
+pub async fn list_alerts(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AlertsQuery>,
+) -> impl IntoResponse {
+    match list_alerts_internal(&state, &query).await {
+        Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
+        Err(e) => {
+            eprintln!(
+                "Error listing alerts for manufacturer {}: {:?}",
+                query.manufacturer, e
+            );
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/alerts/{alert_id}/resolve",
+    params(
+        ("alert_id" = i32, Path, description = "ID of the alert", example = 1)
+    ),
+    request_body = ResolveAlertRequest,
+    responses(
+        (status = 200, description = "Alert resolved; further hits open a new one", body = VerificationAlert),
+        (status = 400, description = "Invalid caller address"),
+        (status = 401, description = "Wallet signature missing, expired or not from the caller"),
+        (status = 403, description = "Caller is not the manufacturer of the alert"),
+        (status = 404, description = "Alert not found"),
+        (status = 409, description = "Alert is already resolved"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Analytics"
+)]
+pub async fn resolve_alert(
+    State(state): State<Arc<AppState>>,
+    Path(alert_id): Path<i32>,
+    AxumJson(request): AxumJson<ResolveAlertRequest>,
+) -> impl IntoResponse {
+    match resolve_alert_internal(&state, alert_id, &request).await {
+        Ok(alert) => (StatusCode::OK, AxumJson(alert)).into_response(),
+        Err(e) => {
+            eprintln!("Error resolving alert {}: {:?}", alert_id, e);
+            error_response(e)
+        }
+    }
+}
+
+fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.contains("Invalid pagination") => (StatusCode::BAD_REQUEST, e.to_string()),
+        "Invalid caller address" => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Invalid wallet signature") => (StatusCode::UNAUTHORIZED, e.to_string()),
+        "Caller is not the manufacturer of the alert" => (StatusCode::FORBIDDEN, e.to_string()),
+        "Alert not found" => (StatusCode::NOT_FOUND, e.to_string()),
+        "Alert is already resolved" => (StatusCode::CONFLICT, e.to_string()),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, AxumJson(json!({"error": message}))).into_response()
+}
+
+fn filtered_verifications(query: &VerificationLogsQuery) -> verification_logs::BoxedQuery<'_, Pg> {
+    let mut logs = verification_logs::table
+        .filter(verification_logs::claimed_manufacturer.ilike(&query.manufacturer))
+        .into_boxed();
+
+    if let Some(unique_id) = &query.unique_id {
+        logs = logs.filter(verification_logs::unique_id.eq(unique_id));
+    }
+    if let Some(result) = query.result {
+        logs = logs.filter(verification_logs::result.eq(result.as_str()));
+    }
+
+    logs
+}
+
+async fn list_verifications_internal(
+    state: &Arc<AppState>,
+    query: &VerificationLogsQuery,
+) -> eyre::Result<VerificationLogsResponse> {
+    let page = Page::new(query.cursor.as_deref(), query.limit, query.sort, query.order)?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let total = filtered_verifications(query)
+        .count()
+        .get_result::<i64>(conn)
+        .map_err(|e| eyre::eyre!("Failed to count verifications: {}", e))?;
+
+    let rows = keyset_page!(
+        filtered_verifications(query).select(VerificationLog::as_select()),
+        &page,
+        verification_logs::created_at,
+        verification_logs::unique_id,
+        verification_logs::id,
+        i32
+    )
+    .load::<VerificationLog>(conn)
+    .map_err(|e| eyre::eyre!("Failed to fetch verifications: {}", e))?;
+
+    let (verifications, next_cursor) = page.finish(rows, |log| {
+        (log.created_at, log.unique_id.clone(), log.id.to_string())
+    });
+
+    Ok(VerificationLogsResponse {
+        verifications,
+        total,
+        next_cursor,
+    })
+}
+
+fn filtered_alerts(query: &AlertsQuery) -> verification_alerts::BoxedQuery<'_, Pg> {
+    let mut alerts = verification_alerts::table
+        .filter(verification_alerts::manufacturer_address.ilike(&query.manufacturer))
+        .into_boxed();
+
+    if !query.include_resolved {
+        alerts = alerts.filter(verification_alerts::resolved_at.is_null());
+    }
+
+    alerts
+}
+
+async fn list_alerts_internal(state: &Arc<AppState>, query: &AlertsQuery) -> eyre::Result<AlertsResponse> {
+    let page = Page::new(query.cursor.as_deref(), query.limit, query.sort, query.order)?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let total = filtered_alerts(query)
+        .count()
+        .get_result::<i64>(conn)
+        .map_err(|e| eyre::eyre!("Failed to count alerts: {}", e))?;
+
+    let rows = keyset_page!(
+        filtered_alerts(query).select(VerificationAlert::as_select()),
+        &page,
+        verification_alerts::first_seen,
+        verification_alerts::unique_id,
+        verification_alerts::id,
+        i32
+    )
+    .load::<VerificationAlert>(conn)
+    .map_err(|e| eyre::eyre!("Failed to fetch alerts: {}", e))?;
+
+    let (alerts, next_cursor) = page.finish(rows, |alert| {
+        (alert.first_seen, alert.unique_id.clone(), alert.id.to_string())
+    });
+
+    Ok(AlertsResponse {
+        alerts,
+        total,
+        next_cursor,
+    })
+}
+
+async fn resolve_alert_internal(
+    state: &Arc<AppState>,
+    alert_id: i32,
+    request: &ResolveAlertRequest,
+) -> eyre::Result<VerificationAlert> {
+    let caller = request
+        .auth
+        .verify("resolve-alert", &alert_id.to_string(), request)?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let alert = verification_alerts::table
+        .filter(verification_alerts::id.eq(alert_id))
+        .select(VerificationAlert::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query alert: {}", e))?
+        .ok_or_else(|| eyre::eyre!("Alert not found"))?;
+
+    if !alert
+        .manufacturer_address
+        .eq_ignore_ascii_case(&format!("{:?}", caller))
+    {
+        return Err(eyre::eyre!("Caller is not the manufacturer of the alert"));
+    }
+
+    diesel::update(
+        verification_alerts::table
+            .filter(verification_alerts::id.eq(alert_id))
+            .filter(verification_alerts::resolved_at.is_null()),
+    )
+    .set((
+        verification_alerts::resolved_by.eq(Some(to_checksum(&caller, None))),
+        verification_alerts::resolved_at.eq(Some(Utc::now())),
+    ))
+    .returning(VerificationAlert::as_returning())
+    .get_result(conn)
+    .optional()
+    .map_err(|e| eyre::eyre!("Failed to resolve alert: {}", e))?
+    .ok_or_else(|| eyre::eyre!("Alert is already resolved"))
+}
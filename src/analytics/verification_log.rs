@@ -0,0 +1,144 @@
+use crate::contract_models::NewVerificationLog;
+use crate::schema::verification_logs;
+use axum::http::HeaderMap;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use ethers::utils::keccak256;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VerificationResult {
+    // signed by the registered manufacturer the certificate names
+    Authentic,
+    // well-formed, but signed by someone else or by an unregistered address
+    Counterfeit,
+    // genuine, but the manufacturer has revoked this certificate
+    Revoked,
+    // could not be checked at all (bad signature encoding, unparsable certificate)
+    Invalid,
+    // the check itself failed on our side (chain or database unavailable)
+    Error,
+}
+
+impl VerificationResult {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VerificationResult::Authentic => "authentic",
+            VerificationResult::Counterfeit => "counterfeit",
+            VerificationResult::Revoked => "revoked",
+            VerificationResult::Invalid => "invalid",
+            VerificationResult::Error => "error",
+        }
+    }
+}
+
+// What is known about one /verify_authenticity call; filled in as the check progresses
+// and written once it is over, whichever way it ended
+pub struct VerificationAttempt {
+    pub unique_id: String,
+    pub claimed_manufacturer: String,
+    pub cert_hash: Option<String>,
+    pub signer: Option<String>,
+    pub result: Option<VerificationResult>,
+    pub region: Option<String>,
+    pub client_ip: Option<String>,
+    pub api_key_hash: Option<String>,
+}
+
+impl VerificationAttempt {
+    pub fn new(
+        unique_id: &str,
+        claimed_manufacturer: &str,
+        headers: &HeaderMap,
+        remote: SocketAddr,
+        trusted_proxies: &[IpAddr],
+    ) -> VerificationAttempt {
+        VerificationAttempt {
+            unique_id: unique_id.to_string(),
+            claimed_manufacturer: claimed_manufacturer.to_string(),
+            cert_hash: None,
+            signer: None,
+            result: None,
+            region: client_region(headers),
+            client_ip: Some(client_ip(headers, remote, trusted_proxies)),
+            api_key_hash: api_key_hash(headers),
+        }
+    }
+
+    pub fn into_log(self, result: VerificationResult) -> NewVerificationLog {
+        NewVerificationLog {
+            unique_id: self.unique_id,
+            cert_hash: self.cert_hash,
+            claimed_manufacturer: self.claimed_manufacturer,
+            signer: self.signer,
+            result: result.as_str().to_string(),
+            region: self.region,
+            client_ip: self.client_ip,
+            api_key_hash: self.api_key_hash,
+        }
+    }
+}
+
+// Country of the scanning client: the CDN's geo header when deployed behind one,
+// otherwise whatever the scanning app reports
+pub fn client_region(headers: &HeaderMap) -> Option<String> {
+    ["cf-ipcountry", "x-client-region"]
+        .iter()
+        .filter_map(|name| headers.get(*name))
+        .filter_map(|value| value.to_str().ok())
+        .map(|value| value.trim().to_uppercase())
+        .find(|value| !value.is_empty() && value != "XX")
+}
+
+// X-Forwarded-For is only believed when the peer is one of our proxies; hops are then
+// walked from the right, since everything left of the first untrusted one is client-supplied
+fn client_ip(headers: &HeaderMap, remote: SocketAddr, trusted_proxies: &[IpAddr]) -> String {
+    let mut client = remote.ip();
+    if trusted_proxies.contains(&client) {
+        let forwarded = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect::<Vec<_>>();
+        for hop in forwarded.into_iter().rev() {
+            let Ok(hop) = hop.parse::<IpAddr>() else { break };
+            client = hop;
+            if !trusted_proxies.contains(&hop) {
+                break;
+            }
+        }
+    }
+    client.to_string()
+}
+
+// Integrators identify themselves with X-API-Key; only its hash is kept
+pub(crate) fn api_key_hash(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-api-key")
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|key| format!("0x{}", hex::encode(keccak256(key.as_bytes()))))
+}
+
+// Losing a log row must never fail the verification itself, so errors are only reported
+pub fn record_verification(conn: &mut PgConnection, log: &NewVerificationLog) -> bool {
+    match diesel::insert_into(verification_logs::table)
+        .values(log)
+        .execute(conn)
+    {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!(
+                "Failed to record verification of {} ({}): {:?}",
+                log.unique_id, log.result, e
+            );
+            false
+        }
+    }
+}
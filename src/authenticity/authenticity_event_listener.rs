@@ -2,13 +2,17 @@ use crate::authenticity::authenticity_abi::{
     Authenticity,
     AuthenticityEvents,
     AuthenticityCreatedFilter,
-    ManufacturerRegisteredFilter
+    ManufacturerRegisteredFilter,
+    SigningKeyAuthorizedFilter,
+    SigningKeyRevokedFilter,
+    SigningKeyRotatedFilter,
 };
 // use crate::authenticity::authenticity_abi::{, , /*ItemCreatedFilter*/};
 use crate::config::app_state::AppState;
-use crate::contract_models::{NewContract, NewManufacturer};
-use crate::schema::{contracts, manufacturers};
-use chrono::Utc;
+use crate::events::block_times::{BlockTimes, LogPosition};
+use crate::contract_models::{NewContract, NewManufacturer, NewManufacturerKey};
+use crate::schema::{contracts, manufacturers, signing_keys};
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use diesel::{PgConnection, RunQueryDsl};
 use ecdsa::SigningKey;
@@ -21,6 +25,7 @@ use std::sync::Arc;
 pub async fn listen_for_authenticity_events(state: &Arc<AppState>) -> Result<()> {
     let contract = state.authenticity_contract.clone();
     let client = contract.client();
+    let mut block_times = BlockTimes::default();
 
 
     // Fetch historical events from the last 1,000 blocks in chunks
@@ -42,54 +47,30 @@ pub async fn listen_for_authenticity_events(state: &Arc<AppState>) -> Result<()>
             to_block - current_block + 1
         );
 
-        // Event filters for the chunk
-        let manufacturer_registered_filter = contract
-            .event::<ManufacturerRegisteredFilter>()
+        // All event types in one query, so they can be applied in chain order: a key
+        // authorized, rotated and revoked within one chunk must end up revoked
+        let mut logs = contract
+            .events()
             .from_block(current_block)
-            .to_block(to_block);
-
-        let authenticity_created_filter = contract
-            .event::<AuthenticityCreatedFilter>()
-            .from_block(current_block)
-            .to_block(to_block);
-
-        // Fetch historical events with metadata
-        let manufacturer_registered_logs = manufacturer_registered_filter
-            .query_with_meta()
-            .await
-            .map_err(|e| {
-                eprintln!(
-                    "Failed to query ManufacturerRegistered events for blocks {} to {}: {:?}",
-                    current_block, to_block, e
-                );
-                eyre::eyre!("Failed to query ManufacturerRegistered events: {}", e)
-            })?;
-
-        let authenticity_created_logs = authenticity_created_filter
+            .to_block(to_block)
             .query_with_meta()
             .await
             .map_err(|e| {
                 eprintln!(
-                    "Failed to query AuthenticityCreated events for blocks {} to {}: {:?}",
+                    "Failed to query Authenticity events for blocks {} to {}: {:?}",
                     current_block, to_block, e
                 );
-                eyre::eyre!("Failed to query AuthenticityCreated events: {}", e)
+                eyre::eyre!("Failed to query Authenticity events: {}", e)
             })?;
+        logs.sort_by_key(|(_, meta)| (meta.block_number, meta.log_index));
 
         // Process historical events
         let conn = &mut state.db_pool.get().map_err(|e| {
             eprintln!("Failed to get DB connection: {:?}", e);
             eyre::eyre!("Failed to get DB connection: {}", e)
         })?;
-
-        for (event, meta) in manufacturer_registered_logs {
-            let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-            process_manufacturer_registered_event(&event, conn, txn_hash, &contract).await?;
-        }
-
-        for (event, meta) in authenticity_created_logs {
-            let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-            process_authenticity_created_event(&event, conn, txn_hash)?;
+        for (event, meta) in logs {
+            process_event(&contract, conn, &mut block_times, event, &meta).await?;
         }
 
         current_block = to_block + 1;
@@ -107,31 +88,12 @@ pub async fn listen_for_authenticity_events(state: &Arc<AppState>) -> Result<()>
 
     loop {
         match stream.next().await {
-
-            Some(Ok((AuthenticityEvents::ManufacturerRegisteredFilter(event), meta))) => {
-                let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
+            Some(Ok((event, meta))) => {
                 let conn = &mut state.db_pool.get().map_err(|e| {
                     eprintln!("Failed to get DB connection: {:?}", e);
                     eyre::eyre!("Failed to get DB connection: {}", e)
                 })?;
-                process_manufacturer_registered_event(&event, conn, txn_hash, &contract).await?;
-            }
-
-            Some(Ok((AuthenticityEvents::AuthenticityCreatedFilter(event), meta))) => {
-                let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-                let conn = &mut state.db_pool.get().map_err(|e| {
-                    eprintln!("Failed to get DB connection: {:?}", e);
-                    eyre::eyre!("Failed to get DB connection: {}", e)
-                })?;
-                process_authenticity_created_event(&event, conn, txn_hash)?;
-            }
-
-            Some(Ok((AuthenticityEvents::Eip712DomainChangedFilter(_event), meta))) => {
-                eprintln!(
-                    "EIP712DomainChanged event received (tx: 0x{})",
-                    hex::encode(meta.transaction_hash)
-                );
-                continue;
+                process_event(&contract, conn, &mut block_times, event, &meta).await?;
             }
             Some(Err(e)) => {
                 eprintln!("Event stream error: {:?}", e);
@@ -147,11 +109,55 @@ pub async fn listen_for_authenticity_events(state: &Arc<AppState>) -> Result<()>
     }
 }
 
+// Applies one decoded log; the backfill and the stream both go through here
+async fn process_event(
+    contract: &Authenticity<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
+    conn: &mut PgConnection,
+    block_times: &mut BlockTimes,
+    event: AuthenticityEvents,
+    meta: &LogMeta,
+) -> Result<()> {
+    let client = contract.client();
+    let txn_hash = format!("0x{}", hex::encode(meta.transaction_hash));
+    let position = block_times.position(client.as_ref(), meta).await?;
+
+    match event {
+        AuthenticityEvents::ManufacturerRegisteredFilter(event) => {
+            process_manufacturer_registered_event(&event, conn, Some(txn_hash), &position, contract).await
+        }
+        AuthenticityEvents::AuthenticityCreatedFilter(event) => {
+            process_authenticity_created_event(&event, conn, Some(txn_hash), &position)
+        }
+        AuthenticityEvents::SigningKeyAuthorizedFilter(event) => {
+            process_signing_key_authorized_event(&event, conn, txn_hash, &position)
+        }
+        AuthenticityEvents::SigningKeyRotatedFilter(event) => {
+            process_signing_key_rotated_event(&event, conn, &position)
+        }
+        AuthenticityEvents::SigningKeyRevokedFilter(event) => {
+            process_signing_key_revoked_event(&event, conn, &position)
+        }
+        // the digests are not in the event; signing keys read issue times from the contract
+        AuthenticityEvents::CertificatesRecordedFilter(event) => {
+            eprintln!(
+                "{} certificates recorded by {:?} (tx: {})",
+                event.count, event.manufacturer, txn_hash
+            );
+            Ok(())
+        }
+        AuthenticityEvents::Eip712DomainChangedFilter(_event) => {
+            eprintln!("EIP712DomainChanged event received (tx: {})", txn_hash);
+            Ok(())
+        }
+    }
+}
+
 
 async fn process_manufacturer_registered_event(
     event: &ManufacturerRegisteredFilter,
     conn: &mut PgConnection,
     txn_hash: Option<String>,
+    position: &LogPosition,
     contract: &Authenticity<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
 ) -> Result<()> {
     let manufacturer_address = to_checksum(&event.manufacturer_address, None);
@@ -186,10 +192,13 @@ async fn process_manufacturer_registered_event(
             manufacturer_address,
             manufacturer_name,
             is_registered: true,
-            registered_at:  Utc::now().to_rfc3339(),
+            registered_at: position.block_time,
             tnx_hash: txn_hash.ok_or_else(|| {
                 eyre::eyre!("Transaction hash is required for manufacturer registration")
             })?,
+            block_number: position.block_number,
+            log_index: position.log_index,
+            block_time: position.block_time,
         })
         .execute(conn)
         .map_err(|e| {
@@ -201,10 +210,133 @@ async fn process_manufacturer_registered_event(
 }
 
 
+fn event_time(secs: u64) -> Result<DateTime<Utc>> {
+    DateTime::from_timestamp(secs as i64, 0)
+        .ok_or_else(|| eyre::eyre!("Invalid event timestamp {}", secs))
+}
+
+fn process_signing_key_authorized_event(
+    event: &SigningKeyAuthorizedFilter,
+    conn: &mut PgConnection,
+    txn_hash: String,
+    position: &LogPosition,
+) -> Result<()> {
+    let key_address = to_checksum(&event.key, None);
+
+    // replayed on every restart, the first authorization of a key wins like on chain
+    diesel::insert_into(signing_keys::table)
+        .values(NewManufacturerKey {
+            key_address,
+            manufacturer_address: to_checksum(&event.manufacturer, None),
+            valid_from: event_time(event.valid_from)?,
+            valid_until: match event.valid_until {
+                0 => None,
+                secs => Some(event_time(secs)?),
+            },
+            tnx_hash: txn_hash,
+            block_number: position.block_number,
+            log_index: position.log_index,
+            block_time: position.block_time,
+        })
+        .on_conflict(signing_keys::key_address)
+        .do_nothing()
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!("Failed to insert signing key: {:?}", e);
+            eyre::eyre!("Failed to insert signing key: {}", e)
+        })?;
+
+    Ok(())
+}
+
+fn process_signing_key_rotated_event(
+    event: &SigningKeyRotatedFilter,
+    conn: &mut PgConnection,
+    position: &LogPosition,
+) -> Result<()> {
+    let old_key = to_checksum(&event.old_key, None);
+    let rotated_at = event_time(event.rotated_at)?;
+
+    let key = signing_keys::table
+        .filter(signing_keys::key_address.eq(&old_key))
+        .select((signing_keys::valid_until, signing_keys::block_number, signing_keys::log_index))
+        .first::<(Option<DateTime<Utc>>, i64, i32)>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query signing key: {}", e))?;
+    let Some((valid_until, block_number, log_index)) = key else {
+        eprintln!("Skipping rotation of unknown signing key {}", old_key);
+        return Ok(());
+    };
+    if !position.is_after(Some(block_number), Some(log_index)) {
+        eprintln!("Skipping replayed rotation of signing key {}", old_key);
+        return Ok(());
+    }
+
+    // same rule as the contract: an earlier expiry is kept
+    let valid_until = valid_until.map_or(rotated_at, |until| until.min(rotated_at));
+
+    diesel::update(signing_keys::table.filter(signing_keys::key_address.eq(&old_key)))
+        .set((
+            signing_keys::valid_until.eq(Some(valid_until)),
+            signing_keys::rotated_to.eq(Some(to_checksum(&event.new_key, None))),
+            signing_keys::block_number.eq(position.block_number),
+            signing_keys::log_index.eq(position.log_index),
+            signing_keys::block_time.eq(position.block_time),
+            signing_keys::updated_at.eq(position.block_time),
+        ))
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!("Failed to rotate signing key: {:?}", e);
+            eyre::eyre!("Failed to rotate signing key: {}", e)
+        })?;
+
+    Ok(())
+}
+
+fn process_signing_key_revoked_event(
+    event: &SigningKeyRevokedFilter,
+    conn: &mut PgConnection,
+    position: &LogPosition,
+) -> Result<()> {
+    let key_address = to_checksum(&event.key, None);
+
+    let stamp = signing_keys::table
+        .filter(signing_keys::key_address.eq(&key_address))
+        .select((signing_keys::block_number, signing_keys::log_index))
+        .first::<(i64, i32)>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query signing key: {}", e))?;
+    let Some((block_number, log_index)) = stamp else {
+        eprintln!("Skipping revocation of unknown signing key {}", key_address);
+        return Ok(());
+    };
+    if !position.is_after(Some(block_number), Some(log_index)) {
+        eprintln!("Skipping replayed revocation of signing key {}", key_address);
+        return Ok(());
+    }
+
+    diesel::update(signing_keys::table.filter(signing_keys::key_address.eq(&key_address)))
+        .set((
+            signing_keys::revoked_at.eq(Some(event_time(event.revoked_at)?)),
+            signing_keys::block_number.eq(position.block_number),
+            signing_keys::log_index.eq(position.log_index),
+            signing_keys::block_time.eq(position.block_time),
+            signing_keys::updated_at.eq(position.block_time),
+        ))
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!("Failed to revoke signing key: {:?}", e);
+            eyre::eyre!("Failed to revoke signing key: {}", e)
+        })?;
+
+    Ok(())
+}
+
 fn process_authenticity_created_event(
     event: &AuthenticityCreatedFilter,
     conn: &mut PgConnection,
     txn_hash: Option<String>,
+    position: &LogPosition,
 ) -> Result<()> {
     let contract_address = to_checksum(&event.contract_address, None);
     let owner = to_checksum(&event.owner, None);
@@ -239,7 +371,10 @@ fn process_authenticity_created_event(
             tnx_hash: txn_hash.ok_or_else(|| {
                 eyre::eyre!("Transaction hash is required for manufacturer registration")
             })?,
-            created_at:  Utc::now().to_rfc3339(),
+            created_at: position.block_time,
+            block_number: position.block_number,
+            log_index: position.log_index,
+            block_time: position.block_time,
         })
         .returning(crate::contract_models::Contract::as_returning())
         .get_result(conn)
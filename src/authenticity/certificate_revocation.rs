@@ -0,0 +1,312 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::CertificateRevocation;
+use crate::models::certificate_model::{Certificate, SignedCertificate};
+use crate::models::signed_document::{signed_document, SignedDocument};
+use crate::models::wallet_auth::WalletAuth;
+use crate::schema::certificate_revocations;
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel::PgConnection;
+use ethers::core::utils::to_checksum;
+use ethers::signers::Signer;
+use ethers::types::transaction::eip712::Eip712;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+use validator::Validate;
+
+// A revocation list is a snapshot; offline verifiers should fetch a new one before this runs out
+const CRL_VALIDITY_HOURS: i64 = 24;
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct RevokeCertificateRequest {
+    // the certificate as printed on the unit; only its EIP-712 digest is kept
+    #[validate(nested)]
+    pub certificate: SignedCertificate,
+    #[validate(length(min = 1, max = 500))]
+    #[schema(example = "Unit stolen from the warehouse before sale")]
+    pub reason: String,
+    // signed by the manufacturer the certificate names for `revoke-certificate <unique_id>`
+    pub auth: WalletAuth,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CrlQuery {
+    // only certificates of this manufacturer
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub manufacturer: Option<String>,
+    // only revocations at or after this time, to update a list already held
+    #[schema(value_type = Option<String>, example = "2025-10-01T00:00:00Z")]
+    pub since: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CrlEntry {
+    #[schema(example = "0x5d1f6a0e2b0c4f3e9a7d8c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f")]
+    pub cert_hash: String,
+    #[schema(example = "item123")]
+    pub unique_id: String,
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub manufacturer_address: String,
+    #[schema(example = "Unit stolen from the warehouse before sale")]
+    pub reason: String,
+    // unix seconds
+    #[schema(example = 1759741200)]
+    pub revoked_at: i64,
+}
+
+// Served as a SignedDocument; a certificate is revoked when its EIP-712 digest is listed
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CrlDocument {
+    #[schema(example = 84532)]
+    pub chain_id: u64,
+    // the Authenticity contract the certificates are signed for
+    #[schema(example = "0x1234567890AbcdEF1234567890aBcdef12345678")]
+    pub verifying_contract: String,
+    #[schema(nullable = true)]
+    pub manufacturer: Option<String>,
+    // unix seconds; entries are complete from here on (0 = from the start)
+    #[schema(example = 0)]
+    pub since: i64,
+    #[schema(example = 1759741200)]
+    pub generated_at: i64,
+    #[schema(example = 1759827600)]
+    pub next_update: i64,
+    pub entries: Vec<CrlEntry>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/certificates/revoke",
+    request_body = RevokeCertificateRequest,
+    responses(
+        (status = 200, description = "Certificate revoked; every verification of it fails from now on", body = CertificateRevocation),
+        (status = 400, description = "Invalid certificate or caller address"),
+        (status = 401, description = "Invalid wallet signature"),
+        (status = 403, description = "Caller is not the manufacturer of the certificate"),
+        (status = 409, description = "Certificate is already revoked"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Certificates"
+)]
+pub async fn revoke_certificate(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<RevokeCertificateRequest>,
+) -> impl IntoResponse {
+    match revoke_certificate_internal(&state, &request).await {
+        Ok(revocation) => (StatusCode::OK, Json(revocation)).into_response(),
+        Err(e) => {
+            eprintln!(
+                "Error revoking certificate {}: {:?}",
+                request.certificate.unique_id, e
+            );
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/certificates/revocations/{cert_hash}",
+    params(
+        ("cert_hash" = String, Path, description = "EIP-712 digest of the certificate", example = "0x5d1f6a0e2b0c4f3e9a7d8c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f")
+    ),
+    responses(
+        (status = 200, description = "The certificate is revoked", body = CertificateRevocation),
+        (status = 404, description = "Certificate is not revoked"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Certificates"
+)]
+pub async fn get_certificate_revocation(
+    State(state): State<Arc<AppState>>,
+    Path(cert_hash): Path<String>,
+) -> impl IntoResponse {
+    let result = state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get DB connection: {}", e))
+        .and_then(|mut conn| revocation(&mut conn, &cert_hash));
+    match result {
+        Ok(Some(revocation)) => (StatusCode::OK, Json(revocation)).into_response(),
+        Ok(None) => error_response(eyre::eyre!("Certificate is not revoked")),
+        Err(e) => {
+            eprintln!("Error fetching revocation of {}: {:?}", cert_hash, e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/certificates/crl",
+    params(
+        ("manufacturer" = Option<String>, Query, description = "Only certificates of this manufacturer", example = "0x1234567890abcdef1234567890abcdef12345678"),
+        ("since" = Option<String>, Query, description = "Only revocations at or after this time (RFC 3339), for delta updates", example = "2025-10-01T00:00:00Z")
+    ),
+    responses(
+        (status = 200, description = "Signed certificate revocation list, as a JSON download", body = SignedDocument),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Certificates"
+)]
+pub async fn download_crl(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CrlQuery>,
+) -> impl IntoResponse {
+    match download_crl_internal(&state, &query).await {
+        Ok(crl) => (
+            StatusCode::OK,
+            [(
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"crl.json\"".to_string(),
+            )],
+            Json(crl),
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("Error building certificate revocation list: {:?}", e);
+            error_response(e)
+        }
+    }
+}
+
+fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.starts_with("Invalid wallet signature") => (StatusCode::UNAUTHORIZED, e.to_string()),
+        s if s.starts_with("Invalid") => (StatusCode::BAD_REQUEST, e.to_string()),
+        "Caller is not the manufacturer of the certificate" => (StatusCode::FORBIDDEN, e.to_string()),
+        "Certificate is not revoked" => (StatusCode::NOT_FOUND, e.to_string()),
+        "Certificate is already revoked" => (StatusCode::CONFLICT, e.to_string()),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, Json(serde_json::json!({"error": message}))).into_response()
+}
+
+// EIP-712 digest of the certificate as 0x-prefixed hex, the registry key
+pub fn certificate_hash(certificate: &Certificate) -> Result<String> {
+    let digest = certificate
+        .encode_eip712()
+        .map_err(|e| eyre::eyre!("Failed to hash certificate: {:?}", e))?;
+    Ok(format!("0x{}", hex::encode(digest)))
+}
+
+pub fn revocation(conn: &mut PgConnection, cert_hash: &str) -> Result<Option<CertificateRevocation>> {
+    certificate_revocations::table
+        .filter(certificate_revocations::cert_hash.eq(cert_hash.to_lowercase()))
+        .select(CertificateRevocation::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query certificate revocation: {}", e))
+}
+
+// For paths that must refuse a revoked certificate outright
+pub fn ensure_not_revoked(conn: &mut PgConnection, certificate: &Certificate) -> Result<()> {
+    if revocation(conn, &certificate_hash(certificate)?)?.is_some() {
+        return Err(eyre::eyre!("Certificate has been revoked"));
+    }
+    Ok(())
+}
+
+async fn revoke_certificate_internal(
+    state: &Arc<AppState>,
+    request: &RevokeCertificateRequest,
+) -> Result<CertificateRevocation> {
+    request
+        .validate()
+        .map_err(|e| eyre::eyre!("Invalid certificate: {}", e))?;
+    let certificate: Certificate = request
+        .certificate
+        .clone()
+        .try_into()
+        .map_err(|_| eyre::eyre!("Invalid certificate: owner address"))?;
+
+    let caller = request
+        .auth
+        .verify("revoke-certificate", &certificate.unique_id, request)?;
+    if caller != certificate.owner {
+        return Err(eyre::eyre!("Caller is not the manufacturer of the certificate"));
+    }
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let revocation = CertificateRevocation {
+        cert_hash: certificate_hash(&certificate)?,
+        unique_id: certificate.unique_id.clone(),
+        manufacturer_address: to_checksum(&certificate.owner, None),
+        reason: request.reason.trim().to_string(),
+        revoked_by: to_checksum(&caller, None),
+        revoked_at: Utc::now(),
+    };
+
+    let inserted = diesel::insert_into(certificate_revocations::table)
+        .values(&revocation)
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .map_err(|e| eyre::eyre!("Failed to revoke certificate: {}", e))?;
+    if inserted == 0 {
+        return Err(eyre::eyre!("Certificate is already revoked"));
+    }
+
+    Ok(revocation)
+}
+
+async fn download_crl_internal(state: &Arc<AppState>, query: &CrlQuery) -> Result<SignedDocument> {
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let mut revocations = certificate_revocations::table
+        .select(CertificateRevocation::as_select())
+        .order((certificate_revocations::revoked_at.asc(), certificate_revocations::cert_hash.asc()))
+        .into_boxed();
+    if let Some(manufacturer) = &query.manufacturer {
+        revocations = revocations.filter(certificate_revocations::manufacturer_address.ilike(manufacturer));
+    }
+    if let Some(since) = query.since {
+        revocations = revocations.filter(certificate_revocations::revoked_at.ge(since));
+    }
+    let entries = revocations
+        .load::<CertificateRevocation>(conn)
+        .map_err(|e| eyre::eyre!("Failed to fetch certificate revocations: {}", e))?
+        .into_iter()
+        .map(|revocation| CrlEntry {
+            cert_hash: revocation.cert_hash,
+            unique_id: revocation.unique_id,
+            manufacturer_address: revocation.manufacturer_address,
+            reason: revocation.reason,
+            revoked_at: revocation.revoked_at.timestamp(),
+        })
+        .collect();
+
+    let wallet = state.authenticity_contract.client().signer().clone();
+    let generated_at = Utc::now();
+
+    signed_document(
+        &wallet,
+        &CrlDocument {
+            chain_id: wallet.chain_id(),
+            verifying_contract: to_checksum(&state.authenticity_contract.address(), None),
+            manufacturer: query.manufacturer.clone(),
+            since: query.since.map(|since| since.timestamp()).unwrap_or(0),
+            generated_at: generated_at.timestamp(),
+            next_update: (generated_at + Duration::hours(CRL_VALIDITY_HOURS)).timestamp(),
+            entries,
+        },
+    )
+    .await
+}
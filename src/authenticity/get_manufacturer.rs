@@ -35,6 +35,16 @@ use utoipa::{OpenApi, ToSchema};
                 "manufacturer_name": "SAMSUNG",
                 "is_registered": true,
                 "registered_at": "2025-08-24T12:04:00Z",
+                "profile": {
+                    "legalName": "Samsung Electronics Co., Ltd.",
+                    "logoUrl": "https://cdn.example.com/samsung/logo.png",
+                    "website": "https://www.samsung.com",
+                    "contactEmail": "support@samsung.com",
+                    "contactPhone": null,
+                    "kybStatus": "verified",
+                    "kybReviewedAt": "2025-09-30T09:00:00Z",
+                    "profileUpdatedAt": "2025-09-29T16:20:00Z"
+                }
             })
         ),
         (status = 400, description = "Neither address nor username provided"),
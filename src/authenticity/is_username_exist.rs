@@ -1,13 +1,11 @@
 use crate::config::app_state::AppState;
-use crate::schema::manufacturers;
+use crate::models::username_policy::{check_name, is_policy_rejection, is_taken, NameKind};
 use axum::{
     extract::{Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
-use diesel::associations::HasTable;
-use diesel::{prelude::*, QueryDsl};
 use eyre::Result;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -21,7 +19,13 @@ pub struct IsExistsQuery {
 
 #[derive(Serialize, ToSchema)]
 pub struct IsExistsResponse {
+    // used by a manufacturer or user, ignoring case
     exists: bool,
+    // whether the name could be registered right now
+    available: bool,
+    // why it cannot, when it cannot
+    #[schema(nullable = true, example = "Invalid username: too similar to manufacturer SAMSUNG")]
+    reason: Option<String>,
 }
 
 #[utoipa::path(
@@ -31,8 +35,10 @@ pub struct IsExistsResponse {
         ("username" = Option<String>, Query, description = "Manufacturer's username", example = "john_doe")
     ),
     responses(
-        (status = 200, description = "Check if manufacturer exists", body = IsExistsResponse, example = json!({
-            "exists": true
+        (status = 200, description = "Whether the name is taken and can be registered; checked before manufacturerRegisters is sent", body = IsExistsResponse, example = json!({
+            "exists": true,
+            "available": false,
+            "reason": "Username is already taken"
         })),
         (status = 400, description = "Username not provided"),
         (status = 500, description = "Internal server error")
@@ -45,9 +51,9 @@ pub async fn manufacturer_name_exists(
 ) -> impl IntoResponse {
     eprintln!("username: {:?}", query.username);
     match check_manufacturer_exists_internal(&state, &query).await {
-        Ok(exists) => (
+        Ok(response) => (
             StatusCode::OK,
-            Json(IsExistsResponse { exists }),
+            Json(response),
         ).into_response(),
         Err(e) => {
             eprintln!("Error checking manufacturer existence: {:?}", e);
@@ -62,7 +68,7 @@ pub async fn manufacturer_name_exists(
 async fn check_manufacturer_exists_internal(
     state: &Arc<AppState>,
     query: &IsExistsQuery,
-) -> Result<bool> {
+) -> Result<IsExistsResponse> {
     eprintln!("username: {:?}", query.username);
     let username = query.username.as_ref().ok_or_else(|| {
         eprintln!("Username not provided");
@@ -74,17 +80,18 @@ async fn check_manufacturer_exists_internal(
         eyre::eyre!("Failed to get DB connection: {}", e)
     })?;
 
-    let exists: bool = manufacturers::table
-        .filter(manufacturers::manufacturer_name.eq(username))
-        .select(diesel::dsl::count_star())
-        .first::<i64>(&mut conn)
-        .map(|count| count > 0)
-        .map_err(|e| {
-            eprintln!("Failed to check manufacturer with username {}: {:?}", username, e);
-            eyre::eyre!("Failed to check manufacturer: {}", e)
-        })?;
+    let exists = is_taken(&mut conn, username, None)?;
+    let reason = match check_name(&mut conn, username, NameKind::Manufacturer, None) {
+        Ok(()) => None,
+        Err(e) if is_policy_rejection(&e) => Some(e.to_string()),
+        Err(e) => return Err(e),
+    };
 
-    Ok(exists)
+    Ok(IsExistsResponse {
+        exists,
+        available: reason.is_none(),
+        reason,
+    })
 }
 
 
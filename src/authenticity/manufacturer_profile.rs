@@ -0,0 +1,312 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::{Manufacturer, ManufacturerProfile, ManufacturerProfileChanges};
+use crate::models::wallet_auth::WalletAuth;
+use crate::schema::manufacturers;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KybStatus {
+    // nobody has looked at the profile yet
+    Unverified,
+    // under review, or reviewed before the profile last changed
+    Pending,
+    Verified,
+    Rejected,
+}
+
+impl KybStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KybStatus::Unverified => "unverified",
+            KybStatus::Pending => "pending",
+            KybStatus::Verified => "verified",
+            KybStatus::Rejected => "rejected",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateManufacturerProfileRequest {
+    #[validate(length(max = 200))]
+    #[schema(example = "Samsung Electronics Co., Ltd.")]
+    pub legal_name: Option<String>,
+    #[validate(url, length(max = 2048))]
+    #[schema(example = "https://cdn.example.com/samsung/logo.png")]
+    pub logo_url: Option<String>,
+    #[validate(url, length(max = 2048))]
+    #[schema(example = "https://www.samsung.com")]
+    pub website: Option<String>,
+    #[validate(email)]
+    #[schema(example = "support@samsung.com")]
+    pub contact_email: Option<String>,
+    #[validate(length(max = 32))]
+    #[schema(example = "+82 2 2053 3000")]
+    pub contact_phone: Option<String>,
+    // signed by the manufacturer's wallet for `update-manufacturer-profile <manufacturer_address>`
+    pub auth: WalletAuth,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct ReviewKybRequest {
+    pub status: KybStatus,
+    // kept with the review, mostly to explain rejections
+    #[validate(length(max = 1000))]
+    #[schema(example = "Business registration matches the legal name")]
+    pub note: Option<String>,
+    // signed by an admin wallet for `review-manufacturer-kyb <manufacturer_address>`
+    pub auth: WalletAuth,
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/manufacturer/{manufacturer_address}/profile",
+    params(
+        ("manufacturer_address" = String, Path, description = "Address of the manufacturer", example = "0x1234567890abcdef1234567890abcdef12345678")
+    ),
+    request_body = UpdateManufacturerProfileRequest,
+    responses(
+        (status = 200, description = "Profile replaced; a reviewed manufacturer that changes any profile field goes back to pending review", body = Manufacturer),
+        (status = 400, description = "Invalid profile or caller address"),
+        (status = 401, description = "Invalid wallet signature"),
+        (status = 403, description = "Caller is not the manufacturer"),
+        (status = 404, description = "Manufacturer not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Manufacturers"
+)]
+pub async fn update_manufacturer_profile(
+    State(state): State<Arc<AppState>>,
+    Path(manufacturer_address): Path<String>,
+    Json(request): Json<UpdateManufacturerProfileRequest>,
+) -> impl IntoResponse {
+    match update_manufacturer_profile_internal(&state, &manufacturer_address, &request).await {
+        Ok(manufacturer) => (StatusCode::OK, Json(manufacturer)).into_response(),
+        Err(e) => {
+            eprintln!(
+                "Error updating profile of manufacturer {}: {:?}",
+                manufacturer_address, e
+            );
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/admin/manufacturers/{manufacturer_address}/kyb",
+    params(
+        ("manufacturer_address" = String, Path, description = "Address of the manufacturer", example = "0x1234567890abcdef1234567890abcdef12345678")
+    ),
+    request_body = ReviewKybRequest,
+    responses(
+        (status = 200, description = "KYB status recorded", body = Manufacturer),
+        (status = 400, description = "Invalid review or caller address"),
+        (status = 401, description = "Invalid wallet signature"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Manufacturer not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Manufacturers"
+)]
+pub async fn review_manufacturer_kyb(
+    State(state): State<Arc<AppState>>,
+    Path(manufacturer_address): Path<String>,
+    Json(request): Json<ReviewKybRequest>,
+) -> impl IntoResponse {
+    match review_manufacturer_kyb_internal(&state, &manufacturer_address, &request).await {
+        Ok(manufacturer) => (StatusCode::OK, Json(manufacturer)).into_response(),
+        Err(e) => {
+            eprintln!(
+                "Error reviewing KYB of manufacturer {}: {:?}",
+                manufacturer_address, e
+            );
+            error_response(e)
+        }
+    }
+}
+
+fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.starts_with("Invalid profile") || s.starts_with("Invalid review") => {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        "Invalid caller address" => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.starts_with("Invalid wallet signature") => (StatusCode::UNAUTHORIZED, e.to_string()),
+        "Caller is not the manufacturer" | "Caller is not an admin" => {
+            (StatusCode::FORBIDDEN, e.to_string())
+        }
+        "Manufacturer not found" => (StatusCode::NOT_FOUND, e.to_string()),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, Json(serde_json::json!({"error": message}))).into_response()
+}
+
+// The fields a KYB review vouches for: legal name, logo, website, email and phone
+type ReviewedFields = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+// Stored address, reviewed fields and KYB status, whatever the case of the given address
+fn find_manufacturer(
+    conn: &mut PgConnection,
+    manufacturer_address: &str,
+) -> Result<(String, ReviewedFields, String)> {
+    manufacturers::table
+        .filter(manufacturers::manufacturer_address.ilike(manufacturer_address))
+        .select((
+            manufacturers::manufacturer_address,
+            (
+                manufacturers::legal_name,
+                manufacturers::logo_url,
+                manufacturers::website,
+                manufacturers::contact_email,
+                manufacturers::contact_phone,
+            ),
+            manufacturers::kyb_status,
+        ))
+        .first::<(String, ReviewedFields, String)>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query manufacturer: {}", e))?
+        .ok_or_else(|| eyre::eyre!("Manufacturer not found"))
+}
+
+fn load_manufacturer(conn: &mut PgConnection, manufacturer_address: &str) -> Result<Manufacturer> {
+    manufacturers::table
+        .filter(manufacturers::manufacturer_address.eq(manufacturer_address))
+        .select(Manufacturer::as_select())
+        .first(conn)
+        .map_err(|e| eyre::eyre!("Failed to fetch manufacturer: {}", e))
+}
+
+// Blank strings clear a field just like an absent one
+fn trimmed(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+async fn update_manufacturer_profile_internal(
+    state: &Arc<AppState>,
+    manufacturer_address: &str,
+    request: &UpdateManufacturerProfileRequest,
+) -> Result<Manufacturer> {
+    request
+        .validate()
+        .map_err(|e| eyre::eyre!("Invalid profile: {}", e))?;
+
+    let caller = request
+        .auth
+        .verify("update-manufacturer-profile", manufacturer_address, request)?;
+    if !format!("{:?}", caller).eq_ignore_ascii_case(manufacturer_address) {
+        return Err(eyre::eyre!("Caller is not the manufacturer"));
+    }
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let (stored_address, reviewed, kyb_status) = find_manufacturer(conn, manufacturer_address)?;
+    let changes = ManufacturerProfileChanges {
+        legal_name: trimmed(&request.legal_name),
+        logo_url: trimmed(&request.logo_url),
+        website: trimmed(&request.website),
+        contact_email: trimmed(&request.contact_email),
+        contact_phone: trimmed(&request.contact_phone),
+        profile_updated_at: Some(Utc::now()),
+    };
+
+    // a review covers the whole profile as it was seen, so any change is reviewed again
+    let changed = (
+        changes.legal_name.clone(),
+        changes.logo_url.clone(),
+        changes.website.clone(),
+        changes.contact_email.clone(),
+        changes.contact_phone.clone(),
+    ) != reviewed;
+    let was_reviewed = kyb_status == KybStatus::Verified.as_str() || kyb_status == KybStatus::Rejected.as_str();
+    let kyb_status = if was_reviewed && changed {
+        KybStatus::Pending.as_str().to_string()
+    } else {
+        kyb_status
+    };
+
+    diesel::update(manufacturers::table.filter(manufacturers::manufacturer_address.eq(&stored_address)))
+        .set((&changes, manufacturers::kyb_status.eq(kyb_status)))
+        .execute(conn)
+        .map_err(|e| eyre::eyre!("Failed to update manufacturer profile: {}", e))?;
+
+    load_manufacturer(conn, &stored_address)
+}
+
+async fn review_manufacturer_kyb_internal(
+    state: &Arc<AppState>,
+    manufacturer_address: &str,
+    request: &ReviewKybRequest,
+) -> Result<Manufacturer> {
+    request
+        .validate()
+        .map_err(|e| eyre::eyre!("Invalid review: {}", e))?;
+
+    let caller = request
+        .auth
+        .verify("review-manufacturer-kyb", manufacturer_address, request)?;
+    if !state.admin_addresses.contains(&caller) {
+        return Err(eyre::eyre!("Caller is not an admin"));
+    }
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let (stored_address, _, _) = find_manufacturer(conn, manufacturer_address)?;
+
+    diesel::update(manufacturers::table.filter(manufacturers::manufacturer_address.eq(&stored_address)))
+        .set((
+            manufacturers::kyb_status.eq(request.status.as_str()),
+            manufacturers::kyb_note.eq(trimmed(&request.note)),
+            manufacturers::kyb_reviewed_by.eq(Some(format!("{:?}", caller))),
+            manufacturers::kyb_reviewed_at.eq(Some(Utc::now())),
+        ))
+        .execute(conn)
+        .map_err(|e| eyre::eyre!("Failed to record KYB review: {}", e))?;
+
+    load_manufacturer(conn, &stored_address)
+}
+
+// Profile of the manufacturer behind a certificate, for verification results
+pub fn manufacturer_profile(
+    conn: &mut PgConnection,
+    manufacturer_address: &str,
+) -> Result<Option<ManufacturerProfile>> {
+    manufacturers::table
+        .filter(manufacturers::manufacturer_address.ilike(manufacturer_address))
+        .select(ManufacturerProfile::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to fetch manufacturer profile: {}", e))
+}
@@ -1,4 +1,7 @@
 pub mod authenticity_event_listener;
 pub mod get_manufacturer;
 pub mod is_username_exist;
-pub mod authenticity_abi;
\ No newline at end of file
+pub mod authenticity_abi;
+pub mod certificate_revocation;
+pub mod manufacturer_profile;
+pub mod signing_keys;
@@ -0,0 +1,380 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::ManufacturerKey;
+use crate::ownership::onchain_ownership_code::{prepare_transaction, PreparedTransaction};
+use crate::products::manage_product::registered_manufacturer;
+use crate::schema::signing_keys;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::PgConnection;
+use ethers::types::Address;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+// Authenticity only lets a manufacturer manage its own keys (msg.sender), so these
+// endpoints check the request against the mirrored key set and return the call for the
+// manufacturer's wallet to send. The table follows once the event is indexed.
+
+#[derive(Deserialize, ToSchema)]
+pub struct AuthorizeKeyRequest {
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub caller: String,
+    #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef12")]
+    pub key: String,
+    // defaults to now
+    #[schema(value_type = Option<String>, example = "2025-10-01T00:00:00Z")]
+    pub valid_from: Option<DateTime<Utc>>,
+    // open ended when absent
+    #[schema(value_type = Option<String>, example = "2026-10-01T00:00:00Z")]
+    pub valid_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RotateKeyRequest {
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub caller: String,
+    #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef12")]
+    pub old_key: String,
+    #[schema(example = "0x9876543210fedcba9876543210fedcba98765432")]
+    pub new_key: String,
+    #[schema(value_type = Option<String>, example = "2026-10-01T00:00:00Z")]
+    pub valid_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RevokeKeyRequest {
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub caller: String,
+    #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef12")]
+    pub key: String,
+    // when the key is believed to have leaked; certificates of this key not recorded as
+    // issued before then are rejected. Defaults to now and cannot be in the future.
+    #[schema(value_type = Option<String>, example = "2025-10-02T08:00:00Z")]
+    pub compromised_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ManufacturerKeysResponse {
+    keys: Vec<ManufacturerKey>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/manufacturer/{manufacturer_address}/keys",
+    params(
+        ("manufacturer_address" = String, Path, description = "Address of the manufacturer", example = "0x1234567890abcdef1234567890abcdef12345678")
+    ),
+    responses(
+        (status = 200, description = "Every key the manufacturer has authorized, newest first", body = ManufacturerKeysResponse),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Manufacturers"
+)]
+pub async fn list_signing_keys(
+    State(state): State<Arc<AppState>>,
+    Path(manufacturer_address): Path<String>,
+) -> impl IntoResponse {
+    match list_signing_keys_internal(&state, &manufacturer_address).await {
+        Ok(keys) => (StatusCode::OK, Json(ManufacturerKeysResponse { keys })).into_response(),
+        Err(e) => {
+            eprintln!(
+                "Error listing signing keys of manufacturer {}: {:?}",
+                manufacturer_address, e
+            );
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/manufacturer/keys/authorize",
+    request_body = AuthorizeKeyRequest,
+    responses(
+        (status = 200, description = "The manufacturer must send `authorizeSigningKey` from its wallet", body = PreparedTransaction),
+        (status = 400, description = "Invalid key, address or validity window"),
+        (status = 403, description = "Caller is not a registered manufacturer"),
+        (status = 409, description = "Key was already authorized"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Manufacturers"
+)]
+pub async fn authorize_signing_key(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<AuthorizeKeyRequest>,
+) -> impl IntoResponse {
+    match authorize_signing_key_internal(&state, &request).await {
+        Ok(transaction) => (StatusCode::OK, Json(transaction)).into_response(),
+        Err(e) => {
+            eprintln!("Error preparing authorizeSigningKey transaction: {:?}", e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/manufacturer/keys/rotate",
+    request_body = RotateKeyRequest,
+    responses(
+        (status = 200, description = "The manufacturer must send `rotateSigningKey` from its wallet; the old key stops signing when it is mined", body = PreparedTransaction),
+        (status = 400, description = "Invalid key, address or validity window"),
+        (status = 403, description = "Caller is not a registered manufacturer"),
+        (status = 404, description = "Signing key not found"),
+        (status = 409, description = "Old key is revoked or new key was already authorized"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Manufacturers"
+)]
+pub async fn rotate_signing_key(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<RotateKeyRequest>,
+) -> impl IntoResponse {
+    match rotate_signing_key_internal(&state, &request).await {
+        Ok(transaction) => (StatusCode::OK, Json(transaction)).into_response(),
+        Err(e) => {
+            eprintln!("Error preparing rotateSigningKey transaction: {:?}", e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/manufacturer/keys/revoke",
+    request_body = RevokeKeyRequest,
+    responses(
+        (status = 200, description = "The manufacturer must send `revokeSigningKey` from its wallet", body = PreparedTransaction),
+        (status = 400, description = "Invalid key or address"),
+        (status = 403, description = "Caller is not a registered manufacturer"),
+        (status = 404, description = "Signing key not found"),
+        (status = 409, description = "Key is already revoked"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Manufacturers"
+)]
+pub async fn revoke_signing_key(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<RevokeKeyRequest>,
+) -> impl IntoResponse {
+    match revoke_signing_key_internal(&state, &request).await {
+        Ok(transaction) => (StatusCode::OK, Json(transaction)).into_response(),
+        Err(e) => {
+            eprintln!("Error preparing revokeSigningKey transaction: {:?}", e);
+            error_response(e)
+        }
+    }
+}
+
+fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.starts_with("Invalid") => (StatusCode::BAD_REQUEST, e.to_string()),
+        "Caller address cannot be empty" => (StatusCode::BAD_REQUEST, e.to_string()),
+        "Caller is not a registered manufacturer" => (StatusCode::FORBIDDEN, e.to_string()),
+        "Signing key not found" => (StatusCode::NOT_FOUND, e.to_string()),
+        s if s.contains("already") => (StatusCode::CONFLICT, e.to_string()),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, Json(serde_json::json!({"error": message}))).into_response()
+}
+
+// When Authenticity considers the certificate issued: the first time it was recorded on chain
+// (recordCertificates or a claim), or now for one it has never seen. The certificate date is
+// chosen by the signer, so a leaked key could backdate it; this is the same clock
+// verifySignature judges keys by, so nothing accepted here reverts there.
+pub async fn chain_issued_at(state: &Arc<AppState>, cert_hash: &str) -> Result<DateTime<Utc>> {
+    let digest: [u8; 32] = hex::decode(cert_hash.trim_start_matches("0x"))?
+        .try_into()
+        .map_err(|_| eyre::eyre!("Certificate hash must be 32 bytes"))?;
+
+    let issued_at = state
+        .authenticity_contract
+        .get_issued_at(digest)
+        .call()
+        .await
+        .map_err(|e| eyre::eyre!("Failed to read certificate issue time: {}", e))?;
+
+    Ok(match issued_at {
+        0 => Utc::now(),
+        secs => DateTime::from_timestamp(secs as i64, 0)
+            .ok_or_else(|| eyre::eyre!("Invalid certificate issue time: {}", secs))?,
+    })
+}
+
+// The key that signed the certificate for the manufacturer, if `signer` is one of its keys and
+// was valid at `issued_at` (see chain_issued_at). Mirrors Authenticity.isValidSigner.
+pub fn authorized_key(
+    conn: &mut PgConnection,
+    manufacturer_address: &str,
+    signer: &str,
+    issued_at: DateTime<Utc>,
+) -> Result<Option<ManufacturerKey>> {
+    signing_keys::table
+        .filter(signing_keys::key_address.ilike(signer))
+        .filter(signing_keys::manufacturer_address.ilike(manufacturer_address))
+        .filter(signing_keys::valid_from.le(issued_at))
+        .filter(
+            signing_keys::valid_until
+                .is_null()
+                .or(signing_keys::valid_until.gt(issued_at)),
+        )
+        .filter(
+            signing_keys::revoked_at
+                .is_null()
+                .or(signing_keys::revoked_at.gt(issued_at)),
+        )
+        .select(ManufacturerKey::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query signing key: {}", e))
+}
+
+fn find_key(conn: &mut PgConnection, key: &str) -> Result<Option<ManufacturerKey>> {
+    signing_keys::table
+        .filter(signing_keys::key_address.ilike(key))
+        .select(ManufacturerKey::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query signing key: {}", e))
+}
+
+fn parse_key(key: &str) -> Result<Address> {
+    let key: Address = key
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid address: key"))?;
+    if key.is_zero() {
+        return Err(eyre::eyre!("Invalid address: key"));
+    }
+    Ok(key)
+}
+
+// Contract timestamps are unix seconds, 0 meaning "not set"
+fn unix_secs(time: Option<DateTime<Utc>>) -> u64 {
+    time.map(|time| time.timestamp().max(0) as u64).unwrap_or(0)
+}
+
+async fn list_signing_keys_internal(state: &Arc<AppState>, manufacturer_address: &str) -> Result<Vec<ManufacturerKey>> {
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    signing_keys::table
+        .filter(signing_keys::manufacturer_address.ilike(manufacturer_address))
+        .order((signing_keys::block_number.desc(), signing_keys::log_index.desc()))
+        .select(ManufacturerKey::as_select())
+        .load(conn)
+        .map_err(|e| eyre::eyre!("Failed to fetch signing keys: {}", e))
+}
+
+async fn authorize_signing_key_internal(
+    state: &Arc<AppState>,
+    request: &AuthorizeKeyRequest,
+) -> Result<PreparedTransaction> {
+    let key = parse_key(&request.key)?;
+    let valid_from = request.valid_from.unwrap_or_else(Utc::now);
+    if let Some(valid_until) = request.valid_until
+        && valid_until <= valid_from
+    {
+        return Err(eyre::eyre!("Invalid validity window: valid_until must be after valid_from"));
+    }
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let manufacturer_address = registered_manufacturer(conn, &request.caller)?;
+    if manufacturer_address.eq_ignore_ascii_case(&request.key) {
+        return Err(eyre::eyre!("Invalid key: the manufacturer's own address always signs"));
+    }
+    if find_key(conn, &request.key)?.is_some() {
+        return Err(eyre::eyre!("Key was already authorized"));
+    }
+
+    let call = state.authenticity_contract.authorize_signing_key(
+        key,
+        unix_secs(Some(valid_from)),
+        unix_secs(request.valid_until),
+    );
+
+    prepare_transaction(&request.caller, state.authenticity_contract.address(), call.calldata())
+}
+
+async fn rotate_signing_key_internal(
+    state: &Arc<AppState>,
+    request: &RotateKeyRequest,
+) -> Result<PreparedTransaction> {
+    let old_key = parse_key(&request.old_key)?;
+    let new_key = parse_key(&request.new_key)?;
+    if let Some(valid_until) = request.valid_until
+        && valid_until <= Utc::now()
+    {
+        return Err(eyre::eyre!("Invalid validity window: valid_until must be in the future"));
+    }
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let manufacturer_address = registered_manufacturer(conn, &request.caller)?;
+    let old = find_key(conn, &request.old_key)?
+        .filter(|old| old.manufacturer_address.eq_ignore_ascii_case(&manufacturer_address))
+        .ok_or_else(|| eyre::eyre!("Signing key not found"))?;
+    if old.revoked_at.is_some() {
+        return Err(eyre::eyre!("Key is already revoked"));
+    }
+    if manufacturer_address.eq_ignore_ascii_case(&request.new_key) {
+        return Err(eyre::eyre!("Invalid key: the manufacturer's own address always signs"));
+    }
+    if find_key(conn, &request.new_key)?.is_some() {
+        return Err(eyre::eyre!("Key was already authorized"));
+    }
+
+    let call = state.authenticity_contract.rotate_signing_key(
+        old_key,
+        new_key,
+        unix_secs(request.valid_until),
+    );
+
+    prepare_transaction(&request.caller, state.authenticity_contract.address(), call.calldata())
+}
+
+async fn revoke_signing_key_internal(
+    state: &Arc<AppState>,
+    request: &RevokeKeyRequest,
+) -> Result<PreparedTransaction> {
+    let key = parse_key(&request.key)?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let manufacturer_address = registered_manufacturer(conn, &request.caller)?;
+    let signing_key = find_key(conn, &request.key)?
+        .filter(|signing_key| signing_key.manufacturer_address.eq_ignore_ascii_case(&manufacturer_address))
+        .ok_or_else(|| eyre::eyre!("Signing key not found"))?;
+    if signing_key.revoked_at.is_some() {
+        return Err(eyre::eyre!("Key is already revoked"));
+    }
+
+    // the contract clamps a future cut-off to the block time anyway
+    let compromised_at = request.compromised_at.map(|time| time.min(Utc::now()));
+    let call = state
+        .authenticity_contract
+        .revoke_signing_key(key, unix_secs(compromised_at));
+
+    prepare_transaction(&request.caller, state.authenticity_contract.address(), call.calldata())
+}
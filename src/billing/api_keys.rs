@@ -0,0 +1,214 @@
+use crate::analytics::verification_log::api_key_hash;
+use crate::config::app_state::AppState;
+use crate::contract_models::ApiKey;
+use crate::models::wallet_auth::WalletAuth;
+use crate::schema::api_keys;
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use ethers::core::utils::to_checksum;
+use ethers::utils::keccak256;
+use eyre::Result;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+use validator::Validate;
+
+// Integrators send X-API-Key to have their calls charged to their own gas budget. Keys are
+// issued by admins and only their hash is stored, so a key is shown once, when created.
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateApiKeyRequest {
+    // who the key is for, for the admins' own reference
+    #[validate(length(min = 1, max = 100))]
+    #[schema(example = "Acme retail scanner app")]
+    pub name: String,
+    // signed by an admin wallet for `create-api-key <name>`
+    pub auth: WalletAuth,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RevokeApiKeyRequest {
+    // signed by an admin wallet for `revoke-api-key <key_hash>`
+    pub auth: WalletAuth,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CreatedApiKey {
+    // the key itself; it cannot be retrieved again
+    #[schema(example = "eri_9f2c4b7d1e0a3c5f8b6d2e4a7c9f1b3d5e7a9c1f3b5d7e9a2c4f6b8d0e2a4c6e")]
+    api_key: String,
+    #[serde(flatten)]
+    key: ApiKey,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/api-keys",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 200, description = "Key created; `api_key` is only ever returned here", body = CreatedApiKey),
+        (status = 400, description = "Invalid name or caller address"),
+        (status = 401, description = "Invalid wallet signature"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Billing"
+)]
+pub async fn create_api_key(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> impl IntoResponse {
+    match create_api_key_internal(&state, &request).await {
+        Ok(created) => (StatusCode::OK, Json(created)).into_response(),
+        Err(e) => {
+            eprintln!("Error creating API key {}: {:?}", request.name, e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/api-keys/{key_hash}/revoke",
+    params(
+        ("key_hash" = String, Path, description = "keccak256 of the key, as returned when it was created", example = "0x5d1f6a0e2b0c4f3e9a7d8c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f")
+    ),
+    request_body = RevokeApiKeyRequest,
+    responses(
+        (status = 200, description = "Key revoked; requests sending it are refused from now on", body = ApiKey),
+        (status = 400, description = "Invalid caller address"),
+        (status = 401, description = "Invalid wallet signature"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "API key not found"),
+        (status = 409, description = "API key is already revoked"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Billing"
+)]
+pub async fn revoke_api_key(
+    State(state): State<Arc<AppState>>,
+    Path(key_hash): Path<String>,
+    Json(request): Json<RevokeApiKeyRequest>,
+) -> impl IntoResponse {
+    match revoke_api_key_internal(&state, &key_hash, &request).await {
+        Ok(key) => (StatusCode::OK, Json(key)).into_response(),
+        Err(e) => {
+            eprintln!("Error revoking API key {}: {:?}", key_hash, e);
+            error_response(e)
+        }
+    }
+}
+
+fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.starts_with("Invalid wallet signature") => (StatusCode::UNAUTHORIZED, e.to_string()),
+        s if s.starts_with("Invalid") => (StatusCode::BAD_REQUEST, e.to_string()),
+        "Caller is not an admin" => (StatusCode::FORBIDDEN, e.to_string()),
+        "API key not found" => (StatusCode::NOT_FOUND, e.to_string()),
+        "API key is already revoked" => (StatusCode::CONFLICT, e.to_string()),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, Json(serde_json::json!({"error": message}))).into_response()
+}
+
+// Whether `key_hash` belongs to a key that was issued and not revoked
+pub fn is_active_key(conn: &mut PgConnection, key_hash: &str) -> Result<bool> {
+    api_keys::table
+        .filter(api_keys::key_hash.eq(key_hash.to_lowercase()))
+        .filter(api_keys::revoked_at.is_null())
+        .select(diesel::dsl::count_star())
+        .first::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(|e| eyre::eyre!("Failed to query API key: {}", e))
+}
+
+// Hash of the request's X-API-Key, None when it sent none. A key that was never issued or
+// was revoked is refused rather than ignored, so it cannot open a budget of its own.
+pub fn request_key_hash(conn: &mut PgConnection, headers: &HeaderMap) -> Result<Option<String>> {
+    match api_key_hash(headers) {
+        Some(hash) if is_active_key(conn, &hash)? => Ok(Some(hash)),
+        Some(_) => Err(eyre::eyre!("Invalid API key")),
+        None => Ok(None),
+    }
+}
+
+fn generate_key() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill(&mut bytes);
+    format!("eri_{}", hex::encode(bytes))
+}
+
+async fn create_api_key_internal(state: &Arc<AppState>, request: &CreateApiKeyRequest) -> Result<CreatedApiKey> {
+    request
+        .validate()
+        .map_err(|e| eyre::eyre!("Invalid name: {}", e))?;
+
+    let caller = request.auth.verify("create-api-key", &request.name, request)?;
+    if !state.admin_addresses.contains(&caller) {
+        return Err(eyre::eyre!("Caller is not an admin"));
+    }
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let api_key = generate_key();
+    let key = ApiKey {
+        key_hash: format!("0x{}", hex::encode(keccak256(api_key.as_bytes()))),
+        name: request.name.trim().to_string(),
+        created_by: to_checksum(&caller, None),
+        created_at: Utc::now(),
+        revoked_at: None,
+    };
+    diesel::insert_into(api_keys::table)
+        .values(&key)
+        .execute(conn)
+        .map_err(|e| eyre::eyre!("Failed to create API key: {}", e))?;
+
+    Ok(CreatedApiKey { api_key, key })
+}
+
+async fn revoke_api_key_internal(
+    state: &Arc<AppState>,
+    key_hash: &str,
+    request: &RevokeApiKeyRequest,
+) -> Result<ApiKey> {
+    let caller = request.auth.verify("revoke-api-key", key_hash, request)?;
+    if !state.admin_addresses.contains(&caller) {
+        return Err(eyre::eyre!("Caller is not an admin"));
+    }
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let key = api_keys::table
+        .filter(api_keys::key_hash.eq(key_hash.to_lowercase()))
+        .select(ApiKey::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query API key: {}", e))?
+        .ok_or_else(|| eyre::eyre!("API key not found"))?;
+    if key.revoked_at.is_some() {
+        return Err(eyre::eyre!("API key is already revoked"));
+    }
+
+    diesel::update(api_keys::table.filter(api_keys::key_hash.eq(&key.key_hash)))
+        .set(api_keys::revoked_at.eq(Utc::now()))
+        .returning(ApiKey::as_returning())
+        .get_result(conn)
+        .map_err(|e| eyre::eyre!("Failed to revoke API key: {}", e))
+}
@@ -0,0 +1,621 @@
+use crate::billing::api_keys::request_key_hash;
+use crate::config::app_state::AppState;
+use crate::contract_models::{GasBudget, NewGasLedgerEntry, NewGasReservation};
+use crate::models::wallet_auth::WalletAuth;
+use crate::schema::{gas_budgets, gas_ledger, gas_reservations};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::{Int8, Text, Timestamptz};
+use diesel::PgConnection;
+use ethers::core::utils::to_checksum;
+use ethers::types::{Address, TransactionReceipt, U256};
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+// The backend wallet pays gas for the calls it sends itself. Each transaction is booked
+// against a sponsor (the manufacturer or user it was sent for, or the integrator whose
+// X-API-Key made the request). The estimated fee is reserved before anything is sent and
+// swapped for the actual fee once the receipt is in, so concurrent calls cannot together
+// spend past the sponsor's monthly budget.
+
+// A reservation whose request died before settling it stops counting after this long
+const RESERVATION_TTL_MINUTES: i64 = 15;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SponsorKind {
+    Manufacturer,
+    User,
+    // keyed by the hash of a registered X-API-Key
+    ApiKey,
+    // calls made for the platform itself
+    Platform,
+}
+
+impl SponsorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SponsorKind::Manufacturer => "manufacturer",
+            SponsorKind::User => "user",
+            SponsorKind::ApiKey => "api_key",
+            SponsorKind::Platform => "platform",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GasOperation {
+    CreateItem,
+    RegisterUser,
+    SetAuthenticity,
+}
+
+impl GasOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GasOperation::CreateItem => "create_item",
+            GasOperation::RegisterUser => "register_user",
+            GasOperation::SetAuthenticity => "set_authenticity",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Sponsor {
+    pub kind: SponsorKind,
+    pub id: String,
+}
+
+impl Sponsor {
+    pub fn manufacturer(address: Address) -> Self {
+        Sponsor {
+            kind: SponsorKind::Manufacturer,
+            id: to_checksum(&address, None),
+        }
+    }
+
+    pub fn platform() -> Self {
+        Sponsor {
+            kind: SponsorKind::Platform,
+            id: "platform".to_string(),
+        }
+    }
+
+    // An integrator calling with a registered X-API-Key pays for the call instead of
+    // `default`; an unknown or revoked key fails the request
+    pub fn for_request(conn: &mut PgConnection, headers: &HeaderMap, default: Sponsor) -> Result<Self> {
+        Ok(match request_key_hash(conn, headers)? {
+            Some(hash) => Sponsor {
+                kind: SponsorKind::ApiKey,
+                id: hash,
+            },
+            None => default,
+        })
+    }
+
+    // From a query or admin request; addresses are checksummed like the ledger stores them
+    fn parse(kind: SponsorKind, id: &str) -> Result<Self> {
+        let id = match kind {
+            SponsorKind::Manufacturer | SponsorKind::User => {
+                let address: Address = id
+                    .parse()
+                    .map_err(|_| eyre::eyre!("Invalid sponsor: not an address"))?;
+                to_checksum(&address, None)
+            }
+            SponsorKind::ApiKey => id.to_lowercase(),
+            SponsorKind::Platform => "platform".to_string(),
+        };
+        Ok(Sponsor { kind, id })
+    }
+}
+
+// Fee set aside for one sponsored transaction until it is settled
+pub struct GasReservation {
+    id: i32,
+    sponsor: Sponsor,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct GasBudgetRequest {
+    pub sponsor_kind: SponsorKind,
+    // address, API key hash, or anything for `platform`
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub sponsor: String,
+    // signed for `read-gas-budget <sponsor>` by an admin, or by the sponsor's own wallet
+    pub auth: WalletAuth,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SetGasBudgetRequest {
+    pub sponsor_kind: SponsorKind,
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub sponsor: String,
+    // absent removes the sponsor's own budget so the default for its kind applies again
+    #[schema(example = 50000000000000000i64)]
+    pub monthly_limit_wei: Option<i64>,
+    // signed by an admin wallet for `set-gas-budget <sponsor>`
+    pub auth: WalletAuth,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GasBudgetStatus {
+    sponsor_kind: SponsorKind,
+    #[schema(example = "0x1234567890AbcdEF1234567890aBcdef12345678")]
+    sponsor: String,
+    // null = unlimited
+    #[schema(nullable = true, example = 50000000000000000i64)]
+    monthly_limit_wei: Option<i64>,
+    // whether the limit is the sponsor's own or the default for its kind
+    #[schema(example = true)]
+    custom_limit: bool,
+    #[schema(value_type = String, example = "2025-10-01T00:00:00Z")]
+    period_start: DateTime<Utc>,
+    #[schema(example = 1250000000000000i64)]
+    spent_wei: i64,
+    // estimated fees of transactions still in flight
+    #[schema(example = 0)]
+    reserved_wei: i64,
+    #[schema(nullable = true, example = 48750000000000000i64)]
+    remaining_wei: Option<i64>,
+    // new sponsored transactions are refused until the next period
+    #[schema(example = false)]
+    exhausted: bool,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct GasReportRequest {
+    // start of the period, inclusive (default start of the current month)
+    #[schema(value_type = Option<String>, example = "2025-10-01T00:00:00Z")]
+    pub from: Option<DateTime<Utc>>,
+    // end of the period, exclusive (default now)
+    #[schema(value_type = Option<String>, example = "2025-11-01T00:00:00Z")]
+    pub to: Option<DateTime<Utc>>,
+    pub sponsor_kind: Option<SponsorKind>,
+    // only this sponsor (needs `sponsor_kind`)
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub sponsor: Option<String>,
+    // signed for `read-gas-report <sponsor or "all">` by an admin; a sponsor's own wallet
+    // may sign for a report filtered to itself
+    pub auth: WalletAuth,
+}
+
+#[derive(QueryableByName, Serialize, ToSchema)]
+pub struct GasReportRow {
+    #[diesel(sql_type = Text)]
+    #[schema(value_type = SponsorKind, example = "manufacturer")]
+    sponsor_kind: String,
+    #[diesel(sql_type = Text)]
+    #[schema(example = "0x1234567890AbcdEF1234567890aBcdef12345678")]
+    sponsor: String,
+    #[diesel(sql_type = Text)]
+    #[schema(value_type = GasOperation, example = "create_item")]
+    operation: String,
+    #[diesel(sql_type = Int8)]
+    #[schema(example = 42)]
+    transactions: i64,
+    #[diesel(sql_type = Int8)]
+    #[schema(example = 7350000)]
+    gas_used: i64,
+    #[diesel(sql_type = Int8)]
+    #[schema(example = 1250000000000000i64)]
+    fee_wei: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GasReport {
+    #[schema(value_type = String, example = "2025-10-01T00:00:00Z")]
+    from: DateTime<Utc>,
+    #[schema(value_type = String, example = "2025-11-01T00:00:00Z")]
+    to: DateTime<Utc>,
+    #[schema(example = 42)]
+    transactions: i64,
+    #[schema(example = 7350000)]
+    gas_used: i64,
+    #[schema(example = 1250000000000000i64)]
+    fee_wei: i64,
+    // per sponsor and operation, most expensive first
+    rows: Vec<GasReportRow>,
+}
+
+#[derive(QueryableByName)]
+struct Spent {
+    #[diesel(sql_type = Int8)]
+    fee_wei: i64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/gas/budget",
+    request_body = GasBudgetRequest,
+    responses(
+        (status = 200, description = "Budget and spending of the sponsor in the current month", body = GasBudgetStatus),
+        (status = 400, description = "Invalid sponsor or caller address"),
+        (status = 401, description = "Invalid wallet signature"),
+        (status = 403, description = "Caller is neither an admin nor the sponsor"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Billing"
+)]
+pub async fn get_gas_budget(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<GasBudgetRequest>,
+) -> impl IntoResponse {
+    match get_gas_budget_internal(&state, &request).await {
+        Ok(status) => (StatusCode::OK, Json(status)).into_response(),
+        Err(e) => {
+            eprintln!("Error fetching gas budget of {}: {:?}", request.sponsor, e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/admin/gas/budget",
+    request_body = SetGasBudgetRequest,
+    responses(
+        (status = 200, description = "Budget set; it applies to the current month right away", body = GasBudgetStatus),
+        (status = 400, description = "Invalid sponsor, limit or caller address"),
+        (status = 401, description = "Invalid wallet signature"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Billing"
+)]
+pub async fn set_gas_budget(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<SetGasBudgetRequest>,
+) -> impl IntoResponse {
+    match set_gas_budget_internal(&state, &request).await {
+        Ok(status) => (StatusCode::OK, Json(status)).into_response(),
+        Err(e) => {
+            eprintln!("Error setting gas budget of {}: {:?}", request.sponsor, e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/gas/report",
+    request_body = GasReportRequest,
+    responses(
+        (status = 200, description = "Gas paid per sponsor and operation over the period, for billing", body = GasReport),
+        (status = 400, description = "Invalid range, sponsor or caller address"),
+        (status = 401, description = "Invalid wallet signature"),
+        (status = 403, description = "Caller is neither an admin nor the sponsor the report is filtered to"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Billing"
+)]
+pub async fn get_gas_report(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<GasReportRequest>,
+) -> impl IntoResponse {
+    match get_gas_report_internal(&state, &request).await {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => {
+            eprintln!("Error building gas report: {:?}", e);
+            error_response(e)
+        }
+    }
+}
+
+fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.starts_with("Invalid wallet signature") => (StatusCode::UNAUTHORIZED, e.to_string()),
+        s if s.starts_with("Invalid") => (StatusCode::BAD_REQUEST, e.to_string()),
+        "Caller is not an admin" | "Caller is not an admin or the sponsor" => {
+            (StatusCode::FORBIDDEN, e.to_string())
+        }
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, Json(serde_json::json!({"error": message}))).into_response()
+}
+
+// Budgets run per calendar month (UTC)
+fn period_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .single()
+        .unwrap_or(now)
+}
+
+// Default monthly limit for a kind, e.g. GAS_BUDGET_MANUFACTURER_WEI; unset = unlimited
+fn default_limit(kind: SponsorKind) -> Option<i64> {
+    env::var(format!("GAS_BUDGET_{}_WEI", kind.as_str().to_uppercase()))
+        .ok()
+        .and_then(|limit| limit.parse::<i64>().ok())
+}
+
+fn custom_limit(conn: &mut PgConnection, sponsor: &Sponsor) -> Result<Option<i64>> {
+    gas_budgets::table
+        .filter(gas_budgets::sponsor_kind.eq(sponsor.kind.as_str()))
+        .filter(gas_budgets::sponsor.eq(&sponsor.id))
+        .select(gas_budgets::monthly_limit_wei)
+        .first::<i64>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query gas budget: {}", e))
+}
+
+fn spent_since(conn: &mut PgConnection, sponsor: &Sponsor, since: DateTime<Utc>) -> Result<i64> {
+    diesel::sql_query(
+        "SELECT COALESCE(SUM(fee_wei), 0)::BIGINT AS fee_wei FROM gas_ledger \
+         WHERE sponsor_kind = $1 AND sponsor = $2 AND created_at >= $3",
+    )
+    .bind::<Text, _>(sponsor.kind.as_str())
+    .bind::<Text, _>(&sponsor.id)
+    .bind::<Timestamptz, _>(since)
+    .get_result::<Spent>(conn)
+    .map(|spent| spent.fee_wei)
+    .map_err(|e| eyre::eyre!("Failed to sum gas spending: {}", e))
+}
+
+fn reserved(conn: &mut PgConnection, sponsor: &Sponsor) -> Result<i64> {
+    diesel::sql_query(
+        "SELECT COALESCE(SUM(fee_wei), 0)::BIGINT AS fee_wei FROM gas_reservations \
+         WHERE sponsor_kind = $1 AND sponsor = $2 AND created_at >= $3",
+    )
+    .bind::<Text, _>(sponsor.kind.as_str())
+    .bind::<Text, _>(&sponsor.id)
+    .bind::<Timestamptz, _>(Utc::now() - Duration::minutes(RESERVATION_TTL_MINUTES))
+    .get_result::<Spent>(conn)
+    .map(|spent| spent.fee_wei)
+    .map_err(|e| eyre::eyre!("Failed to sum gas reservations: {}", e))
+}
+
+// Wei amounts are stored as BIGINT; anything larger is clamped
+fn wei(amount: U256) -> i64 {
+    amount.min(U256::from(i64::MAX)).as_u64() as i64
+}
+
+fn budget_status(conn: &mut PgConnection, sponsor: &Sponsor) -> Result<GasBudgetStatus> {
+    let custom = custom_limit(conn, sponsor)?;
+    let monthly_limit_wei = custom.or_else(|| default_limit(sponsor.kind));
+    let period_start = period_start(Utc::now());
+    let spent_wei = spent_since(conn, sponsor, period_start)?;
+    let reserved_wei = reserved(conn, sponsor)?;
+    let committed_wei = spent_wei.saturating_add(reserved_wei);
+
+    Ok(GasBudgetStatus {
+        sponsor_kind: sponsor.kind,
+        sponsor: sponsor.id.clone(),
+        monthly_limit_wei,
+        custom_limit: custom.is_some(),
+        period_start,
+        spent_wei,
+        reserved_wei,
+        remaining_wei: monthly_limit_wei.map(|limit| (limit - committed_wei).max(0)),
+        exhausted: monthly_limit_wei.is_some_and(|limit| committed_wei >= limit),
+    })
+}
+
+// Sets the estimated fee aside for the sponsor, refusing the call when it does not fit in
+// what is left of this month's budget. Reservations of one sponsor are serialized by an
+// advisory lock, so the check and the insert cannot interleave with another request's.
+pub fn reserve_gas(conn: &mut PgConnection, sponsor: &Sponsor, estimated_fee: U256) -> Result<GasReservation> {
+    let fee_wei = wei(estimated_fee);
+
+    conn.transaction(|conn| {
+        diesel::sql_query("SELECT pg_advisory_xact_lock(hashtext($1 || ':' || $2))")
+            .bind::<Text, _>(sponsor.kind.as_str())
+            .bind::<Text, _>(&sponsor.id)
+            .execute(conn)
+            .map_err(|e| eyre::eyre!("Failed to lock gas budget: {}", e))?;
+
+        let status = budget_status(conn, sponsor)?;
+        if let Some(limit) = status.monthly_limit_wei
+            && status.spent_wei.saturating_add(status.reserved_wei).saturating_add(fee_wei) > limit
+        {
+            return Err(eyre::eyre!("Gas budget exhausted"));
+        }
+
+        let id = diesel::insert_into(gas_reservations::table)
+            .values(&NewGasReservation {
+                sponsor_kind: sponsor.kind.as_str().to_string(),
+                sponsor: sponsor.id.clone(),
+                fee_wei,
+            })
+            .returning(gas_reservations::id)
+            .get_result::<i32>(conn)
+            .map_err(|e| eyre::eyre!("Failed to reserve gas: {}", e))?;
+
+        Ok(GasReservation {
+            id,
+            sponsor: sponsor.clone(),
+        })
+    })
+}
+
+// Replaces the reservation with the mined transaction's actual fee, or just frees it when
+// nothing was mined. The gas is spent either way, so a failed write is only reported and
+// never changes the outcome of the request.
+pub fn settle_gas(
+    conn: &mut PgConnection,
+    reservation: GasReservation,
+    operation: GasOperation,
+    sent: Result<TransactionReceipt>,
+    gas_price: U256,
+) -> Result<TransactionReceipt> {
+    let settled = conn.transaction(|conn| {
+        if let Ok(receipt) = &sent {
+            record_gas(conn, &reservation.sponsor, operation, receipt, gas_price)?;
+        }
+        diesel::delete(gas_reservations::table.find(reservation.id)).execute(conn)
+    });
+    if let Err(e) = settled {
+        eprintln!(
+            "Failed to settle gas reservation {} of {} {}: {:?}",
+            reservation.id,
+            reservation.sponsor.kind.as_str(),
+            reservation.sponsor.id,
+            e
+        );
+    }
+
+    sent
+}
+
+fn record_gas(
+    conn: &mut PgConnection,
+    sponsor: &Sponsor,
+    operation: GasOperation,
+    receipt: &TransactionReceipt,
+    gas_price: U256,
+) -> QueryResult<usize> {
+    let gas_used = receipt.gas_used.unwrap_or_default();
+    let gas_price = receipt.effective_gas_price.unwrap_or(gas_price);
+    let entry = NewGasLedgerEntry {
+        tnx_hash: format!("0x{}", hex::encode(receipt.transaction_hash)),
+        operation: operation.as_str().to_string(),
+        sponsor_kind: sponsor.kind.as_str().to_string(),
+        sponsor: sponsor.id.clone(),
+        gas_used: wei(gas_used),
+        gas_price_wei: wei(gas_price),
+        fee_wei: wei(gas_used.saturating_mul(gas_price)),
+        succeeded: receipt.status.is_some_and(|status| status.as_u64() == 1),
+    };
+
+    diesel::insert_into(gas_ledger::table)
+        .values(&entry)
+        .on_conflict_do_nothing()
+        .execute(conn)
+}
+
+// Admins read any sponsor's spending; a manufacturer or user wallet only its own
+fn ensure_can_read(state: &AppState, caller: Address, sponsor: Option<&Sponsor>) -> Result<()> {
+    if state.admin_addresses.contains(&caller) {
+        return Ok(());
+    }
+    match sponsor {
+        Some(Sponsor {
+            kind: SponsorKind::Manufacturer | SponsorKind::User,
+            id,
+        }) if *id == to_checksum(&caller, None) => Ok(()),
+        _ => Err(eyre::eyre!("Caller is not an admin or the sponsor")),
+    }
+}
+
+async fn get_gas_budget_internal(state: &Arc<AppState>, request: &GasBudgetRequest) -> Result<GasBudgetStatus> {
+    let sponsor = Sponsor::parse(request.sponsor_kind, &request.sponsor)?;
+
+    let caller = request.auth.verify("read-gas-budget", &sponsor.id, request)?;
+    ensure_can_read(state, caller, Some(&sponsor))?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    budget_status(conn, &sponsor)
+}
+
+async fn set_gas_budget_internal(
+    state: &Arc<AppState>,
+    request: &SetGasBudgetRequest,
+) -> Result<GasBudgetStatus> {
+    let sponsor = Sponsor::parse(request.sponsor_kind, &request.sponsor)?;
+    if request.monthly_limit_wei.is_some_and(|limit| limit < 0) {
+        return Err(eyre::eyre!("Invalid limit: must not be negative"));
+    }
+
+    let caller = request.auth.verify("set-gas-budget", &sponsor.id, request)?;
+    if !state.admin_addresses.contains(&caller) {
+        return Err(eyre::eyre!("Caller is not an admin"));
+    }
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let own_budget = gas_budgets::table
+        .filter(gas_budgets::sponsor_kind.eq(sponsor.kind.as_str()))
+        .filter(gas_budgets::sponsor.eq(&sponsor.id));
+    match request.monthly_limit_wei {
+        Some(monthly_limit_wei) => {
+            let budget = GasBudget {
+                sponsor_kind: sponsor.kind.as_str().to_string(),
+                sponsor: sponsor.id.clone(),
+                monthly_limit_wei,
+                updated_by: to_checksum(&caller, None),
+                updated_at: Utc::now(),
+            };
+            diesel::insert_into(gas_budgets::table)
+                .values(&budget)
+                .on_conflict((gas_budgets::sponsor_kind, gas_budgets::sponsor))
+                .do_update()
+                .set(&budget)
+                .execute(conn)
+                .map_err(|e| eyre::eyre!("Failed to set gas budget: {}", e))?;
+        }
+        None => {
+            diesel::delete(own_budget)
+                .execute(conn)
+                .map_err(|e| eyre::eyre!("Failed to remove gas budget: {}", e))?;
+        }
+    }
+
+    budget_status(conn, &sponsor)
+}
+
+async fn get_gas_report_internal(state: &Arc<AppState>, request: &GasReportRequest) -> Result<GasReport> {
+    let to = request.to.unwrap_or_else(Utc::now);
+    let from = request.from.unwrap_or_else(|| period_start(to - Duration::seconds(1)));
+    if from >= to {
+        return Err(eyre::eyre!("Invalid range: from must be before to"));
+    }
+    let sponsor = match (request.sponsor_kind, &request.sponsor) {
+        (Some(kind), Some(sponsor)) => Some(Sponsor::parse(kind, sponsor)?),
+        (None, Some(_)) => return Err(eyre::eyre!("Invalid sponsor: sponsor_kind is required")),
+        (_, None) => None,
+    };
+
+    let subject = sponsor.as_ref().map_or("all", |sponsor| sponsor.id.as_str());
+    let caller = request.auth.verify("read-gas-report", subject, request)?;
+    ensure_can_read(state, caller, sponsor.as_ref())?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let rows = diesel::sql_query(
+        "SELECT sponsor_kind, sponsor, operation, COUNT(*) AS transactions, \
+                COALESCE(SUM(gas_used), 0)::BIGINT AS gas_used, \
+                COALESCE(SUM(fee_wei), 0)::BIGINT AS fee_wei \
+         FROM gas_ledger \
+         WHERE created_at >= $1 AND created_at < $2 \
+           AND ($3::TEXT IS NULL OR sponsor_kind = $3) \
+           AND ($4::TEXT IS NULL OR sponsor = $4) \
+         GROUP BY sponsor_kind, sponsor, operation \
+         ORDER BY fee_wei DESC, sponsor_kind, sponsor, operation",
+    )
+    .bind::<Timestamptz, _>(from)
+    .bind::<Timestamptz, _>(to)
+    .bind::<diesel::sql_types::Nullable<Text>, _>(request.sponsor_kind.map(|kind| kind.as_str()))
+    .bind::<diesel::sql_types::Nullable<Text>, _>(sponsor.map(|sponsor| sponsor.id))
+    .load::<GasReportRow>(conn)
+    .map_err(|e| eyre::eyre!("Failed to aggregate gas ledger: {}", e))?;
+
+    Ok(GasReport {
+        from,
+        to,
+        transactions: rows.iter().map(|row| row.transactions).sum(),
+        gas_used: rows.iter().map(|row| row.gas_used).sum(),
+        fee_wei: rows.iter().map(|row| row.fee_wei).sum(),
+        rows,
+    })
+}
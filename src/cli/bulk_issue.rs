@@ -0,0 +1,81 @@
+use crate::services::bulk_issuance::{build_archive, issue_batch, BatchFormat};
+use dotenv::dotenv;
+use ethers::prelude::LocalWallet;
+use ethers::signers::Signer;
+use eyre::Result;
+use std::env;
+use std::fs;
+
+const USAGE: &str =
+    "usage: bulk-issue <rows.csv|rows.ndjson> [--format csv|ndjson] [--out certificates.zip]";
+
+// Signs a batch offline so the manufacturer's key never leaves their machine; upload the
+// resulting certificates.ndjson to POST /api/certificates/bulk (format ndjson) to register it. Reads
+// MANUFACTURER_PRIVATE_KEY plus the EIP-712 domain variables from .env.
+pub async fn run(args: Vec<String>) -> Result<()> {
+    dotenv().ok();
+
+    let mut input = None;
+    let mut format = None;
+    let mut out = "certificates.zip".to_string();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("csv") => Some(BatchFormat::Csv),
+                    Some("ndjson") => Some(BatchFormat::Ndjson),
+                    _ => return Err(eyre::eyre!("--format must be csv or ndjson\n{}", USAGE)),
+                }
+            }
+            "--out" => {
+                out = args
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("--out needs a path\n{}", USAGE))?
+            }
+            _ if input.is_none() => input = Some(arg),
+            _ => return Err(eyre::eyre!("Unexpected argument {}\n{}", arg, USAGE)),
+        }
+    }
+
+    let input = input.ok_or_else(|| eyre::eyre!(USAGE))?;
+    let format = format.unwrap_or(
+        if input.ends_with(".ndjson") || input.ends_with(".jsonl") {
+            BatchFormat::Ndjson
+        } else {
+            BatchFormat::Csv
+        },
+    );
+
+    let wallet: LocalWallet = env::var("MANUFACTURER_PRIVATE_KEY")
+        .map_err(|_| eyre::eyre!("MANUFACTURER_PRIVATE_KEY must be set"))?
+        .parse()
+        .map_err(|e| eyre::eyre!("Invalid MANUFACTURER_PRIVATE_KEY: {}", e))?;
+
+    eprintln!("Signing as manufacturer {:?}", wallet.address());
+
+    let rows = fs::read_to_string(&input)
+        .map_err(|e| eyre::eyre!("Failed to read {}: {}", input, e))?;
+
+    let outcome = issue_batch(&wallet, format, &rows).await?;
+    fs::write(&out, build_archive(&outcome)?)
+        .map_err(|e| eyre::eyre!("Failed to write {}: {}", out, e))?;
+
+    for report in outcome.report.iter().filter(|r| r.error.is_some()) {
+        eprintln!(
+            "row {} ({}): {}",
+            report.row,
+            report.unique_id.as_deref().unwrap_or("-"),
+            report.error.as_deref().unwrap_or_default()
+        );
+    }
+    eprintln!(
+        "Issued {} certificate(s), {} row(s) failed, archive written to {}",
+        outcome.issued.len(),
+        outcome.failed(),
+        out
+    );
+
+    Ok(())
+}
@@ -1,9 +1,25 @@
+use crate::authenticity::certificate_revocation::{
+    download_crl, get_certificate_revocation, revoke_certificate,
+};
 use crate::authenticity::get_manufacturer::get_manufacturer;
+use crate::authenticity::manufacturer_profile::{review_manufacturer_kyb, update_manufacturer_profile};
+use crate::authenticity::signing_keys::{
+    authorize_signing_key, list_signing_keys, revoke_signing_key, rotate_signing_key,
+};
 use crate::authenticity::is_username_exist::manufacturer_name_exists;
 use crate::config::app_state::AppState;
 use crate::config::swagger_config::ApiDoc;
 use crate::ownership::get_my_items::{ get_owner_items};
+use crate::ownership::list_items::list_items;
+use crate::ownership::list_users::list_users;
+use crate::ownership::list_claims::list_claims;
+use crate::search::search_items::search;
+use crate::analytics::manufacturer_analytics::get_manufacturer_analytics;
+use crate::analytics::verification_audit::{list_alerts, list_verifications, resolve_alert};
+use crate::billing::api_keys::{create_api_key, revoke_api_key};
+use crate::billing::gas_sponsorship::{get_gas_budget, get_gas_report, set_gas_budget};
 use crate::ownership::get_user_info::get_user;
+use crate::ownership::user_profile::{change_username, update_user_profile};
 use crate::ownership::is_name_exist::user_exists;
 use crate::services::create_eip712::create_certificate;
 use crate::services::other_tests::{
@@ -12,7 +28,7 @@ use crate::services::other_tests::{
 };
 use crate::services::qr_code::generate_qr_code;
 use crate::services::verify_authenticity::verify_authenticity;
-use axum::routing::{get, post};
+use axum::routing::{get, post, put};
 use axum::Router;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -21,11 +37,22 @@ use tower_http::cors::CorsLayer;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 use crate::ownership::get_item::get_item;
+use crate::ownership::item_history::{export_item_history, get_item_history};
+use crate::ownership::ownership_proof::{get_ownership_proof, verify_ownership_proof};
+use crate::ownership::flag_item::{clear_item_flag, flag_item, get_item_flags};
 use crate::ownership::get_transfer_code::get_ownership_code;
 use crate::ownership::revoke_ownership_code::revoke_ownership_code;
+use crate::ownership::onchain_ownership_code::{claim_with_code, commit_ownership_code, revoke_onchain_code};
 use crate::ownership::transfer_ownership_code::transfer_ownership_code;
 use crate::services::claim_ownership::claim_ownership;
 use crate::services::create_item::create_item;
+use crate::services::claim_item::{claim_item, get_claim_status};
+use crate::services::bulk_issuance::bulk_issue_certificates;
+use crate::products::manage_product::{create_product, update_product};
+use crate::products::get_product::{get_product, list_products};
+use crate::recalls::manage_recall::{close_recall, create_recall};
+use crate::recalls::get_recall::{get_recall, list_recalls};
+use crate::recalls::recall_notice::{acknowledge_recall, get_recall_notices};
 use crate::services::register_user::user_register;
 use crate::services::set_autheticity::set_authenticity;
 
@@ -41,14 +68,58 @@ pub fn paths(state: Arc<AppState>, path: RouterPath) -> Router {
         .route(&path.transfer_ownership, get(transfer_ownership_code))
         .route(&path.qr_code, post(generate_qr_code))
         .route(&path.get_manufacturer, get(get_manufacturer))
+        .route(&path.manufacturer_profile, put(update_manufacturer_profile))
+        .route(&path.manufacturer_kyb, put(review_manufacturer_kyb))
+        .route(&path.signing_keys, get(list_signing_keys))
+        .route(&path.authorize_signing_key, post(authorize_signing_key))
+        .route(&path.rotate_signing_key, post(rotate_signing_key))
+        .route(&path.revoke_signing_key, post(revoke_signing_key))
+        .route(&path.revoke_certificate, post(revoke_certificate))
+        .route(&path.certificate_revocation, get(get_certificate_revocation))
+        .route(&path.certificate_crl, get(download_crl))
         .route(&path.transfer_code, get(get_ownership_code))
+        .route(&path.get_user, get(get_user))
         .route(&path.is_user_exist, get(user_exists))
+        .route(&path.user_profile, put(update_user_profile))
+        .route(&path.change_username, post(change_username))
         .route(&path.get_my_items, get(get_owner_items))
+        .route(&path.list_items, get(list_items))
+        .route(&path.list_users, get(list_users))
+        .route(&path.list_claims, get(list_claims))
+        .route(&path.search, get(search))
+        .route(&path.manufacturer_analytics, get(get_manufacturer_analytics))
+        .route(&path.verification_logs, get(list_verifications))
+        .route(&path.verification_alerts, get(list_alerts))
+        .route(&path.resolve_verification_alert, post(resolve_alert))
+        .route(&path.gas_budget, post(get_gas_budget))
+        .route(&path.set_gas_budget, put(set_gas_budget))
+        .route(&path.gas_report, post(get_gas_report))
+        .route(&path.api_keys, post(create_api_key))
+        .route(&path.revoke_api_key, post(revoke_api_key))
         .route(&path.get_item, get(get_item))
+        .route(&path.item_flags, post(flag_item).get(get_item_flags))
+        .route(&path.clear_item_flag, post(clear_item_flag))
+        .route(&path.item_history, get(get_item_history))
+        .route(&path.export_item_history, get(export_item_history))
+        .route(&path.ownership_proof, get(get_ownership_proof))
+        .route(&path.verify_ownership_proof, post(verify_ownership_proof))
         .route(&path.revoke_code, post(revoke_ownership_code))
+        .route(&path.commit_code, post(commit_ownership_code))
+        .route(&path.claim_with_code, post(claim_with_code))
+        .route(&path.revoke_onchain_code, post(revoke_onchain_code))
         .route(&path.set_authenticity, post(set_authenticity))
         .route(&path.claim_ownership, post(claim_ownership))
         .route(&path.create_item, post(create_item))
+        .route(&path.claim_item, post(claim_item))
+        .route(&path.claim_status, get(get_claim_status))
+        .route(&path.bulk_issue, post(bulk_issue_certificates))
+        .route(&path.products, post(create_product).get(list_products))
+        .route(&path.product, get(get_product).put(update_product))
+        .route(&path.recalls, post(create_recall).get(list_recalls))
+        .route(&path.recall_notices, get(get_recall_notices))
+        .route(&path.recall, get(get_recall))
+        .route(&path.close_recall, post(close_recall))
+        .route(&path.acknowledge_recall, post(acknowledge_recall))
         .route(&path.manufacturer_name_exists, get(manufacturer_name_exists))
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
         .with_state(state)
@@ -68,18 +139,61 @@ pub struct RouterPath {
     pub create_certificate: String,
     pub qr_code: String,
     pub get_manufacturer: String,
+    pub manufacturer_profile: String,
+    pub manufacturer_kyb: String,
+    pub signing_keys: String,
+    pub authorize_signing_key: String,
+    pub rotate_signing_key: String,
+    pub revoke_signing_key: String,
+    pub revoke_certificate: String,
+    pub certificate_revocation: String,
+    pub certificate_crl: String,
     pub manufacturer_name_exists: String,
     pub get_user: String,
     pub is_user_exist: String,
+    pub user_profile: String,
+    pub change_username: String,
     pub get_my_items: String,
+    pub list_items: String,
+    pub list_users: String,
+    pub list_claims: String,
+    pub search: String,
+    pub manufacturer_analytics: String,
+    pub verification_logs: String,
+    pub verification_alerts: String,
+    pub resolve_verification_alert: String,
+    pub gas_budget: String,
+    pub set_gas_budget: String,
+    pub gas_report: String,
+    pub api_keys: String,
+    pub revoke_api_key: String,
     pub transfer_ownership: String,
     pub transfer_code: String,
     pub revoke_code: String,
+    pub commit_code: String,
+    pub claim_with_code: String,
+    pub revoke_onchain_code: String,
     pub user_register: String,
     pub set_authenticity: String,
     pub claim_ownership: String,
     pub create_item: String,
+    pub claim_item: String,
+    pub claim_status: String,
+    pub bulk_issue: String,
+    pub products: String,
+    pub product: String,
+    pub recalls: String,
+    pub recall_notices: String,
+    pub recall: String,
+    pub close_recall: String,
+    pub acknowledge_recall: String,
     pub get_item: String,
+    pub item_flags: String,
+    pub clear_item_flag: String,
+    pub item_history: String,
+    pub export_item_history: String,
+    pub ownership_proof: String,
+    pub verify_ownership_proof: String,
 }
 
 impl RouterPath {
@@ -93,18 +207,61 @@ impl RouterPath {
             create_certificate: "/create_certificate".to_string(),
             qr_code: "/qr_code".to_string(),
             get_manufacturer: "/api/manufacturer".to_string(),
+            manufacturer_profile: "/api/manufacturer/{manufacturer_address}/profile".to_string(),
+            manufacturer_kyb: "/api/admin/manufacturers/{manufacturer_address}/kyb".to_string(),
+            signing_keys: "/api/manufacturer/{manufacturer_address}/keys".to_string(),
+            authorize_signing_key: "/api/manufacturer/keys/authorize".to_string(),
+            rotate_signing_key: "/api/manufacturer/keys/rotate".to_string(),
+            revoke_signing_key: "/api/manufacturer/keys/revoke".to_string(),
+            revoke_certificate: "/api/certificates/revoke".to_string(),
+            certificate_revocation: "/api/certificates/revocations/{cert_hash}".to_string(),
+            certificate_crl: "/api/certificates/crl".to_string(),
             manufacturer_name_exists: "/api/manufacturer/exists".to_string(),
             get_user: "/api/user/get".to_string(),
             is_user_exist: "/api/user/exists".to_string(),
+            user_profile: "/api/user/{user_address}/profile".to_string(),
+            change_username: "/api/user/username".to_string(),
             get_my_items: "/api/items/owner".to_string(),
+            list_items: "/api/items".to_string(),
+            list_users: "/api/users".to_string(),
+            list_claims: "/api/claims".to_string(),
+            search: "/api/search".to_string(),
+            manufacturer_analytics: "/api/analytics/manufacturers/{manufacturer_address}".to_string(),
+            verification_logs: "/api/verifications".to_string(),
+            verification_alerts: "/api/alerts".to_string(),
+            resolve_verification_alert: "/api/alerts/{alert_id}/resolve".to_string(),
+            gas_budget: "/api/gas/budget".to_string(),
+            set_gas_budget: "/api/admin/gas/budget".to_string(),
+            gas_report: "/api/gas/report".to_string(),
+            api_keys: "/api/admin/api-keys".to_string(),
+            revoke_api_key: "/api/admin/api-keys/{key_hash}/revoke".to_string(),
             transfer_ownership: "/api/transfer_ownership".to_string(),
             transfer_code: "/api/get_transfer_code".to_string(),
             revoke_code: "/api/revoke_ownership_code".to_string(),
+            commit_code: "/api/ownership/code/commit".to_string(),
+            claim_with_code: "/api/ownership/code/claim".to_string(),
+            revoke_onchain_code: "/api/ownership/code/revoke".to_string(),
             user_register: "/api/user/register".to_string(),
             set_authenticity:  "/api/set_authenticity".to_string(),
             claim_ownership: "/api/ownership/claim".to_string(),
             create_item:  "/api/item/create".to_string(),
+            claim_item: "/api/items/claim".to_string(),
+            claim_status: "/api/items/claim/{item_id}".to_string(),
+            bulk_issue: "/api/certificates/bulk".to_string(),
+            products: "/api/products".to_string(),
+            product: "/api/products/{product_id}".to_string(),
+            recalls: "/api/recalls".to_string(),
+            recall_notices: "/api/recalls/notices".to_string(),
+            recall: "/api/recalls/{campaign_id}".to_string(),
+            close_recall: "/api/recalls/{campaign_id}/close".to_string(),
+            acknowledge_recall: "/api/recalls/{campaign_id}/acknowledge".to_string(),
             get_item: "/api/item/{item_id}".to_string(),
+            item_flags: "/api/item/{item_id}/flags".to_string(),
+            clear_item_flag: "/api/item/{item_id}/flags/clear".to_string(),
+            item_history: "/api/item/{item_id}/history".to_string(),
+            export_item_history: "/api/item/{item_id}/history/export".to_string(),
+            ownership_proof: "/api/item/{item_id}/ownership-proof".to_string(),
+            verify_ownership_proof: "/api/ownership-proof/verify".to_string(),
         }
     }
 }
\ No newline at end of file
@@ -6,6 +6,7 @@ use ethers::middleware::{Middleware, SignerMiddleware};
 use ethers::prelude::{Http, LocalWallet, Provider};
 use ethers::signers::{Signer, Wallet};
 use std::env;
+use std::net::IpAddr;
 use std::sync::Arc;
 use std::time::Duration;
 use diesel::Connection;
@@ -21,6 +22,11 @@ pub struct AppState {
     pub db_pool: Pool<ConnectionManager<PgConnection>>,
     pub authenticity_contract: Authenticity<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
     pub ownership_contract: Ownership<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
+    pub ownership_code_ttl_secs: i64,
+    pub ownership_proof_ttl_secs: i64,
+    pub verify_ownership_on_chain: bool,
+    pub admin_addresses: Vec<Address>,
+    pub trusted_proxies: Vec<IpAddr>,
 }
 
 impl AppState {
@@ -48,6 +54,55 @@ impl AppState {
             .map_err(|_| anyhow::anyhow!("Invalid contract address"))
             .unwrap();
 
+        // how long a generated ownership code stays claimable (defaults to 24 hours)
+        let ownership_code_ttl_secs = env::var("OWNERSHIP_CODE_TTL_SECS")
+            .ok()
+            .and_then(|ttl| ttl.parse::<i64>().ok())
+            .filter(|ttl| *ttl > 0)
+            .unwrap_or(86_400);
+
+        // how long a signed proof of ownership is accepted (defaults to 7 days)
+        let ownership_proof_ttl_secs = env::var("OWNERSHIP_PROOF_TTL_SECS")
+            .ok()
+            .and_then(|ttl| ttl.parse::<i64>().ok())
+            .filter(|ttl| *ttl > 0)
+            .unwrap_or(604_800);
+
+        // confirm isOwner on the Ownership contract before issuing a transfer code
+        let verify_ownership_on_chain = env::var("VERIFY_OWNERSHIP_ON_CHAIN")
+            .map(|flag| matches!(flag.to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+
+        // wallets allowed to review manufacturers, comma separated
+        let admin_addresses = env::var("ADMIN_ADDRESSES")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+            .filter_map(|address| match address.parse::<Address>() {
+                Ok(address) => Some(address),
+                Err(_) => {
+                    eprintln!("Ignoring invalid admin address {:?}", address);
+                    None
+                }
+            })
+            .collect();
+
+        // reverse proxies whose X-Forwarded-For is believed, comma separated
+        let trusted_proxies = env::var("TRUSTED_PROXIES")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|proxy| !proxy.is_empty())
+            .filter_map(|proxy| match proxy.parse::<IpAddr>() {
+                Ok(proxy) => Some(proxy),
+                Err(_) => {
+                    eprintln!("Ignoring invalid trusted proxy {:?}", proxy);
+                    None
+                }
+            })
+            .collect();
+
         let provider = Provider::<Http>::try_from(&rpc_url)?.interval(Duration::from_millis(1000));
         let chain_id = provider.get_chainid().await?.as_u64();
 
@@ -63,6 +118,11 @@ impl AppState {
             db_pool: pool,
             authenticity_contract,
             ownership_contract,
+            ownership_code_ttl_secs,
+            ownership_proof_ttl_secs,
+            verify_ownership_on_chain,
+            admin_addresses,
+            trusted_proxies,
         };
         Ok(state)
     }
@@ -3,6 +3,7 @@ use crate::config::app_router::paths;
 use crate::config::app_state::AppState;
 use crate::authenticity::authenticity_event_listener::listen_for_authenticity_events;
 use crate::ownership::ownership_event::listen_for_ownership_events;
+use crate::ownership::expire_ownership_codes::sweep_expired_ownership_codes;
 use anyhow::Result;
 use axum::Router;
 use dotenv::dotenv;
@@ -19,6 +20,7 @@ pub async fn server() -> Result<()> {
 
     let state_clone1 = arc_state.clone();
     let state_clone2 = arc_state.clone();
+    let state_clone3 = arc_state.clone();
 
     tokio::spawn(async move {
         // loop {
@@ -38,6 +40,12 @@ pub async fn server() -> Result<()> {
         // }
     });
 
+    tokio::spawn(async move {
+        if let Err(e) = sweep_expired_ownership_codes(&state_clone3).await {
+            eprintln!("Error in ownership code sweeper: {:?}", e);
+        }
+    });
+
     // Define routes
     let app: Router = paths(arc_state, RouterPath::init());
 
@@ -48,7 +56,7 @@ pub async fn server() -> Result<()> {
     eprintln!("Server running on {:?}", addr);
     eprintln!("Swagger UI available at {:?}/swagger-ui/index.html#/", addr);
 
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
     Ok(()) // another way to say return nothing
 }
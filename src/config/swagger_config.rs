@@ -1,7 +1,43 @@
+use crate::authenticity::certificate_revocation::{
+    __path_download_crl, __path_get_certificate_revocation, __path_revoke_certificate, CrlDocument,
+    CrlEntry, RevokeCertificateRequest,
+};
 use crate::authenticity::get_manufacturer::__path_get_manufacturer;
+use crate::authenticity::manufacturer_profile::{
+    __path_review_manufacturer_kyb, __path_update_manufacturer_profile, KybStatus, ReviewKybRequest,
+    UpdateManufacturerProfileRequest,
+};
+use crate::authenticity::signing_keys::{
+    __path_authorize_signing_key, __path_list_signing_keys, __path_revoke_signing_key,
+    __path_rotate_signing_key, AuthorizeKeyRequest, ManufacturerKeysResponse, RevokeKeyRequest,
+    RotateKeyRequest,
+};
+use crate::models::signed_document::SignedDocument;
+use crate::models::wallet_auth::WalletAuth;
 use crate::authenticity::is_username_exist::__path_manufacturer_name_exists;
 use crate::authenticity::is_username_exist::{IsExistsQuery, IsExistsResponse};
-use crate::contract_models::{Manufacturer, ManufacturerQuery, Item};
+use crate::contract_models::{ApiKey, CertificateRevocation, UserProfile, Manufacturer, ManufacturerKey, ManufacturerProfile, ManufacturerQuery, Item, ItemFlag, OwnershipClaim, Product, RecallCampaign, RecallItem, VerificationAlert, VerificationLog};
+use crate::models::pagination::{SortBy, SortOrder};
+use crate::analytics::manufacturer_analytics::{
+    __path_get_manufacturer_analytics, AnalyticsQuery, AnalyticsTotals, Bucket, ManufacturerAnalytics,
+    RegionScans, SeriesPoint,
+};
+use crate::analytics::clone_detection::AlertKind;
+use crate::analytics::verification_audit::{
+    __path_list_alerts, __path_list_verifications, __path_resolve_alert, AlertsQuery, AlertsResponse,
+    ResolveAlertRequest, VerificationLogsQuery, VerificationLogsResponse,
+};
+use crate::analytics::verification_log::VerificationResult;
+use crate::billing::api_keys::{
+    __path_create_api_key, __path_revoke_api_key, CreateApiKeyRequest, CreatedApiKey,
+    RevokeApiKeyRequest,
+};
+use crate::billing::gas_sponsorship::{
+    __path_get_gas_budget, __path_get_gas_report, __path_set_gas_budget, GasBudgetRequest,
+    GasBudgetStatus, GasOperation, GasReport, GasReportRequest, GasReportRow, SetGasBudgetRequest,
+    SponsorKind,
+};
+use crate::search::search_items::{__path_search, ItemHit, ManufacturerHit, SearchQuery, SearchResponse, UserHit};
 use crate::models::certificate_model::{
     CertificateData, Eip712Object, RegInput, SignedCertificate,
 };
@@ -9,10 +45,37 @@ use crate::ownership::{
     get_user_info::{__path_get_user, UserQuery, UserResponse},
     is_name_exist::{__path_user_exists, UserExistsQuery, UserExistsResponse},
     get_my_items::{__path_get_owner_items, ItemQuery, ItemsResponse},
-    transfer_ownership_code::{__path_transfer_ownership_code, OwnershipCodeResponse, GenerateOwnershipCodeQuery},
+    list_items::{__path_list_items, ListItemsQuery},
+    list_users::{__path_list_users, UsersQuery, UsersResponse},
+    list_claims::{__path_list_claims, ClaimsQuery, ClaimsResponse},
+    user_profile::{
+        __path_change_username, __path_update_user_profile, ChangeUsernameRequest,
+        ProfileVisibility, UpdateUserProfileRequest,
+    },
+    transfer_ownership_code::{__path_transfer_ownership_code, GenerateOwnershipCodeQuery},
     get_transfer_code::{__path_get_ownership_code, GetOwnershipCodeQuery},
-    revoke_ownership_code::{__path_revoke_ownership_code, OwnershipQuery, OwnershipResponse },
-    get_item::__path_get_item
+    revoke_ownership_code::{__path_revoke_ownership_code, OwnershipQuery},
+    get_item::{__path_get_item, ItemDetails},
+    item_status::ItemStatus,
+    item_history::{
+        __path_get_item_history, __path_export_item_history, HistoryEvent, HistoryEventKind,
+        HistoryQuery, ItemHistoryResponse,
+    },
+    ownership_proof::{
+        __path_get_ownership_proof, __path_verify_ownership_proof, OwnershipProofQuery,
+        OwnershipProofDocument, OwnershipProofVerification,
+    },
+    flag_item::{
+        __path_flag_item, __path_clear_item_flag, __path_get_item_flags,
+        FlagItemRequest, ClearItemFlagRequest, ItemFlagsResponse,
+    },
+    ownership_code::CodeFormat,
+    transfer_state::TransferState,
+    onchain_ownership_code::{
+        __path_commit_ownership_code, __path_claim_with_code, __path_revoke_onchain_code,
+        CommitOwnershipCodeRequest, CommitOwnershipCodeResponse, ClaimWithCodeRequest,
+        RevokeOnchainCodeRequest, PreparedTransaction,
+    },
 };
 use crate::services::{
     create_eip712::__path_create_certificate,
@@ -21,10 +84,26 @@ use crate::services::{
         __path_verify_signature,
     },
     qr_code::__path_generate_qr_code,
-    verify_authenticity::__path_verify_authenticity,
+    verify_authenticity::{__path_verify_authenticity, VerificationResponse},
     set_autheticity::{__path_set_authenticity, SetAuthenticityResponse, SetAuthenticityRequest},
-    claim_ownership::{__path_claim_ownership, ClaimOwnershipResponse, ClaimOwnershipRequest},
+    claim_ownership::{__path_claim_ownership, ClaimOwnershipRequest},
     create_item::{__path_create_item, CreateItemResponse, CreateItemRequest},
+    claim_item::{__path_claim_item, __path_get_claim_status, ClaimItemRequest, ClaimItemResponse, PendingClaim},
+    bulk_issuance::{__path_bulk_issue_certificates, BatchFormat, BulkIssuanceRequest, RowReport},
+};
+use crate::products::{
+    attribute_schema::{AttributeDefinition, AttributeType},
+    manage_product::{__path_create_product, __path_update_product, CreateProductRequest, UpdateProductRequest},
+    get_product::{__path_get_product, __path_list_products, ProductsQuery, ProductsResponse},
+};
+use crate::recalls::{
+    recall_campaign::{RecallReport, ItemRecall},
+    manage_recall::{__path_create_recall, __path_close_recall, CreateRecallRequest, CloseRecallRequest},
+    get_recall::{__path_get_recall, __path_list_recalls, RecallsQuery, RecallsResponse},
+    recall_notice::{
+        __path_get_recall_notices, __path_acknowledge_recall, RecallNoticesQuery, RecallNotice,
+        RecallNoticesResponse, AcknowledgeRecallRequest,
+    },
 };
 use crate::services::register_user::{__path_user_register, UserRegisterResponse, UserRegisterRequest};
 use utoipa::OpenApi;
@@ -41,18 +120,65 @@ use utoipa::OpenApi;
         create_certificate,
         generate_qr_code,
         get_manufacturer,
+        update_manufacturer_profile,
+        review_manufacturer_kyb,
+        list_signing_keys,
+        authorize_signing_key,
+        rotate_signing_key,
+        revoke_signing_key,
+        revoke_certificate,
+        get_certificate_revocation,
+        download_crl,
         manufacturer_name_exists,
         get_user,
         user_exists,
+        update_user_profile,
+        change_username,
         get_owner_items,
+        list_items,
+        list_users,
+        list_claims,
+        search,
+        get_manufacturer_analytics,
+        list_verifications,
+        list_alerts,
+        resolve_alert,
+        get_gas_budget,
+        set_gas_budget,
+        get_gas_report,
+        create_api_key,
+        revoke_api_key,
         transfer_ownership_code,
         get_ownership_code,
         revoke_ownership_code,
+        commit_ownership_code,
+        claim_with_code,
+        revoke_onchain_code,
         user_register,
         set_authenticity,
         claim_ownership,
         create_item,
+        claim_item,
+        get_claim_status,
+        bulk_issue_certificates,
         get_item,
+        flag_item,
+        clear_item_flag,
+        get_item_flags,
+        get_item_history,
+        export_item_history,
+        get_ownership_proof,
+        verify_ownership_proof,
+        create_product,
+        update_product,
+        get_product,
+        list_products,
+        create_recall,
+        close_recall,
+        get_recall,
+        list_recalls,
+        get_recall_notices,
+        acknowledge_recall,
     ),
     components(
         schemas(
@@ -62,28 +188,130 @@ use utoipa::OpenApi;
             Eip712Object,
             ManufacturerQuery,
             Manufacturer,
+            ManufacturerProfile,
+            KybStatus,
+            UpdateManufacturerProfileRequest,
+            ReviewKybRequest,
+            WalletAuth,
+            ManufacturerKey,
+            ManufacturerKeysResponse,
+            AuthorizeKeyRequest,
+            RotateKeyRequest,
+            RevokeKeyRequest,
+            CertificateRevocation,
+            RevokeCertificateRequest,
+            CrlEntry,
+            CrlDocument,
             IsExistsResponse,
             IsExistsQuery,
             UserResponse,
             UserQuery,
+            UserProfile,
+            ProfileVisibility,
+            UpdateUserProfileRequest,
+            ChangeUsernameRequest,
             UserExistsQuery,
             UserExistsResponse,
             ItemQuery,
             ItemsResponse,
+            ListItemsQuery,
+            UsersQuery,
+            UsersResponse,
+            ClaimsQuery,
+            ClaimsResponse,
+            OwnershipClaim,
+            SortBy,
+            SortOrder,
+            SearchQuery,
+            SearchResponse,
+            ItemHit,
+            ManufacturerHit,
+            UserHit,
+            AnalyticsQuery,
+            AnalyticsTotals,
+            Bucket,
+            ManufacturerAnalytics,
+            RegionScans,
+            SeriesPoint,
+            VerificationResult,
+            VerificationLogsQuery,
+            VerificationLogsResponse,
+            VerificationLog,
+            AlertsQuery,
+            AlertsResponse,
+            VerificationAlert,
+            AlertKind,
+            ResolveAlertRequest,
+            SponsorKind,
+            GasOperation,
+            GasBudgetRequest,
+            GasBudgetStatus,
+            SetGasBudgetRequest,
+            GasReportRequest,
+            GasReportRow,
+            GasReport,
+            ApiKey,
+            CreateApiKeyRequest,
+            CreatedApiKey,
+            RevokeApiKeyRequest,
             GenerateOwnershipCodeQuery,
-            OwnershipCodeResponse,
+            CodeFormat,
+            TransferState,
+            CommitOwnershipCodeRequest,
+            CommitOwnershipCodeResponse,
+            ClaimWithCodeRequest,
+            RevokeOnchainCodeRequest,
+            PreparedTransaction,
             GetOwnershipCodeQuery,
-            OwnershipResponse,
             OwnershipQuery,
             UserRegisterResponse,
             UserRegisterRequest,
             SetAuthenticityRequest,
             SetAuthenticityResponse,
             ClaimOwnershipRequest,
-            ClaimOwnershipResponse,
             CreateItemResponse,
             CreateItemRequest,
-            Item
+            ClaimItemRequest,
+            ClaimItemResponse,
+            PendingClaim,
+            BatchFormat,
+            BulkIssuanceRequest,
+            RowReport,
+            Product,
+            AttributeDefinition,
+            AttributeType,
+            CreateProductRequest,
+            UpdateProductRequest,
+            ProductsQuery,
+            ProductsResponse,
+            Item,
+            ItemStatus,
+            ItemFlag,
+            FlagItemRequest,
+            ClearItemFlagRequest,
+            ItemFlagsResponse,
+            VerificationResponse,
+            ItemDetails,
+            RecallCampaign,
+            RecallItem,
+            RecallReport,
+            ItemRecall,
+            CreateRecallRequest,
+            CloseRecallRequest,
+            RecallsQuery,
+            RecallsResponse,
+            RecallNoticesQuery,
+            RecallNotice,
+            RecallNoticesResponse,
+            AcknowledgeRecallRequest,
+            HistoryEvent,
+            HistoryEventKind,
+            HistoryQuery,
+            ItemHistoryResponse,
+            SignedDocument,
+            OwnershipProofQuery,
+            OwnershipProofDocument,
+            OwnershipProofVerification
         ),
         // responses()
     ),
@@ -1,7 +1,13 @@
-use chrono::NaiveDateTime;
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use crate::analytics::clone_detection::AlertKind;
+use crate::analytics::verification_log::VerificationResult;
+use crate::authenticity::manufacturer_profile::KybStatus;
+use crate::ownership::item_status::ItemStatus;
+use crate::ownership::user_profile::ProfileVisibility;
+use crate::products::attribute_schema::AttributeDefinition;
 
 #[derive(Queryable, Selectable, Insertable, Serialize, Deserialize)]
 #[diesel(table_name = crate::schema::contracts)]
@@ -10,7 +16,7 @@ pub struct Contract {
     pub contract_address: String,
     pub owner: String,
     pub tnx_hash: String,
-    pub created_at: String,
+    pub created_at: DateTime<Utc>,
 }
 #[derive(Queryable, Selectable, Insertable, Serialize, Deserialize)]
 #[diesel(table_name = crate::schema::contracts)]
@@ -18,7 +24,10 @@ pub struct NewContract {
     pub contract_address: String,
     pub owner: String,
     pub tnx_hash: String,
-    pub created_at: String,
+    pub created_at: DateTime<Utc>,
+    pub block_number: i64,
+    pub log_index: i32,
+    pub block_time: DateTime<Utc>,
 }
 
 #[derive(Queryable, Selectable, Insertable, Serialize, Deserialize)]
@@ -27,8 +36,43 @@ pub struct UserInfo {
     pub user_address: String,
     pub username: String,
     pub is_registered: bool,
-    pub created_at: String,
+    pub created_at: DateTime<Utc>,
     pub tnx_hash: String,
+    pub block_number: Option<i64>,
+    pub log_index: Option<i32>,
+    pub block_time: Option<DateTime<Utc>>,
+}
+
+// What the user chose to tell about themselves; only the username is on-chain
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, ToSchema)]
+#[diesel(table_name = crate::schema::users_info)]
+pub struct UserProfile {
+    #[schema(example = "John Doe")]
+    pub display_name: Option<String>,
+    #[schema(example = "https://cdn.example.com/avatars/john.png")]
+    pub avatar_url: Option<String>,
+    // where notifications go
+    #[schema(example = "john@example.com")]
+    pub email: Option<String>,
+    #[schema(value_type = ProfileVisibility, example = "public")]
+    pub profile_visibility: String,
+    // whether others may see the email on a public profile
+    #[schema(example = false)]
+    pub show_email: bool,
+    #[schema(value_type = Option<String>, example = "2025-10-08T09:00:00Z")]
+    pub profile_updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = crate::schema::users_info)]
+#[diesel(treat_none_as_null = true)]
+pub struct UserProfileChanges {
+    pub display_name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub email: Option<String>,
+    pub profile_visibility: String,
+    pub show_email: bool,
+    pub profile_updated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Queryable, Selectable, Serialize, Deserialize, Debug, ToSchema)]
@@ -41,8 +85,45 @@ pub struct Manufacturer {
     manufacturer_name: String,
     #[schema(example = true)]
     is_registered: bool,
-    #[schema(example = "2025-08-24T12:04:00Z")]
-    registered_at: String,
+    #[schema(value_type = String, example = "2025-08-24T12:04:00Z")]
+    registered_at: DateTime<Utc>,
+    #[diesel(embed)]
+    profile: ManufacturerProfile,
+}
+
+// What the manufacturer says about itself, and whether an admin has checked it
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, ToSchema)]
+#[diesel(table_name = crate::schema::manufacturers)]
+#[serde(rename_all = "camelCase")]
+pub struct ManufacturerProfile {
+    #[schema(example = "Samsung Electronics Co., Ltd.")]
+    legal_name: Option<String>,
+    #[schema(example = "https://cdn.example.com/samsung/logo.png")]
+    logo_url: Option<String>,
+    #[schema(example = "https://www.samsung.com")]
+    website: Option<String>,
+    #[schema(example = "support@samsung.com")]
+    contact_email: Option<String>,
+    #[schema(example = "+82 2 2053 3000")]
+    contact_phone: Option<String>,
+    #[schema(value_type = KybStatus, example = "verified")]
+    kyb_status: String,
+    #[schema(value_type = Option<String>, example = "2025-09-30T09:00:00Z")]
+    kyb_reviewed_at: Option<DateTime<Utc>>,
+    #[schema(value_type = Option<String>, example = "2025-09-29T16:20:00Z")]
+    profile_updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = crate::schema::manufacturers)]
+#[diesel(treat_none_as_null = true)]
+pub struct ManufacturerProfileChanges {
+    pub legal_name: Option<String>,
+    pub logo_url: Option<String>,
+    pub website: Option<String>,
+    pub contact_email: Option<String>,
+    pub contact_phone: Option<String>,
+    pub profile_updated_at: Option<DateTime<Utc>>,
 }
 
 
@@ -52,18 +133,32 @@ pub struct NewManufacturer {
     pub manufacturer_address: String,
     pub manufacturer_name: String,
     pub is_registered: bool,
-    pub registered_at: String,
-    pub tnx_hash: String
+    pub registered_at: DateTime<Utc>,
+    pub tnx_hash: String,
+    pub block_number: i64,
+    pub log_index: i32,
+    pub block_time: DateTime<Utc>,
 }
 
 #[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, ToSchema)]
 #[diesel(table_name = crate::schema::ownership_codes)]
 pub struct OwnershipCode {
-    pub ownership_code: String,
+    pub code_hash: String,
     pub item_id: String,
     pub item_owner: String,
     pub temp_owner: String,
-    pub created_at: String,
+    #[schema(value_type = String, example = "2025-08-26T00:37:12Z")]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = String, example = "2025-08-27T00:37:12Z")]
+    pub expires_at: DateTime<Utc>,
+    #[schema(example = "base32")]
+    pub code_format: String,
+    #[schema(example = "offered")]
+    pub status: String,
+    #[schema(value_type = String, example = "2025-08-26T00:37:12Z")]
+    pub status_changed_at: DateTime<Utc>,
+    #[schema(nullable = true)]
+    pub commit_tnx_hash: Option<String>,
 }
 
 #[derive(Queryable, Selectable, Serialize, Deserialize, ToSchema)]
@@ -77,7 +172,17 @@ pub struct Item {
     pub manufacturer: String,
     #[schema(nullable = true, value_type = Vec<Option<String>>)]
     pub metadata: Vec<Option<String>>,
-    pub created_at: String,
+    // block time of the creating transaction
+    #[schema(value_type = String, example = "2025-08-25T19:47:00Z")]
+    pub created_at: DateTime<Utc>,
+    #[schema(nullable = true)]
+    pub product_id: Option<i32>,
+    #[schema(nullable = true, example = 18234567)]
+    pub block_number: Option<i64>,
+    #[schema(nullable = true, example = 3)]
+    pub log_index: Option<i32>,
+    #[schema(nullable = true, value_type = Option<String>, example = "2025-08-25T19:47:00Z")]
+    pub block_time: Option<DateTime<Utc>>,
 }
 
 #[derive(Insertable, Serialize, Deserialize)]
@@ -90,19 +195,247 @@ pub struct NewItem {
     pub owner: String,
     pub manufacturer: String,
     pub metadata: Vec<String>,
-    pub created_at: String,
+    pub created_at: DateTime<Utc>,
     pub tnx_hash: String,
+    pub product_id: Option<i32>,
+    pub block_number: i64,
+    pub log_index: i32,
+    pub block_time: DateTime<Utc>,
 }
 
-#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[derive(Queryable, Selectable, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = crate::schema::products)]
+pub struct Product {
+    pub id: i32,
+    pub manufacturer_address: String,
+    #[schema(example = "GAL-S24-128-BLK")]
+    pub sku: String,
+    #[schema(example = "Galaxy S24")]
+    pub name: String,
+    #[schema(value_type = Vec<AttributeDefinition>)]
+    pub attribute_schema: serde_json::Value,
+    pub images: Vec<String>,
+    #[schema(value_type = String, example = "2025-09-12T09:00:00Z")]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = String, example = "2025-09-12T09:00:00Z")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::products)]
+pub struct NewProduct {
+    pub manufacturer_address: String,
+    pub sku: String,
+    pub name: String,
+    pub attribute_schema: serde_json::Value,
+    pub images: Vec<String>,
+}
+
+#[derive(Insertable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::certificate_products)]
+pub struct NewCertificateProduct {
+    pub unique_id: String,
+    pub product_id: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::issued_certificates)]
+pub struct NewIssuedCertificate {
+    pub unique_id: String,
+    pub manufacturer_address: String,
+    pub cert_hash: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::verification_logs)]
+pub struct NewVerificationLog {
+    pub unique_id: String,
+    pub cert_hash: Option<String>,
+    pub claimed_manufacturer: String,
+    pub signer: Option<String>,
+    pub result: String,
+    pub region: Option<String>,
+    pub client_ip: Option<String>,
+    pub api_key_hash: Option<String>,
+}
+
+#[derive(Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = crate::schema::verification_logs)]
+pub struct VerificationLog {
+    pub id: i32,
+    #[schema(example = "item123")]
+    pub unique_id: String,
+    // EIP-712 digest of the certificate, when it could be computed
+    #[schema(nullable = true, example = "0x9f2c...e1")]
+    pub cert_hash: Option<String>,
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub claimed_manufacturer: String,
+    #[schema(nullable = true, example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub signer: Option<String>,
+    #[schema(value_type = VerificationResult)]
+    pub result: String,
+    #[schema(nullable = true, example = "NG")]
+    pub region: Option<String>,
+    // client_ip is kept for clone detection only and never served back
+    #[schema(nullable = true)]
+    pub api_key_hash: Option<String>,
+    #[schema(value_type = String, example = "2025-09-29T10:00:00Z")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = crate::schema::verification_alerts)]
+pub struct VerificationAlert {
+    pub id: i32,
+    #[schema(example = "item123")]
+    pub unique_id: String,
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub manufacturer_address: String,
+    #[schema(value_type = AlertKind)]
+    pub kind: String,
+    #[schema(example = "Scanned from 4 regions within 60 minutes")]
+    pub details: String,
+    #[schema(example = 4)]
+    pub scan_count: i32,
+    #[schema(value_type = String, example = "2025-09-29T10:00:00Z")]
+    pub first_seen: DateTime<Utc>,
+    #[schema(value_type = String, example = "2025-09-29T10:42:00Z")]
+    pub last_seen: DateTime<Utc>,
+    #[schema(nullable = true)]
+    pub resolved_by: Option<String>,
+    #[schema(nullable = true, value_type = Option<String>)]
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::verification_alerts)]
+pub struct NewVerificationAlert {
+    pub unique_id: String,
+    pub manufacturer_address: String,
+    pub kind: String,
+    pub details: String,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, ToSchema, Clone)]
+#[diesel(table_name = crate::schema::item_flags)]
+pub struct ItemFlag {
+    pub id: i32,
+    pub item_id: String,
+    #[schema(value_type = ItemStatus, example = "stolen")]
+    pub status: String,
+    #[schema(example = "Taken from my car on 2025-09-14")]
+    pub reason: String,
+    #[schema(example = json!(["https://police.example.com/reports/48213"]))]
+    pub evidence: Vec<String>,
+    pub flagged_by: String,
+    #[schema(value_type = String, example = "2025-09-15T10:00:00Z")]
+    pub created_at: DateTime<Utc>,
+    #[schema(nullable = true)]
+    pub cleared_by: Option<String>,
+    #[schema(nullable = true, value_type = Option<String>)]
+    pub cleared_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Insertable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::item_flags)]
+pub struct NewItemFlag {
+    pub item_id: String,
+    pub status: String,
+    pub reason: String,
+    pub evidence: Vec<String>,
+    pub flagged_by: String,
+}
+
+// One row of the item_history_events view
+#[derive(Queryable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::item_history_events)]
+pub struct ItemHistoryEvent {
+    pub event_id: String,
+    pub kind: String,
+    pub from_address: Option<String>,
+    pub to_address: Option<String>,
+    pub status: Option<String>,
+    pub reason: Option<String>,
+    pub tnx_hash: Option<String>,
+    pub block_number: Option<i64>,
+    pub block_time: Option<DateTime<Utc>>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, ToSchema, Clone)]
+#[diesel(table_name = crate::schema::recall_campaigns)]
+pub struct RecallCampaign {
+    pub id: i32,
+    pub manufacturer_address: String,
+    #[schema(example = "Battery overheating")]
+    pub title: String,
+    #[schema(example = "Cells from one supplier batch may overheat while charging")]
+    pub reason: String,
+    #[schema(nullable = true, example = "Free battery replacement at any service center")]
+    pub remedy: Option<String>,
+    #[schema(nullable = true, example = "SN100000")]
+    pub serial_from: Option<String>,
+    #[schema(nullable = true, example = "SN100999")]
+    pub serial_to: Option<String>,
+    #[schema(nullable = true)]
+    pub product_id: Option<i32>,
+    #[schema(nullable = true, example = 1725148800)]
+    pub produced_from: Option<i64>,
+    #[schema(nullable = true, example = 1727740799)]
+    pub produced_to: Option<i64>,
+    #[schema(value_type = String, example = "2025-09-17T09:00:00Z")]
+    pub created_at: DateTime<Utc>,
+    #[schema(nullable = true, value_type = Option<String>)]
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Insertable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::recall_campaigns)]
+pub struct NewRecallCampaign {
+    pub manufacturer_address: String,
+    pub title: String,
+    pub reason: String,
+    pub remedy: Option<String>,
+    pub serial_from: Option<String>,
+    pub serial_to: Option<String>,
+    pub product_id: Option<i32>,
+    pub produced_from: Option<i64>,
+    pub produced_to: Option<i64>,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, ToSchema, Clone)]
+#[diesel(table_name = crate::schema::recall_items)]
+pub struct RecallItem {
+    pub campaign_id: i32,
+    pub item_id: String,
+    #[schema(value_type = String, example = "2025-09-17T09:00:00Z")]
+    pub matched_at: DateTime<Utc>,
+    #[schema(nullable = true)]
+    pub acknowledged_by: Option<String>,
+    #[schema(nullable = true, value_type = Option<String>)]
+    pub acknowledged_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, ToSchema)]
 #[diesel(table_name = crate::schema::ownership_claims)]
 pub struct OwnershipClaim {
     pub id: i32,
+    #[schema(example = "item123")]
     pub item_id: String,
+    #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef12")]
     pub new_owner: String,
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
     pub old_owner: String,
+    #[schema(example = "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef")]
     pub tnx_hash: String,
-    pub created_at: String,
+    #[schema(value_type = String, example = "2025-09-18T03:10:04Z")]
+    pub created_at: DateTime<Utc>,
+    #[schema(nullable = true, example = 18240012)]
+    pub block_number: Option<i64>,
+    #[schema(nullable = true, example = 7)]
+    pub log_index: Option<i32>,
+    #[schema(nullable = true, value_type = Option<String>)]
+    pub block_time: Option<DateTime<Utc>>,
 }
 
 #[derive(Insertable, Serialize, Deserialize)]
@@ -112,7 +445,10 @@ pub struct NewOwnershipClaim {
     pub new_owner: String,
     pub old_owner: String,
     pub tnx_hash: String,
-    pub created_at: String,
+    pub created_at: DateTime<Utc>,
+    pub block_number: i64,
+    pub log_index: i32,
+    pub block_time: DateTime<Utc>,
 }
 
 #[derive(Queryable, Insertable, Serialize, Deserialize)]
@@ -121,7 +457,10 @@ pub struct CodeRevokation {
     pub id: i32,
     pub item_hash: String,
     pub tnx_hash: String,
-    pub created_at: String,
+    pub created_at: DateTime<Utc>,
+    pub block_number: Option<i64>,
+    pub log_index: Option<i32>,
+    pub block_time: Option<DateTime<Utc>>,
 }
 
 #[derive(Insertable, Serialize, Deserialize)]
@@ -129,7 +468,18 @@ pub struct CodeRevokation {
 pub struct NewCodeRevokation {
     pub item_hash: String,
     pub tnx_hash: String,
-    pub created_at: String,
+    pub created_at: DateTime<Utc>,
+    pub block_number: i64,
+    pub log_index: i32,
+    pub block_time: DateTime<Utc>,
+}
+
+#[derive(Insertable, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::ownership_mismatches)]
+pub struct NewOwnershipMismatch {
+    pub item_id: String,
+    pub db_owner: String,
+    pub chain_owner: Option<String>,
 }
 
 #[derive(Queryable, Selectable, Insertable, Serialize, Deserialize)]
@@ -138,7 +488,10 @@ pub struct AuthenticitySetting {
     pub id: i32,
     pub authenticity_address: String,
     pub tnx_hash: String,
-    pub created_at: String,
+    pub created_at: DateTime<Utc>,
+    pub block_number: Option<i64>,
+    pub log_index: Option<i32>,
+    pub block_time: Option<DateTime<Utc>>,
 }
 
 #[derive(Insertable, Serialize, Deserialize)]
@@ -146,7 +499,10 @@ pub struct AuthenticitySetting {
 pub struct NewAuthenticitySetting {
     pub authenticity_address: String,
     pub tnx_hash: String,
-    pub created_at: String,
+    pub created_at: DateTime<Utc>,
+    pub block_number: i64,
+    pub log_index: i32,
+    pub block_time: DateTime<Utc>,
 }
 
 
@@ -166,4 +522,103 @@ pub struct ManufacturerQuery {
 //     Address(String),
 //     #[schema(example = "john_doe")]
 //     Username(String),
-// }
\ No newline at end of file
+// }
+#[derive(Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = crate::schema::signing_keys)]
+pub struct ManufacturerKey {
+    #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef12")]
+    pub key_address: String,
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub manufacturer_address: String,
+    #[schema(value_type = String, example = "2025-10-01T00:00:00Z")]
+    pub valid_from: DateTime<Utc>,
+    #[schema(nullable = true, value_type = Option<String>, example = "2026-10-01T00:00:00Z")]
+    pub valid_until: Option<DateTime<Utc>>,
+    #[schema(nullable = true, value_type = Option<String>)]
+    pub revoked_at: Option<DateTime<Utc>>,
+    #[schema(nullable = true)]
+    pub rotated_to: Option<String>,
+    #[schema(example = "0x5d1f6a0e2b0c4f3e9a7d8c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f")]
+    pub tnx_hash: String,
+    #[schema(value_type = String, example = "2025-10-01T00:00:12Z")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::signing_keys)]
+pub struct NewManufacturerKey {
+    pub key_address: String,
+    pub manufacturer_address: String,
+    pub valid_from: DateTime<Utc>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub tnx_hash: String,
+    pub block_number: i64,
+    pub log_index: i32,
+    pub block_time: DateTime<Utc>,
+}
+
+#[derive(Queryable, Selectable, Insertable, Serialize, ToSchema)]
+#[diesel(table_name = crate::schema::certificate_revocations)]
+pub struct CertificateRevocation {
+    // EIP-712 digest of the certificate
+    #[schema(example = "0x5d1f6a0e2b0c4f3e9a7d8c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f")]
+    pub cert_hash: String,
+    #[schema(example = "item123")]
+    pub unique_id: String,
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub manufacturer_address: String,
+    #[schema(example = "Unit stolen from the warehouse before sale")]
+    pub reason: String,
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub revoked_by: String,
+    #[schema(value_type = String, example = "2025-10-06T09:00:00Z")]
+    pub revoked_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::schema::gas_ledger)]
+pub struct NewGasLedgerEntry {
+    pub tnx_hash: String,
+    pub operation: String,
+    pub sponsor_kind: String,
+    pub sponsor: String,
+    pub gas_used: i64,
+    pub gas_price_wei: i64,
+    pub fee_wei: i64,
+    pub succeeded: bool,
+}
+
+#[derive(Queryable, Selectable, Insertable, AsChangeset, Debug)]
+#[diesel(table_name = crate::schema::gas_budgets)]
+pub struct GasBudget {
+    pub sponsor_kind: String,
+    pub sponsor: String,
+    pub monthly_limit_wei: i64,
+    pub updated_by: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::schema::gas_reservations)]
+pub struct NewGasReservation {
+    pub sponsor_kind: String,
+    pub sponsor: String,
+    pub fee_wei: i64,
+}
+
+#[derive(Queryable, Selectable, Insertable, Serialize, ToSchema)]
+#[diesel(table_name = crate::schema::api_keys)]
+pub struct ApiKey {
+    // keccak256 of the key; also its sponsor id in the gas budget and ledger
+    #[schema(example = "0x5d1f6a0e2b0c4f3e9a7d8c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f")]
+    pub key_hash: String,
+    #[schema(example = "Acme retail scanner app")]
+    pub name: String,
+    #[schema(example = "0x1234567890AbcdEF1234567890aBcdef12345678")]
+    pub created_by: String,
+    #[schema(value_type = String, example = "2025-10-16T09:00:00Z")]
+    pub created_at: DateTime<Utc>,
+    // keys stop working for good once revoked
+    #[schema(value_type = Option<String>, example = "2025-11-01T09:00:00Z")]
+    pub revoked_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use ethers::contract::LogMeta;
+use ethers::providers::Middleware;
+use eyre::Result;
+use std::collections::HashMap;
+
+// blocks are looked up once per listener; past this many the cache starts over
+const MAX_CACHED_BLOCKS: usize = 10_000;
+
+// Where an indexed log sits on chain. Every row the indexer writes carries it, and its
+// block time is the row's created_at.
+#[derive(Clone, Copy, Debug)]
+pub struct LogPosition {
+    pub block_number: i64,
+    pub log_index: i32,
+    pub block_time: DateTime<Utc>,
+}
+
+impl LogPosition {
+    // Whether this log comes after the one a row was last stamped with, so replayed or
+    // backfilled logs cannot undo a later change. Rows without a stamp take any log.
+    pub fn is_after(&self, block_number: Option<i64>, log_index: Option<i32>) -> bool {
+        match (block_number, log_index) {
+            (Some(block_number), Some(log_index)) => {
+                (self.block_number, self.log_index) > (block_number, log_index)
+            }
+            _ => true,
+        }
+    }
+}
+
+// Block timestamps by block number. A chunk of historical logs or a burst of streamed
+// ones usually shares a handful of blocks, so each is only fetched once.
+#[derive(Default)]
+pub struct BlockTimes {
+    cache: HashMap<u64, DateTime<Utc>>,
+}
+
+impl BlockTimes {
+    pub async fn position<M: Middleware>(&mut self, client: &M, meta: &LogMeta) -> Result<LogPosition> {
+        let block_number = meta.block_number.as_u64();
+
+        Ok(LogPosition {
+            block_number: block_number as i64,
+            log_index: meta.log_index.as_u32() as i32,
+            block_time: self.block_time(client, block_number).await?,
+        })
+    }
+
+    async fn block_time<M: Middleware>(&mut self, client: &M, block_number: u64) -> Result<DateTime<Utc>> {
+        if let Some(block_time) = self.cache.get(&block_number) {
+            return Ok(*block_time);
+        }
+
+        let block = client
+            .get_block(block_number)
+            .await
+            .map_err(|e| {
+                eprintln!("Failed to fetch block {}: {:?}", block_number, e.to_string());
+                eyre::eyre!("Failed to fetch block {}: {}", block_number, e)
+            })?
+            .ok_or_else(|| eyre::eyre!("Block {} not found", block_number))?;
+
+        let block_time = DateTime::from_timestamp(block.timestamp.as_u64() as i64, 0)
+            .ok_or_else(|| eyre::eyre!("Invalid timestamp for block {}", block_number))?;
+
+        if self.cache.len() >= MAX_CACHED_BLOCKS {
+            self.cache.clear();
+        }
+        self.cache.insert(block_number, block_time);
+
+        Ok(block_time)
+    }
+}
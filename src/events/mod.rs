@@ -1 +1,2 @@
 pub mod ownership_event_listener;
+pub mod block_times;
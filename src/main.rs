@@ -1,4 +1,5 @@
 use config::server::server;
+use std::env;
 
 mod config;
 mod models;
@@ -9,8 +10,22 @@ mod events;
 mod authenticity;
 mod ownership;
 mod contract_models;
+mod cli;
+mod products;
+mod recalls;
+mod search;
+mod analytics;
+mod billing;
 
 #[tokio::main]
 async fn main() {
+    // `bulk-issue` signs a certificate batch locally instead of starting the server
+    if env::args().nth(1).as_deref() == Some("bulk-issue") {
+        cli::bulk_issue::run(env::args().skip(2).collect())
+            .await
+            .expect("Error!");
+        return;
+    }
+
     server().await.expect("Error!");
 }
@@ -1,8 +1,11 @@
 use crate::authenticity::authenticity_abi::authenticity;
+use crate::models::metadata::{canonical_metadata, sort_legacy_metadata, Attributes};
+use crate::ownership::ownership_abi;
 use crate::utility::to_meta_hash;
 use ethabi::ethereum_types::{Address, U256};
 use ethers::contract::EthEvent;
 use ethers::types::transaction::eip712::{EIP712Domain, Eip712, Eip712Error};
+use ethers::types::Signature;
 use ethers::utils::keccak256;
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
@@ -103,6 +106,23 @@ impl Eip712 for Certificate {
     }
 }
 
+impl Certificate {
+    // Recovers the address that signed this certificate's EIP-712 digest
+    pub fn recover_signer(&self, signature: &str) -> anyhow::Result<Address> {
+        let signature_bytes = hex::decode(signature.trim_start_matches("0x"))
+            .map_err(|_| anyhow::anyhow!("Invalid signature format"))?;
+        let signature = Signature::try_from(signature_bytes.as_slice())
+            .map_err(|_| anyhow::anyhow!("Invalid signature format"))?;
+        let digest = self
+            .encode_eip712()
+            .map_err(|e| anyhow::anyhow!("Failed to encode certificate: {}", e))?;
+
+        signature
+            .recover(digest)
+            .map_err(|_| anyhow::anyhow!("Invalid signature"))
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, ToSchema, Validate)]
 pub struct SignedCertificate {
     #[validate(length(min = 1))]
@@ -172,6 +192,20 @@ impl From<Certificate> for authenticity::Certificate {
     }
 }
 
+impl From<Certificate> for ownership_abi::Certificate {
+    fn from(cert: Certificate) -> Self {
+        Self {
+            name: cert.name,
+            unique_id: cert.unique_id,
+            serial: cert.serial,
+            date: cert.date,
+            owner: cert.owner,
+            metadata: cert.metadata,
+            metadata_hash: cert.metadata_hash,
+        }
+    }
+}
+
 // Custom EIP712Domain for ToSchema
 #[derive(Clone, Serialize, Deserialize, Debug, ToSchema)]
 pub struct CustomEIP712Domain {
@@ -208,6 +242,8 @@ pub struct Eip712Object {
     pub domain: CustomEIP712Domain,
     pub types: serde_json::Value,
     pub value: serde_json::Value,
+    // metadata strings behind value.metadataHash, to send back with the signature
+    pub metadata: Vec<String>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, ToSchema)]
@@ -218,13 +254,43 @@ pub struct CertificateData {
     pub date: u64,
     #[schema(value_type = String, format = Binary)]
     pub owner: String,
+    #[serde(default)]
     pub metadata: Vec<String>,
+    // typed alternative to `metadata`, turned into canonical "key: value" strings
+    #[serde(default)]
+    #[schema(nullable = true, value_type = Option<Object>, example = json!({"color": "blue", "storage_gb": 128}))]
+    pub attributes: Option<Attributes>,
+    // product template to fill the certificate from and validate its metadata against; the
+    // item is linked to it when the signed certificate is issued with the same product_id
+    #[serde(default)]
+    #[schema(nullable = true, example = 1)]
+    pub product_id: Option<i32>,
+}
+
+impl CertificateData {
+    // Replaces `attributes` with their canonical metadata strings (see models::metadata)
+    pub fn resolve_metadata(&mut self) -> anyhow::Result<()> {
+        if let Some(attributes) = self.attributes.take() {
+            if !self.metadata.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "Invalid metadata: send either metadata or attributes, not both"
+                ));
+            }
+            self.metadata =
+                canonical_metadata(&attributes).map_err(|e| anyhow::anyhow!("{}", e))?;
+        } else {
+            // legacy "key: value" strings hash in the same order as attributes would
+            sort_legacy_metadata(&mut self.metadata);
+        }
+        Ok(())
+    }
 }
 
 impl TryFrom<CertificateData> for Certificate {
     type Error = anyhow::Error;
-    fn try_from(dto: CertificateData) -> Result<Self, Self::Error> {
-        
+    fn try_from(mut dto: CertificateData) -> Result<Self, Self::Error> {
+        dto.resolve_metadata()?;
+
         Ok(Certificate {
             name: dto.name,
             unique_id: dto.unique_id,
@@ -0,0 +1,181 @@
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+// Certificate metadata as typed key/value pairs.
+//
+// Hashing scheme (what ends up as the certificate's metadataHash):
+//   1. keys are trimmed and lowercased; values are trimmed strings, numbers in their JSON form
+//      with integer-valued floats written as integers (128.0 -> 128) and booleans as
+//      `true` / `false`
+//   2. every pair becomes the string "key: value"
+//   3. the strings are sorted by key (byte order); a legacy `metadata` array is sorted by the
+//      text before its first ": ", then by the rest, so it orders like attributes would
+//   4. metadataHash = keccak256(abi.encode(string[]))
+// Step 4 is exactly `utility::to_meta_hash`, so the on-chain metadataHash and the stored `metadata`
+// strings keep their current form; only the ordering and spelling become canonical.
+pub type Attributes = BTreeMap<String, AttributeValue>;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum AttributeValue {
+    Bool(bool),
+    #[schema(value_type = f64)]
+    Number(serde_json::Number),
+    String(String),
+}
+
+impl AttributeValue {
+    fn canonical(&self) -> String {
+        match self {
+            AttributeValue::Bool(value) => value.to_string(),
+            AttributeValue::Number(value) => match value.as_f64() {
+                // 2^53: beyond it an f64 no longer holds every integer exactly
+                Some(float)
+                    if value.is_f64()
+                        && float.fract() == 0.0
+                        && float.abs() < 9_007_199_254_740_992.0 =>
+                {
+                    (float as i64).to_string()
+                }
+                _ => value.to_string(),
+            },
+            AttributeValue::String(value) => value.trim().to_string(),
+        }
+    }
+}
+
+pub fn canonical_key(key: &str) -> String {
+    key.trim().to_lowercase()
+}
+
+// "key: value" for a single attribute, as stored in items.metadata
+pub fn canonical_entry(key: &str, value: &str) -> String {
+    format!("{}: {}", canonical_key(key), value.trim())
+}
+
+// Legacy "key: value" strings in the order canonical_metadata produces. Whole strings would
+// not do: "size2: b" sorts before "size: a", while key `size` sorts before `size2`.
+pub fn sort_legacy_metadata(metadata: &mut [String]) {
+    metadata.sort_by(|a, b| legacy_sort_key(a).cmp(&legacy_sort_key(b)));
+}
+
+fn legacy_sort_key(entry: &str) -> (&str, &str) {
+    entry.split_once(": ").unwrap_or((entry, ""))
+}
+
+// Metadata strings in canonical order; rejects keys that collide once normalized
+pub fn canonical_metadata(attributes: &Attributes) -> Result<Vec<String>> {
+    let mut canonical = BTreeMap::new();
+
+    for (key, value) in attributes {
+        let key = canonical_key(key);
+        if key.is_empty() || key.contains(':') {
+            return Err(eyre::eyre!(
+                "Invalid metadata: attribute names must be non-empty and contain no ':'"
+            ));
+        }
+        if canonical.insert(key.clone(), value.canonical()).is_some() {
+            return Err(eyre::eyre!("Invalid metadata: {} is set more than once", key));
+        }
+    }
+
+    Ok(canonical
+        .into_iter()
+        .map(|(key, value)| canonical_entry(&key, &value))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::certificate_model::CertificateData;
+    use crate::utility::to_meta_hash;
+    use serde_json::json;
+
+    fn attributes(value: serde_json::Value) -> Attributes {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn integer_valued_floats_hash_like_integers() {
+        let float = canonical_metadata(&attributes(json!({"storage_gb": 128.0}))).unwrap();
+        let integer = canonical_metadata(&attributes(json!({"storage_gb": 128}))).unwrap();
+        assert_eq!(float, vec!["storage_gb: 128".to_string()]);
+        assert_eq!(float, integer);
+
+        let fraction = canonical_metadata(&attributes(json!({"weight": 0.5}))).unwrap();
+        assert_eq!(fraction, vec!["weight: 0.5".to_string()]);
+        let negative = canonical_metadata(&attributes(json!({"offset": -2.0}))).unwrap();
+        assert_eq!(negative, vec!["offset: -2".to_string()]);
+    }
+
+    #[test]
+    fn keys_are_normalized_and_sorted() {
+        let metadata = canonical_metadata(&attributes(json!({
+            " Size ": "  medium ",
+            "Color": "blue",
+            "waterproof": true
+        })))
+        .unwrap();
+        assert_eq!(metadata, vec!["color: blue", "size: medium", "waterproof: true"]);
+    }
+
+    #[test]
+    fn rejects_keys_that_collide_or_contain_colons() {
+        assert!(canonical_metadata(&attributes(json!({"Color": "blue", "color ": "red"}))).is_err());
+        assert!(canonical_metadata(&attributes(json!({"a:b": "c"}))).is_err());
+        assert!(canonical_metadata(&attributes(json!({"  ": "c"}))).is_err());
+    }
+
+    #[test]
+    fn legacy_metadata_is_sorted() {
+        let mut data = CertificateData {
+            name: "Widget".to_string(),
+            unique_id: "item123".to_string(),
+            serial: "SN1".to_string(),
+            date: 1,
+            owner: "0x1234567890abcdef1234567890abcdef12345678".to_string(),
+            metadata: vec!["size: medium".to_string(), "color: blue".to_string()],
+            attributes: None,
+            product_id: None,
+        };
+        data.resolve_metadata().unwrap();
+        assert_eq!(data.metadata, vec!["color: blue", "size: medium"]);
+
+        let mut from_attributes = CertificateData {
+            metadata: Vec::new(),
+            attributes: Some(attributes(json!({"size": "medium", "color": "blue"}))),
+            ..data.clone()
+        };
+        from_attributes.resolve_metadata().unwrap();
+        assert_eq!(to_meta_hash(&from_attributes.metadata), to_meta_hash(&data.metadata));
+
+        // a key that prefixes another one sorts first, as it does among attributes
+        let mut prefixed = CertificateData {
+            metadata: vec!["size2: b".to_string(), "size: a".to_string()],
+            ..data.clone()
+        };
+        prefixed.resolve_metadata().unwrap();
+        assert_eq!(prefixed.metadata, vec!["size: a", "size2: b"]);
+
+        let mut prefixed_attributes = CertificateData {
+            metadata: Vec::new(),
+            attributes: Some(attributes(json!({"size2": "b", "size": "a"}))),
+            ..data.clone()
+        };
+        prefixed_attributes.resolve_metadata().unwrap();
+        assert_eq!(to_meta_hash(&prefixed_attributes.metadata), to_meta_hash(&prefixed.metadata));
+    }
+
+    #[test]
+    fn metadata_hash_is_keccak_of_abi_encoded_strings() {
+        let metadata =
+            canonical_metadata(&attributes(json!({"color": "blue", "storage_gb": 128.0}))).unwrap();
+        assert_eq!(
+            hex::encode(to_meta_hash(&metadata)),
+            "fa49dd5b1844892995151de6e992f161e3e0a2dce92102c5ddc0287489b30e83"
+        );
+    }
+}
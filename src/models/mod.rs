@@ -1,4 +1,9 @@
 pub(crate) mod certificate_model;
 pub(crate) mod emitted_events;
+pub(crate) mod metadata;
+pub(crate) mod pagination;
 pub(crate) mod router_path;
+pub(crate) mod signed_document;
+pub(crate) mod username_policy;
+pub(crate) mod wallet_auth;
 pub mod auth;
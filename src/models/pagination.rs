@@ -0,0 +1,159 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Keyset pagination shared by the list endpoints. A cursor is the sort key and id of the
+// last row of a page, so pages stay stable while new rows are indexed. Cursors are opaque
+// to clients (hex-encoded JSON) and only valid for the sort they were issued for.
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortBy {
+    #[default]
+    CreatedAt,
+    Name,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+#[derive(Clone, Debug)]
+pub enum CursorKey {
+    CreatedAt(DateTime<Utc>),
+    Name(String),
+}
+
+#[derive(Serialize, Deserialize)]
+struct Cursor {
+    sort: SortBy,
+    order: SortOrder,
+    // RFC 3339 when sorting by created_at
+    key: String,
+    id: String,
+}
+
+pub struct Page {
+    pub sort: SortBy,
+    pub order: SortOrder,
+    pub limit: i64,
+    // sort key and id of the last row already returned
+    pub after: Option<(CursorKey, String)>,
+}
+
+impl Page {
+    pub fn new(
+        cursor: Option<&str>,
+        limit: Option<i64>,
+        sort: Option<SortBy>,
+        order: Option<SortOrder>,
+    ) -> eyre::Result<Page> {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+            return Err(eyre::eyre!(
+                "Invalid pagination: limit must be between 1 and {}",
+                MAX_PAGE_SIZE
+            ));
+        }
+        let sort = sort.unwrap_or_default();
+        let order = order.unwrap_or_default();
+
+        let after = match cursor.filter(|cursor| !cursor.is_empty()) {
+            Some(cursor) => {
+                let cursor = hex::decode(cursor)
+                    .ok()
+                    .and_then(|bytes| serde_json::from_slice::<Cursor>(&bytes).ok())
+                    .ok_or_else(|| eyre::eyre!("Invalid pagination: malformed cursor"))?;
+                if cursor.sort != sort || cursor.order != order {
+                    return Err(eyre::eyre!(
+                        "Invalid pagination: cursor was issued for a different sort"
+                    ));
+                }
+                let key = match sort {
+                    SortBy::CreatedAt => DateTime::parse_from_rfc3339(&cursor.key)
+                        .map(|at| CursorKey::CreatedAt(at.with_timezone(&Utc)))
+                        .map_err(|_| eyre::eyre!("Invalid pagination: malformed cursor"))?,
+                    SortBy::Name => CursorKey::Name(cursor.key),
+                };
+                Some((key, cursor.id))
+            }
+            None => None,
+        };
+
+        Ok(Page {
+            sort,
+            order,
+            limit,
+            after,
+        })
+    }
+
+    // `rows` must have been loaded with `limit + 1` (see keyset_page!); the extra row only
+    // tells whether another page exists. `key` gives a row's created_at, name and id.
+    pub fn finish<T>(
+        &self,
+        mut rows: Vec<T>,
+        key: impl Fn(&T) -> (DateTime<Utc>, String, String),
+    ) -> (Vec<T>, Option<String>) {
+        if rows.len() as i64 <= self.limit {
+            return (rows, None);
+        }
+        rows.truncate(self.limit as usize);
+
+        let next_cursor = rows.last().map(|last| {
+            let (created_at, name, id) = key(last);
+            let cursor = Cursor {
+                sort: self.sort,
+                order: self.order,
+                key: match self.sort {
+                    SortBy::CreatedAt => created_at.to_rfc3339(),
+                    SortBy::Name => name,
+                },
+                id,
+            };
+            hex::encode(serde_json::to_vec(&cursor).unwrap_or_default())
+        });
+
+        (rows, next_cursor)
+    }
+}
+
+// Orders a boxed query by the page's sort with `$id` as tie-breaker, skips everything up to
+// the cursor and fetches one row more than the page size. Must be used inside a function
+// returning eyre::Result, since a cursor id that doesn't parse as `$id_ty` is an error.
+macro_rules! keyset_page {
+    ($query:expr, $page:expr, $created_at:expr, $name:expr, $id:expr, $id_ty:ty) => {{
+        use $crate::models::pagination::{CursorKey, SortBy, SortOrder};
+        let page: &$crate::models::pagination::Page = $page;
+        let mut query = match (page.sort, page.order) {
+            (SortBy::CreatedAt, SortOrder::Asc) => $query.order(($created_at.asc(), $id.asc())),
+            (SortBy::CreatedAt, SortOrder::Desc) => $query.order(($created_at.desc(), $id.desc())),
+            (SortBy::Name, SortOrder::Asc) => $query.order(($name.asc(), $id.asc())),
+            (SortBy::Name, SortOrder::Desc) => $query.order(($name.desc(), $id.desc())),
+        };
+        if let Some((key, id)) = &page.after {
+            let id: $id_ty = id
+                .parse()
+                .map_err(|_| eyre::eyre!("Invalid pagination: malformed cursor"))?;
+            query = match (key, page.order) {
+                (CursorKey::CreatedAt(at), SortOrder::Asc) => query
+                    .filter($created_at.gt(*at).or($created_at.eq(*at).and($id.gt(id)))),
+                (CursorKey::CreatedAt(at), SortOrder::Desc) => query
+                    .filter($created_at.lt(*at).or($created_at.eq(*at).and($id.lt(id)))),
+                (CursorKey::Name(name), SortOrder::Asc) => query
+                    .filter($name.gt(name.clone()).or($name.eq(name.clone()).and($id.gt(id)))),
+                (CursorKey::Name(name), SortOrder::Desc) => query
+                    .filter($name.lt(name.clone()).or($name.eq(name.clone()).and($id.lt(id)))),
+            };
+        }
+        query.limit(page.limit + 1)
+    }};
+}
+pub(crate) use keyset_page;
@@ -0,0 +1,50 @@
+use ethers::signers::Signer;
+use ethers::types::{Address, Signature};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+// A JSON document signed by the backend wallet. Offline: recover the EIP-191 signer over
+// the compact JSON of `document` with object keys sorted (e.g. `jq -cS .document`) and
+// compare it with the backend address. serde_json::Value keeps object keys sorted, so
+// serialising `document` again gives back exactly the signed bytes.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SignedDocument {
+    #[schema(value_type = Object)]
+    pub document: serde_json::Value,
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub signer: String,
+    #[schema(example = "0x5d1f...1b")]
+    pub signature: String,
+}
+
+pub async fn signed_document<S: Signer>(
+    wallet: &S,
+    document: &impl Serialize,
+) -> eyre::Result<SignedDocument> {
+    let document = serde_json::to_value(document)?;
+    let payload = serde_json::to_vec(&document)?;
+
+    let signature = wallet
+        .sign_message(&payload)
+        .await
+        .map_err(|e| eyre::eyre!("Failed to sign document: {}", e))?;
+
+    Ok(SignedDocument {
+        document,
+        signer: format!("{:?}", wallet.address()),
+        signature: format!("0x{}", signature),
+    })
+}
+
+
+impl SignedDocument {
+    // Address that signed `document`; `signer` is only what the document claims
+    pub fn recover_signer(&self) -> eyre::Result<Address> {
+        let signature = Signature::from_str(&self.signature)
+            .map_err(|e| eyre::eyre!("Malformed signature: {}", e))?;
+        signature
+            .recover(serde_json::to_vec(&self.document)?)
+            .map_err(|e| eyre::eyre!("Malformed signature: {}", e))
+    }
+}
@@ -0,0 +1,282 @@
+use crate::schema::{manufacturers, users_info};
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use diesel::PgConnection;
+use eyre::Result;
+
+// One namespace for users and manufacturers: a name is refused when it is malformed,
+// reserved, offensive, already used by anyone (ignoring case) or only differs from a
+// manufacturer's name by look-alike characters.
+
+define_sql_function!(fn lower(x: Text) -> Text);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NameKind {
+    // 3-32 of [A-Za-z0-9_.-]; Ownership reverts below 3 bytes
+    User,
+    // 2-64 characters, single spaces allowed between words ("Acme Corp")
+    Manufacturer,
+}
+
+impl NameKind {
+    fn bounds(&self) -> (usize, usize) {
+        match self {
+            NameKind::User => (3, 32),
+            NameKind::Manufacturer => (2, 64),
+        }
+    }
+}
+
+// Names nobody can register, compared by skeleton so "4dm1n" is caught too
+const RESERVED: &[&str] = &[
+    "admin", "administrator", "root", "system", "support", "help", "security", "official",
+    "moderator", "staff", "team", "eri", "api", "null", "undefined", "anonymous", "verify",
+    "verified", "manufacturer", "owner", "wallet", "contract",
+];
+
+// Brands users cannot pose as; manufacturers may register them (KYB decides the rest)
+const BRANDS: &[&str] = &[
+    "apple", "samsung", "sony", "nike", "adidas", "puma", "gucci", "prada", "chanel", "dior",
+    "hermes", "rolex", "omega", "cartier", "louisvuitton", "balenciaga", "versace", "burberry",
+    "tiffany", "pandora", "microsoft", "google", "amazon", "huawei", "xiaomi", "lenovo", "dell",
+    "canon", "nikon", "bose", "dyson", "lego", "nintendo", "playstation", "xbox",
+];
+
+// Matched anywhere in the skeleton, so only words without innocent superstrings belong here
+const PROFANITY: &[&str] = &["fuck", "bitch", "whore", "nigger", "nigga", "faggot"];
+
+// Folds characters that look alike onto one representative, lowercases and drops
+// separators; two names with the same skeleton are indistinguishable at a glance
+pub fn skeleton(name: &str) -> String {
+    name
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter_map(|ch| match ch {
+            ' ' | '_' | '-' | '.' | '\u{200b}' | '\u{200c}' | '\u{200d}' | '\u{2060}' => None,
+            // Cyrillic and Greek letters drawn like Latin ones
+            'а' | 'α' => Some('a'),
+            'в' | 'β' | 'ь' => Some('b'),
+            'с' | 'ϲ' => Some('c'),
+            'ԁ' => Some('d'),
+            'е' | 'ё' | 'ε' => Some('e'),
+            'ɡ' => Some('g'),
+            'һ' | 'η' => Some('h'),
+            'і' | 'ї' | 'ι' | 'ӏ' => Some('l'),
+            'ј' => Some('j'),
+            'к' | 'κ' => Some('k'),
+            'м' | 'μ' => Some('m'),
+            'п' => Some('n'),
+            'о' | 'ο' | 'σ' => Some('o'),
+            'р' | 'ρ' => Some('p'),
+            'ԛ' => Some('q'),
+            'ѕ' => Some('s'),
+            'т' | 'τ' => Some('t'),
+            'υ' => Some('u'),
+            'ν' => Some('v'),
+            'ѡ' | 'ω' => Some('w'),
+            'х' | 'χ' => Some('x'),
+            'у' | 'γ' => Some('y'),
+            // digits and symbols read as letters
+            '0' => Some('o'),
+            '1' | 'i' | '|' | '!' => Some('l'),
+            '3' => Some('e'),
+            '4' | '@' => Some('a'),
+            '5' | '$' => Some('s'),
+            '7' => Some('t'),
+            '8' => Some('b'),
+            '9' => Some('g'),
+            ch => Some(ch),
+        })
+        .collect()
+}
+
+// Also folds letter pairs that pass for a single letter. Ordinary names collide this way
+// ("clara" and "dara"), so it is only used against the fixed reserved and brand lists.
+fn loose_skeleton(name: &str) -> String {
+    skeleton(name).replace("rn", "m").replace("vv", "w").replace("cl", "d")
+}
+
+// Format, reserved words and profanity; needs no database
+pub fn validate_name(name: &str, kind: NameKind) -> Result<()> {
+    let (min, max) = kind.bounds();
+    let length = name.chars().count();
+    if length < min || length > max {
+        return Err(eyre::eyre!("Invalid username: must be {} to {} characters", min, max));
+    }
+
+    let allowed = |ch: char| {
+        ch.is_ascii_alphanumeric()
+            || matches!(ch, '_' | '-' | '.')
+            || (kind == NameKind::Manufacturer && ch == ' ')
+    };
+    if !name.chars().all(allowed) {
+        return Err(eyre::eyre!(
+            "Invalid username: only letters, digits, '_', '-' and '.' are allowed"
+        ));
+    }
+    if !name.starts_with(|ch: char| ch.is_ascii_alphanumeric())
+        || !name.ends_with(|ch: char| ch.is_ascii_alphanumeric())
+    {
+        return Err(eyre::eyre!("Invalid username: must start and end with a letter or digit"));
+    }
+    if name.contains("  ") {
+        return Err(eyre::eyre!("Invalid username: words are separated by a single space"));
+    }
+
+    let loose = loose_skeleton(name);
+    let reserved = RESERVED
+        .iter()
+        .chain(if kind == NameKind::User { BRANDS } else { &[] })
+        .any(|word| loose_skeleton(word) == loose);
+    if reserved {
+        return Err(eyre::eyre!("Invalid username: {} is reserved", name));
+    }
+    let key = skeleton(name);
+    if PROFANITY.iter().any(|word| key.contains(&skeleton(word))) {
+        return Err(eyre::eyre!("Invalid username: {} is not allowed", name));
+    }
+
+    Ok(())
+}
+
+// Whether a user or manufacturer other than `claimant` already uses the name, ignoring case
+pub fn is_taken(conn: &mut PgConnection, name: &str, claimant: Option<&str>) -> Result<bool> {
+    let name = name.to_lowercase();
+    let claimant = claimant.unwrap_or_default().to_lowercase();
+
+    let users = users_info::table
+        .filter(lower(users_info::username).eq(&name))
+        .filter(lower(users_info::user_address).ne(&claimant))
+        .select(diesel::dsl::count_star())
+        .first::<i64>(conn)
+        .map_err(|e| eyre::eyre!("Failed to check username: {}", e))?;
+    let manufacturers = manufacturers::table
+        .filter(lower(manufacturers::manufacturer_name).eq(&name))
+        .filter(lower(manufacturers::manufacturer_address).ne(&claimant))
+        .select(diesel::dsl::count_star())
+        .first::<i64>(conn)
+        .map_err(|e| eyre::eyre!("Failed to check manufacturer name: {}", e))?;
+
+    Ok(users + manufacturers > 0)
+}
+
+// The manufacturer name `name` could be mistaken for, other than the claimant's own
+fn impersonated_manufacturer(
+    conn: &mut PgConnection,
+    name: &str,
+    claimant: Option<&str>,
+) -> Result<Option<String>> {
+    let key = skeleton(name);
+    // skeletons are computed here rather than in SQL; the table is small
+    let names = manufacturers::table
+        .select((manufacturers::manufacturer_address, manufacturers::manufacturer_name))
+        .load::<(String, String)>(conn)
+        .map_err(|e| eyre::eyre!("Failed to fetch manufacturer names: {}", e))?;
+
+    Ok(names
+        .into_iter()
+        .filter(|(address, _)| !claimant.is_some_and(|claimant| address.eq_ignore_ascii_case(claimant)))
+        .map(|(_, manufacturer_name)| manufacturer_name)
+        .find(|manufacturer_name| skeleton(manufacturer_name) == key))
+}
+
+// Everything a name must pass before it is registered or taken over by `claimant`
+pub fn check_name(
+    conn: &mut PgConnection,
+    name: &str,
+    kind: NameKind,
+    claimant: Option<&str>,
+) -> Result<()> {
+    validate_name(name, kind)?;
+
+    if is_taken(conn, name, claimant)? {
+        return Err(eyre::eyre!("Username is already taken"));
+    }
+    if let Some(manufacturer_name) = impersonated_manufacturer(conn, name, claimant)? {
+        return Err(eyre::eyre!(
+            "Invalid username: too similar to manufacturer {}",
+            manufacturer_name
+        ));
+    }
+
+    Ok(())
+}
+
+// Whether `check_name` refused the name itself rather than failing to look it up
+pub fn is_policy_rejection(e: &eyre::Report) -> bool {
+    let message = e.to_string();
+    message.starts_with("Invalid username") || message == "Username is already taken"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejected(name: &str, kind: NameKind) -> String {
+        validate_name(name, kind).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn reserved_words_are_caught_through_look_alikes() {
+        for name in ["admin", "Admin", "4dm1n", "ad.min", "r00t", "5upp0rt", "verified"] {
+            assert!(rejected(name, NameKind::User).ends_with("is reserved"), "{}", name);
+        }
+        assert!(rejected("Official", NameKind::Manufacturer).ends_with("is reserved"));
+    }
+
+    #[test]
+    fn users_cannot_pose_as_brands() {
+        for name in ["samsung", "SAMSUNG", "5amsung", "sam_sung", "arnazon", "xbox", "n1ke"] {
+            assert!(rejected(name, NameKind::User).ends_with("is reserved"), "{}", name);
+        }
+        // manufacturers may register a brand, KYB decides whether it is theirs
+        assert!(validate_name("Samsung", NameKind::Manufacturer).is_ok());
+    }
+
+    #[test]
+    fn homoglyphs_share_a_skeleton() {
+        // Cyrillic а and о, Greek ο
+        assert_eq!(skeleton("Sаmsung"), skeleton("samsung"));
+        assert_eq!(skeleton("gооgle"), skeleton("google"));
+        assert_eq!(skeleton("Gοogle"), skeleton("G00GLE"));
+        assert_eq!(skeleton("acme-co"), skeleton("Acme Co"));
+        assert_eq!(skeleton("a\u{200b}cme"), skeleton("acme"));
+    }
+
+    #[test]
+    fn ordinary_names_do_not_collide() {
+        assert_ne!(skeleton("clara"), skeleton("dara"));
+        assert_ne!(skeleton("barn"), skeleton("bam"));
+        assert_ne!(skeleton("savvy"), skeleton("sawy"));
+        for name in ["clara", "dara", "claire", "modern", "savvy", "classic", "arnold", "cocktail"] {
+            assert!(validate_name(name, NameKind::User).is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn profanity_is_matched_inside_names() {
+        for name in ["fuck", "xXfuckXx", "b1tch", "wh0re99"] {
+            assert!(rejected(name, NameKind::User).ends_with("is not allowed"), "{}", name);
+        }
+    }
+
+    #[test]
+    fn only_ascii_letters_digits_and_separators() {
+        for name in ["jöhn", "Sаmsung", "john doe", "john@doe", "emoji😀"] {
+            assert!(rejected(name, NameKind::User).contains("only letters"), "{}", name);
+        }
+        assert!(validate_name("john_doe-1.2", NameKind::User).is_ok());
+        assert!(validate_name("Acme Corp", NameKind::Manufacturer).is_ok());
+        assert!(validate_name("Acme  Corp", NameKind::Manufacturer).is_err());
+        assert!(validate_name("_john", NameKind::User).is_err());
+        assert!(validate_name("john.", NameKind::User).is_err());
+    }
+
+    #[test]
+    fn length_bounds_depend_on_kind() {
+        assert!(validate_name("ab", NameKind::User).is_err());
+        assert!(validate_name("ab", NameKind::Manufacturer).is_ok());
+        assert!(validate_name(&"a".repeat(32), NameKind::User).is_ok());
+        assert!(validate_name(&"a".repeat(33), NameKind::User).is_err());
+    }
+}
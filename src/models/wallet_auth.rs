@@ -0,0 +1,80 @@
+use chrono::Utc;
+use ethers::types::{Address, Signature};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+// Requests that change what a wallet owns are signed by that wallet (EIP-191 personal_sign)
+// over `auth_message(action, subject, payload_hash, issued_at)`. The payload hash ties the
+// signature to the body it came with, and a signature is only accepted for a few minutes
+// so a captured one cannot be replayed later.
+const MAX_AGE_SECS: i64 = 300;
+// tolerated clock drift of the signing client
+const MAX_SKEW_SECS: i64 = 60;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub struct WalletAuth {
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub caller: String,
+    #[schema(example = "0x4f8e...1b")]
+    pub signature: String,
+    // unix seconds the message was signed at
+    #[schema(example = 1759309200)]
+    pub issued_at: i64,
+}
+
+// e.g. "ERI update-manufacturer-profile 0x1234…5678 0x9f2c…41 at 1759309200"
+pub fn auth_message(action: &str, subject: &str, payload_hash: &str, issued_at: i64) -> String {
+    format!("ERI {} {} {} at {}", action, subject.to_lowercase(), payload_hash, issued_at)
+}
+
+// keccak256 of the request body as the server reads it: compact JSON, keys sorted,
+// `auth` and null fields left out, defaults filled in
+pub fn payload_hash(payload: &impl Serialize) -> eyre::Result<String> {
+    let mut value = serde_json::to_value(payload)
+        .map_err(|e| eyre::eyre!("Failed to encode signed payload: {}", e))?;
+    if let serde_json::Value::Object(fields) = &mut value {
+        fields.remove("auth");
+    }
+    strip_nulls(&mut value);
+
+    Ok(format!("0x{}", hex::encode(Keccak256::digest(value.to_string().as_bytes()))))
+}
+
+fn strip_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            fields.retain(|_, field| !field.is_null());
+            fields.values_mut().for_each(strip_nulls);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+impl WalletAuth {
+    // Returns the caller once the signature is shown to come from its wallet for this payload
+    pub fn verify(&self, action: &str, subject: &str, payload: &impl Serialize) -> eyre::Result<Address> {
+        let caller: Address = self
+            .caller
+            .parse()
+            .map_err(|_| eyre::eyre!("Invalid caller address"))?;
+
+        let age = Utc::now().timestamp() - self.issued_at;
+        if !(-MAX_SKEW_SECS..=MAX_AGE_SECS).contains(&age) {
+            return Err(eyre::eyre!("Invalid wallet signature: expired"));
+        }
+
+        let signature = Signature::from_str(&self.signature)
+            .map_err(|_| eyre::eyre!("Invalid wallet signature: malformed"))?;
+        let signer = signature
+            .recover(auth_message(action, subject, &payload_hash(payload)?, self.issued_at))
+            .map_err(|_| eyre::eyre!("Invalid wallet signature: unrecoverable"))?;
+        if signer != caller {
+            return Err(eyre::eyre!("Invalid wallet signature: signed by another address"));
+        }
+
+        Ok(caller)
+    }
+}
@@ -0,0 +1,35 @@
+use crate::config::app_state::AppState;
+use crate::ownership::transfer_state::expire_stale_offers;
+use eyre::Result;
+use std::env;
+use std::sync::Arc;
+
+// Periodically moves offered ownership codes whose TTL has elapsed to `expired`
+pub async fn sweep_expired_ownership_codes(state: &Arc<AppState>) -> Result<()> {
+    let interval_secs = env::var("OWNERSHIP_CODE_SWEEP_SECS")
+        .ok()
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(60);
+
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+
+        match expire_ownership_codes(state) {
+            Ok(0) => {}
+            Ok(expired) => eprintln!("Marked {} ownership code(s) as expired", expired),
+            Err(e) => eprintln!("Error sweeping expired ownership codes: {:?}", e),
+        }
+    }
+}
+
+fn expire_ownership_codes(state: &Arc<AppState>) -> Result<usize> {
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    expire_stale_offers(conn, None)
+}
@@ -0,0 +1,425 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::{ItemFlag, NewItemFlag};
+use crate::models::wallet_auth::WalletAuth;
+use crate::models::username_policy::lower;
+use crate::ownership::item_status::{active_flag, item_status, FlagRole, ItemStatus};
+use crate::schema::{item_flags, items, manufacturers};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use diesel::PgConnection;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct FlagItemRequest {
+    #[schema(example = "stolen")]
+    pub status: ItemStatus,
+    #[schema(example = "Taken from my car on 2025-09-14")]
+    pub reason: String,
+    // links or references backing the reason (police report, recall notice, ...)
+    #[serde(default)]
+    #[schema(example = json!(["https://police.example.com/reports/48213"]))]
+    pub evidence: Vec<String>,
+    // signed by the item's owner or manufacturer for `flag-item <item_id>`
+    pub auth: WalletAuth,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ClearItemFlagRequest {
+    // signed by the item's owner or manufacturer for `clear-item-flag <item_id>`
+    pub auth: WalletAuth,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ItemFlagsResponse {
+    item_id: String,
+    status: ItemStatus,
+    // newest first
+    flags: Vec<ItemFlag>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/item/{item_id}/flags",
+    params(
+        ("item_id" = String, Path, description = "The unique ID of the item", example = "item123")
+    ),
+    request_body = FlagItemRequest,
+    responses(
+        (status = 201, description = "Item flagged", body = ItemFlag),
+        (status = 400, description = "Invalid input (e.g., empty reason or an invalid caller address)"),
+        (status = 401, description = "Wallet signature missing, expired or not from the caller"),
+        (status = 403, description = "Caller may not raise this flag on the item"),
+        (status = 404, description = "Item not found"),
+        (status = 409, description = "Item is already flagged"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Items"
+)]
+pub async fn flag_item(
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<String>,
+    AxumJson(request): AxumJson<FlagItemRequest>,
+) -> impl IntoResponse {
+    match flag_item_internal(&state, &item_id, &request).await {
+        Ok(flag) => (StatusCode::CREATED, AxumJson(flag)).into_response(),
+        Err(e) => {
+            eprintln!("Error flagging item {}: {:?}", item_id, e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/item/{item_id}/flags/clear",
+    params(
+        ("item_id" = String, Path, description = "The unique ID of the item", example = "item123")
+    ),
+    request_body = ClearItemFlagRequest,
+    responses(
+        (status = 200, description = "Flag cleared, item is active again", body = ItemFlag),
+        (status = 400, description = "Invalid caller address"),
+        (status = 401, description = "Wallet signature missing, expired or not from the caller"),
+        (status = 403, description = "Caller may not clear this flag (destroyed items stay destroyed)"),
+        (status = 404, description = "Item not found or not flagged"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Items"
+)]
+pub async fn clear_item_flag(
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<String>,
+    AxumJson(request): AxumJson<ClearItemFlagRequest>,
+) -> impl IntoResponse {
+    match clear_item_flag_internal(&state, &item_id, &request).await {
+        Ok(flag) => (StatusCode::OK, AxumJson(flag)).into_response(),
+        Err(e) => {
+            eprintln!("Error clearing flag of item {}: {:?}", item_id, e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/item/{item_id}/flags",
+    params(
+        ("item_id" = String, Path, description = "The unique ID of the item", example = "item123")
+    ),
+    responses(
+        (status = 200, description = "Current status and flag history of the item", body = ItemFlagsResponse),
+        (status = 404, description = "Item not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Items"
+)]
+pub async fn get_item_flags(
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<String>,
+) -> impl IntoResponse {
+    match get_item_flags_internal(&state, &item_id).await {
+        Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
+        Err(e) => {
+            eprintln!("Error fetching flags of item {}: {:?}", item_id, e);
+            error_response(e)
+        }
+    }
+}
+
+fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.contains("cannot be empty") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("is not a flag") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Invalid caller address") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Invalid wallet signature") => (StatusCode::UNAUTHORIZED, e.to_string()),
+        s if s.contains("Caller is neither the owner nor the manufacturer") => {
+            (StatusCode::FORBIDDEN, e.to_string())
+        }
+        s if s.contains("may not") => (StatusCode::FORBIDDEN, e.to_string()),
+        s if s.contains("Item not found") => (StatusCode::NOT_FOUND, e.to_string()),
+        s if s.contains("Item is not flagged") => (StatusCode::NOT_FOUND, e.to_string()),
+        s if s.contains("Item is already flagged") => (StatusCode::CONFLICT, e.to_string()),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, AxumJson(json!({"error": message}))).into_response()
+}
+
+async fn flag_item_internal(
+    state: &Arc<AppState>,
+    item_id: &str,
+    request: &FlagItemRequest,
+) -> eyre::Result<ItemFlag> {
+    if request.status == ItemStatus::Active {
+        return Err(eyre::eyre!("active is not a flag; clear the current flag instead"));
+    }
+    if request.reason.trim().is_empty() {
+        return Err(eyre::eyre!("Reason cannot be empty"));
+    }
+    let caller = flag_caller(item_id, request)?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let role = caller_role(conn, item_id, &caller)?;
+    if !request.status.can_be_raised_by(role) {
+        return Err(eyre::eyre!(
+            "The {} may not flag an item as {}",
+            role.as_str(),
+            request.status.as_str()
+        ));
+    }
+
+    if let Some(flag) = active_flag(conn, item_id)? {
+        return Err(eyre::eyre!("Item is already flagged as {}", flag.status));
+    }
+
+    diesel::insert_into(item_flags::table)
+        .values(NewItemFlag {
+            item_id: item_id.to_string(),
+            status: request.status.as_str().to_string(),
+            reason: request.reason.trim().to_string(),
+            evidence: request.evidence.clone(),
+            flagged_by: caller,
+        })
+        .returning(ItemFlag::as_returning())
+        .get_result(conn)
+        .map_err(|e| match e {
+            // uq_item_flags_active_item: flagged concurrently
+            DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                eyre::eyre!("Item is already flagged")
+            }
+            _ => eyre::eyre!("Failed to insert item flag: {}", e),
+        })
+}
+
+async fn clear_item_flag_internal(
+    state: &Arc<AppState>,
+    item_id: &str,
+    request: &ClearItemFlagRequest,
+) -> eyre::Result<ItemFlag> {
+    let caller = clear_caller(item_id, request)?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let role = caller_role(conn, item_id, &caller)?;
+    let flag = active_flag(conn, item_id)?.ok_or_else(|| eyre::eyre!("Item is not flagged"))?;
+
+    let status = ItemStatus::parse(&flag.status)?;
+    if !status.can_be_cleared_by(role) {
+        return Err(eyre::eyre!(
+            "The {} may not clear a {} flag",
+            role.as_str(),
+            status.as_str()
+        ));
+    }
+
+    // the row stays as history
+    diesel::update(
+        item_flags::table
+            .filter(item_flags::id.eq(flag.id))
+            .filter(item_flags::cleared_at.is_null()),
+    )
+    .set((
+        item_flags::cleared_by.eq(Some(caller)),
+        item_flags::cleared_at.eq(Some(Utc::now())),
+    ))
+    .returning(ItemFlag::as_returning())
+    .get_result(conn)
+    .optional()
+    .map_err(|e| eyre::eyre!("Failed to clear item flag: {}", e))?
+    .ok_or_else(|| eyre::eyre!("Item is not flagged"))
+}
+
+async fn get_item_flags_internal(
+    state: &Arc<AppState>,
+    item_id: &str,
+) -> eyre::Result<ItemFlagsResponse> {
+    let conn = &mut state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+
+    let item_exists = items::table
+        .filter(items::item_id.eq(item_id))
+        .select(diesel::dsl::count_star())
+        .first::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?;
+    if !item_exists {
+        return Err(eyre::eyre!("Item not found"));
+    }
+
+    let flags = item_flags::table
+        .filter(item_flags::item_id.eq(item_id))
+        .order(item_flags::created_at.desc())
+        .select(ItemFlag::as_select())
+        .load(conn)
+        .map_err(|e| eyre::eyre!("Failed to query item flags: {}", e))?;
+
+    Ok(ItemFlagsResponse {
+        item_id: item_id.to_string(),
+        status: item_status(conn, item_id)?,
+        flags,
+    })
+}
+
+// The address whose wallet signed the request, lowercased; the role is derived from it alone
+fn flag_caller(item_id: &str, request: &FlagItemRequest) -> eyre::Result<String> {
+    Ok(format!("{:?}", request.auth.verify("flag-item", item_id, request)?))
+}
+
+fn clear_caller(item_id: &str, request: &ClearItemFlagRequest) -> eyre::Result<String> {
+    Ok(format!("{:?}", request.auth.verify("clear-item-flag", item_id, request)?))
+}
+
+// Whether the verified caller acts as the item's current owner or as the registered
+// manufacturer that issued it (items only carry the manufacturer's name)
+fn caller_role(conn: &mut PgConnection, item_id: &str, caller: &str) -> eyre::Result<FlagRole> {
+    let (owner, manufacturer_name) = items::table
+        .filter(items::item_id.eq(item_id))
+        .select((items::owner, items::manufacturer))
+        .first::<(String, String)>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?
+        .ok_or_else(|| eyre::eyre!("Item not found"))?;
+
+    if owner.eq_ignore_ascii_case(caller) {
+        return Ok(FlagRole::Owner);
+    }
+
+    let is_manufacturer = manufacturers::table
+        .filter(lower(manufacturers::manufacturer_address).eq(caller))
+        .filter(lower(manufacturers::manufacturer_name).eq(manufacturer_name.to_lowercase()))
+        .filter(manufacturers::is_registered.eq(true))
+        .select(diesel::dsl::count_star())
+        .first::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(|e| eyre::eyre!("Failed to query manufacturer: {}", e))?;
+
+    if is_manufacturer {
+        Ok(FlagRole::Manufacturer)
+    } else {
+        Err(eyre::eyre!(
+            "Caller is neither the owner nor the manufacturer of the item"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::wallet_auth::{auth_message, payload_hash};
+    use ethers::prelude::{LocalWallet, Signer};
+    use ethers::utils::hash_message;
+
+    const OWNER_KEY: &str = "0x0000000000000000000000000000000000000000000000000000000000a11ce0";
+    const THIEF_KEY: &str = "0x00000000000000000000000000000000000000000000000000000000000b0b00";
+
+    fn wallet(key: &str) -> LocalWallet {
+        key.parse().unwrap()
+    }
+
+    fn unsigned(caller: &LocalWallet) -> WalletAuth {
+        WalletAuth {
+            caller: format!("{:?}", caller.address()),
+            signature: String::new(),
+            issued_at: Utc::now().timestamp(),
+        }
+    }
+
+    // the auth block is left out of the payload hash, so it can be signed in place
+    fn sign(signer: &LocalWallet, action: &str, subject: &str, payload: &impl Serialize, issued_at: i64) -> String {
+        let message = auth_message(action, subject, &payload_hash(payload).unwrap(), issued_at);
+        signer.sign_hash(hash_message(message)).unwrap().to_string()
+    }
+
+    fn flag_request(caller: &LocalWallet, status: ItemStatus) -> FlagItemRequest {
+        FlagItemRequest {
+            status,
+            reason: "Taken from my car".to_string(),
+            evidence: vec![],
+            auth: unsigned(caller),
+        }
+    }
+
+    #[test]
+    fn owner_signature_yields_owner_address() {
+        let owner = wallet(OWNER_KEY);
+        let mut request = flag_request(&owner, ItemStatus::Stolen);
+        request.auth.signature = sign(&owner, "flag-item", "item123", &request, request.auth.issued_at);
+
+        assert_eq!(
+            flag_caller("item123", &request).unwrap(),
+            format!("{:?}", owner.address())
+        );
+    }
+
+    #[test]
+    fn forged_owner_address_cannot_flag() {
+        let owner = wallet(OWNER_KEY);
+        let thief = wallet(THIEF_KEY);
+        // the owner's address is public, the signature is the thief's
+        let mut request = flag_request(&owner, ItemStatus::Destroyed);
+        request.auth.signature = sign(&thief, "flag-item", "item123", &request, request.auth.issued_at);
+
+        assert_eq!(
+            flag_caller("item123", &request).unwrap_err().to_string(),
+            "Invalid wallet signature: signed by another address"
+        );
+    }
+
+    #[test]
+    fn forged_owner_address_cannot_clear() {
+        let owner = wallet(OWNER_KEY);
+        let thief = wallet(THIEF_KEY);
+        let mut request = ClearItemFlagRequest { auth: unsigned(&owner) };
+        request.auth.signature = sign(&thief, "clear-item-flag", "item123", &request, request.auth.issued_at);
+
+        assert!(clear_caller("item123", &request).is_err());
+    }
+
+    #[test]
+    fn signature_is_bound_to_item_and_action() {
+        let owner = wallet(OWNER_KEY);
+        let mut request = ClearItemFlagRequest { auth: unsigned(&owner) };
+        request.auth.signature = sign(&owner, "clear-item-flag", "item123", &request, request.auth.issued_at);
+
+        assert!(clear_caller("item123", &request).is_ok());
+        assert!(clear_caller("item456", &request).is_err());
+
+        // a clear signature replayed as a flag
+        let replayed = FlagItemRequest {
+            auth: request.auth.clone(),
+            ..flag_request(&owner, ItemStatus::Destroyed)
+        };
+        assert!(flag_caller("item123", &replayed).is_err());
+    }
+
+    #[test]
+    fn unsigned_request_is_rejected() {
+        let request = flag_request(&wallet(OWNER_KEY), ItemStatus::Stolen);
+
+        assert_eq!(
+            flag_caller("item123", &request).unwrap_err().to_string(),
+            "Invalid wallet signature: malformed"
+        );
+    }
+}
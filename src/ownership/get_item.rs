@@ -11,8 +11,18 @@ use std::sync::Arc;
 use utoipa::ToSchema;
 use crate::config::app_state::AppState;
 use crate::contract_models::Item;
+use crate::ownership::item_status::{item_status, ItemStatus};
+use crate::recalls::recall_campaign::{item_recalls, ItemRecall};
 use crate::schema::items;
 
+// The item as indexed, plus its lifecycle flag and any open recalls
+#[derive(Serialize, ToSchema)]
+pub struct ItemDetails {
+    #[serde(flatten)]
+    item: Item,
+    status: ItemStatus,
+    recalls: Vec<ItemRecall>,
+}
 
 // Define the error response struct
 #[derive(Serialize, ToSchema)]
@@ -27,7 +37,7 @@ struct ErrorResponse {
         ("item_id" = String, Path, description = "The unique ID of the item", example = "item123")
     ),
     responses(
-        (status = 200, description = "Item retrieved successfully", body = Item, example = json!({
+        (status = 200, description = "Item retrieved successfully", body = ItemDetails, example = json!({
             "id": 1,
             "item_id": "item123",
             "name": "Widget",
@@ -37,7 +47,16 @@ struct ErrorResponse {
             "manufacturer": "Acme Corp",
             "metadata": ["color: blue", "size: medium"],
             "created_at": "2023-09-01T00:00:00Z",
-            "tnx_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
+            "product_id": 1,
+            "tnx_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890",
+            "status": "active",
+            "recalls": [{
+                "campaign_id": 1,
+                "title": "Battery overheating",
+                "reason": "Cells from one supplier batch may overheat while charging",
+                "remedy": "Free battery replacement at any service center",
+                "acknowledged": false
+            }]
         })),
         (status = 404, description = "Item not found", body = ErrorResponse, example = json!({"error": "Item not found"})),
         (status = 500, description = "Internal server error (e.g., database failure)", body = ErrorResponse, example = json!({"error": "Internal server error: Failed to query database"}))
@@ -67,7 +86,7 @@ pub async fn get_item(
     }
 }
 
-async fn get_item_internal(state: &Arc<AppState>, item_id: &str) -> eyre::Result<Item> {
+async fn get_item_internal(state: &Arc<AppState>, item_id: &str) -> eyre::Result<ItemDetails> {
     // Validate item_id
     if item_id.is_empty() {
         return Err(eyre::eyre!("Item ID cannot be empty"));
@@ -88,5 +107,9 @@ async fn get_item_internal(state: &Arc<AppState>, item_id: &str) -> eyre::Result
         .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?
         .ok_or_else(|| eyre::eyre!("Item not found"))?;
 
-    Ok(item)
+    Ok(ItemDetails {
+        status: item_status(conn, item_id)?,
+        recalls: item_recalls(conn, item_id)?,
+        item,
+    })
 }
\ No newline at end of file
@@ -1,29 +1,77 @@
 use axum::{extract::{Query, State}, http::StatusCode, response::IntoResponse, Json};
+use chrono::{DateTime, Utc};
+use diesel::dsl::not;
+use diesel::pg::Pg;
 use diesel::prelude::*;
+use diesel::PgConnection;
 use eyre::Result;
 use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use crate::config::app_state::AppState;
 use crate::contract_models::{Item};
-use crate::schema::items;
+use crate::models::metadata::canonical_entry;
+use crate::models::pagination::{keyset_page, Page, SortBy, SortOrder};
+use crate::models::username_policy::lower;
+use crate::ownership::item_status::ItemStatus;
+use crate::schema::{item_flags, items};
 
 #[derive(Serialize, ToSchema)]
 pub struct ItemsResponse {
     items: Vec<Item>,
+    // items matching the filters across all pages
+    #[schema(example = 42)]
+    total: i64,
+    // pass as `cursor` to get the next page; null on the last page
+    #[schema(nullable = true, example = "7b22736f7274223a...")]
+    next_cursor: Option<String>,
 }
 
 #[derive(Deserialize, ToSchema)]
 pub struct ItemQuery {
     #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
     pub owner: String,
+    // comma-separated key:value pairs an item's metadata must all contain
+    #[schema(example = "color:gold,storage_gb:128")]
+    pub attributes: Option<String>,
+    #[schema(example = "SAMSUNG")]
+    pub manufacturer: Option<String>,
+    #[schema(value_type = Option<String>, example = "2025-09-01T00:00:00Z")]
+    pub created_from: Option<DateTime<Utc>>,
+    #[schema(value_type = Option<String>, example = "2025-10-01T00:00:00Z")]
+    pub created_to: Option<DateTime<Utc>>,
+    pub status: Option<ItemStatus>,
+    pub cursor: Option<String>,
+    #[schema(example = 20)]
+    pub limit: Option<i64>,
+    pub sort: Option<SortBy>,
+    pub order: Option<SortOrder>,
+}
+
+// Filters shared by the item list endpoints; every one that is set has to match
+pub(crate) struct ItemFilter<'a> {
+    pub owner: Option<&'a str>,
+    pub manufacturer: Option<&'a str>,
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+    pub status: Option<ItemStatus>,
+    pub attributes: Option<&'a str>,
 }
 
 #[utoipa::path(
     get,
     path = "/api/items/owner",
     params(
-        ("owner" = String, Query, description = "Owner's blockchain address", example = "0x1234567890abcdef1234567890abcdef12345678")
+        ("owner" = String, Query, description = "Owner's blockchain address, in any letter case", example = "0x1234567890abcdef1234567890abcdef12345678"),
+        ("attributes" = Option<String>, Query, description = "Only items whose metadata has all of these comma-separated key:value attributes (keys are case-insensitive, values exact)", example = "color:gold,storage_gb:128"),
+        ("manufacturer" = Option<String>, Query, description = "Manufacturer name, case-insensitive", example = "SAMSUNG"),
+        ("created_from" = Option<String>, Query, description = "Only items created at or after this time (RFC 3339)", example = "2025-09-01T00:00:00Z"),
+        ("created_to" = Option<String>, Query, description = "Only items created at or before this time (RFC 3339)", example = "2025-10-01T00:00:00Z"),
+        ("status" = Option<ItemStatus>, Query, description = "Lifecycle status", example = "active"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<i64>, Query, description = "Items per page (default 20, max 100)", example = 20),
+        ("sort" = Option<SortBy>, Query, description = "Sort field: `created_at` (default) or `name`", example = "created_at"),
+        ("order" = Option<SortOrder>, Query, description = "Sort order: `desc` (default) or `asc`", example = "desc")
     ),
     responses(
         (status = 200, description = "Items found for the owner", body = ItemsResponse, example = json!({
@@ -40,9 +88,11 @@ pub struct ItemQuery {
                     "created_at": "2025-08-25T19:47:00Z",
                     "tnx_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
                 }
-            ]
+            ],
+            "total": 1,
+            "next_cursor": null
         })),
-        (status = 400, description = "Owner address not provided"),
+        (status = 400, description = "Owner address not provided, malformed attributes filter or invalid pagination"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Items"
@@ -52,41 +102,146 @@ pub async fn get_owner_items(
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
     match get_items_by_owner_internal(&state, &query).await {
-        Ok(items) => (
+        Ok(response) => (
             StatusCode::OK,
-            Json(ItemsResponse { items }),
+            Json(response),
         ).into_response(),
         Err(e) => {
             eprintln!("Error fetching items for owner {}: {:?}", query.owner, e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": format!("Internal server error: {}", e)})),
-            ).into_response()
+            items_error_response(e)
         }
     }
 }
 
+pub(crate) fn items_error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        "Owner address must be provided" => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Invalid attributes filter") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Invalid pagination") => (StatusCode::BAD_REQUEST, e.to_string()),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, Json(serde_json::json!({"error": message}))).into_response()
+}
+
 async fn get_items_by_owner_internal(
     state: &Arc<AppState>,
     query: &ItemQuery,
-) -> Result<Vec<Item>> {
+) -> Result<ItemsResponse> {
     if query.owner.is_empty() {
         return Err(eyre::eyre!("Owner address must be provided"));
     }
+    let page = Page::new(query.cursor.as_deref(), query.limit, query.sort, query.order)?;
 
     let conn = &mut state.db_pool.get().map_err(|e| {
         eprintln!("Failed to get DB connection: {:?}", e);
         eyre::eyre!("Failed to get DB connection: {}", e)
     })?;
 
-    let items = items::table
-        .select(Item::as_select())
-        .filter(items::owner.eq(query.owner.clone()))
-        .load::<Item>(conn)
-        .map_err(|e| {
-            eprintln!("Failed to fetch items for owner {}: {:?}", query.owner, e);
-            eyre::eyre!("Failed to fetch items: {}", e)
-        })?;
-
-    Ok(items)
-}
\ No newline at end of file
+    let filter = ItemFilter {
+        owner: Some(&query.owner),
+        manufacturer: query.manufacturer.as_deref(),
+        created_from: query.created_from,
+        created_to: query.created_to,
+        status: query.status,
+        attributes: query.attributes.as_deref(),
+    };
+    load_items_page(conn, &filter, &page)
+}
+
+// matched against the canonical "key: value" entries (see models::metadata)
+fn attribute_entries(filter: &str) -> Result<Vec<Option<String>>> {
+    filter
+        .split(',')
+        .filter(|pair| !pair.trim().is_empty())
+        .map(|pair| {
+            pair.split_once(':')
+                .filter(|(key, _)| !key.trim().is_empty())
+                .map(|(key, value)| Some(canonical_entry(key, value)))
+                .ok_or_else(|| eyre::eyre!("Invalid attributes filter: {} is not key:value", pair))
+        })
+        .collect()
+}
+
+fn filtered_items<'a>(
+    filter: &ItemFilter<'a>,
+    entries: Option<Vec<Option<String>>>,
+) -> items::BoxedQuery<'a, Pg> {
+    let mut query = items::table.into_boxed();
+
+    if let Some(owner) = filter.owner {
+        query = query.filter(lower(items::owner).eq(owner.to_lowercase()));
+    }
+    // plain equality: ilike would read `%` and `_` in the input as wildcards
+    if let Some(manufacturer) = filter.manufacturer {
+        query = query.filter(lower(items::manufacturer).eq(manufacturer.to_lowercase()));
+    }
+    if let Some(created_from) = filter.created_from {
+        query = query.filter(items::created_at.ge(created_from));
+    }
+    if let Some(created_to) = filter.created_to {
+        query = query.filter(items::created_at.le(created_to));
+    }
+    if let Some(entries) = entries {
+        query = query.filter(items::metadata.contains(entries));
+    }
+
+    // status lives in item_flags: active means no open flag
+    let open_flags = item_flags::table.filter(item_flags::cleared_at.is_null());
+    match filter.status {
+        Some(ItemStatus::Active) => {
+            query = query.filter(not(items::item_id.eq_any(open_flags.select(item_flags::item_id))));
+        }
+        Some(status) => {
+            query = query.filter(
+                items::item_id.eq_any(
+                    open_flags
+                        .filter(item_flags::status.eq(status.as_str()))
+                        .select(item_flags::item_id),
+                ),
+            );
+        }
+        None => {}
+    }
+
+    query
+}
+
+pub(crate) fn load_items_page(
+    conn: &mut PgConnection,
+    filter: &ItemFilter,
+    page: &Page,
+) -> Result<ItemsResponse> {
+    let entries = filter.attributes.map(attribute_entries).transpose()?;
+
+    let total = filtered_items(filter, entries.clone())
+        .count()
+        .get_result::<i64>(conn)
+        .map_err(|e| eyre::eyre!("Failed to count items: {}", e))?;
+
+    let rows = keyset_page!(
+        filtered_items(filter, entries).select(Item::as_select()),
+        page,
+        items::created_at,
+        items::name,
+        items::item_id,
+        String
+    )
+    .load::<Item>(conn)
+    .map_err(|e| {
+        eprintln!("Failed to fetch items: {:?}", e);
+        eyre::eyre!("Failed to fetch items: {}", e)
+    })?;
+
+    let (items, next_cursor) = page.finish(rows, |item| {
+        (item.created_at, item.name.clone(), item.item_id.clone())
+    });
+
+    Ok(ItemsResponse {
+        items,
+        total,
+        next_cursor,
+    })
+}
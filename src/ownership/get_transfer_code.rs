@@ -1,4 +1,5 @@
 use axum::{extract::{Query, State}, http::StatusCode, response::IntoResponse, Json};
+use chrono::Utc;
 use diesel::prelude::*;
 use eyre::Result;
 use std::sync::Arc;
@@ -6,11 +7,13 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use crate::config::app_state::AppState;
 use crate::contract_models::OwnershipCode;
+use crate::ownership::ownership_code::{hash_code, normalize_code};
+use crate::ownership::transfer_state::TransferState;
 use crate::schema::ownership_codes;
 
 #[derive(Deserialize, ToSchema)]
 pub struct GetOwnershipCodeQuery {
-    #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890")]
+    #[schema(example = "7KQ2-M9XD-4TPR-VH3C")]
     pub ownership_code: String,
     #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef12")]
     pub caller: String,
@@ -20,19 +23,24 @@ pub struct GetOwnershipCodeQuery {
     get,
     path = "/api/get_transfer_code",
     params(
-        ("ownership_code" = String, Query, description = "Ownership code to fetch", example = "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"),
+        ("ownership_code" = String, Query, description = "Ownership code to fetch, in hex or base32 form", example = "7KQ2-M9XD-4TPR-VH3C"),
         ("caller" = String, Query, description = "Address of the caller (must match temp_owner)", example = "0xabcdef1234567890abcdef1234567890abcdef12")
     ),
     responses(
         (status = 200, description = "Ownership code found and caller is temp_owner", body = OwnershipCode, example = json!({
-            "ownership_code": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890",
+            "code_hash": "0x5d1f6a0e2b0c4f3e9a7d8c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f",
+            "item_id": "item_001",
             "item_owner": "0x1234567890abcdef1234567890abcdef12345678",
             "temp_owner": "0xabcdef1234567890abcdef1234567890abcdef12",
             "created_at": "2025-08-26T00:37:12.345Z",
-            "tnx_hash": ""
+            "expires_at": "2025-08-27T00:37:12.345Z",
+            "code_format": "base32",
+            "status": "offered",
+            "status_changed_at": "2025-08-26T00:37:12.345Z"
         })),
         (status = 400, description = "Invalid input (e.g., invalid ownership_code or caller format)"),
         (status = 404, description = "Ownership code not found or caller is not temp_owner"),
+        (status = 410, description = "Ownership code has expired, was claimed or was cancelled"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Ownership"
@@ -47,11 +55,14 @@ pub async fn get_ownership_code(
             Json(ownership_code),
         ).into_response(),
         Err(e) => {
-            eprintln!("Error fetching ownership code {}: {:?}", query.ownership_code, e);
+            eprintln!("Error fetching ownership code for caller {}: {:?}", query.caller, e);
             let (status, message) = match e.to_string().as_str() {
                 "Invalid ownership_code format" => (StatusCode::BAD_REQUEST, e.to_string()),
+                "Invalid ownership_code checksum" => (StatusCode::BAD_REQUEST, e.to_string()),
                 "Invalid caller address format" => (StatusCode::BAD_REQUEST, e.to_string()),
                 "Ownership code not found or caller is not temp_owner" => (StatusCode::NOT_FOUND, e.to_string()),
+                "Ownership code has expired" => (StatusCode::GONE, e.to_string()),
+                "Ownership code is no longer active" => (StatusCode::GONE, e.to_string()),
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, format!("Internal server error: {}", e)),
             };
             (status, Json(serde_json::json!({"error": message}))).into_response()
@@ -63,10 +74,8 @@ async fn get_ownership_code_internal(
     state: &Arc<AppState>,
     query: &GetOwnershipCodeQuery,
 ) -> Result<OwnershipCode> {
-    // Validate ownership_code format (must start with 0x and be 66 characters long)
-    if !query.ownership_code.starts_with("0x") || query.ownership_code.len() != 66 {
-        return Err(eyre::eyre!("Invalid ownership_code format"));
-    }
+    // Validate ownership_code format (hex or checksummed base32) and derive the stored hash
+    let code_hash = hash_code(&normalize_code(&query.ownership_code)?);
 
     // Validate caller address format
     if !query.caller.starts_with("0x") || query.caller.len() != 42 {
@@ -80,15 +89,25 @@ async fn get_ownership_code_internal(
 
     // Fetch ownership code and check if caller is temp_owner
     let ownership_code = ownership_codes::table
-        .filter(ownership_codes::ownership_code.eq(&query.ownership_code))
+        .filter(ownership_codes::code_hash.eq(&code_hash))
         .filter(ownership_codes::temp_owner.eq(query.caller.clone()))
         .select(OwnershipCode::as_select())
         .first(conn)
         .optional()
         .map_err(|e| {
-            eprintln!("Error fetching ownership code {}: {:?}", query.ownership_code, e);
+            eprintln!("Error fetching ownership code for caller {}: {:?}", query.caller, e);
             eyre::eyre!("Failed to fetch ownership code: {}", e)
         })?;
 
-    ownership_code.ok_or_else(|| eyre::eyre!("Ownership code not found or caller is not temp_owner"))
+    let ownership_code = ownership_code
+        .ok_or_else(|| eyre::eyre!("Ownership code not found or caller is not temp_owner"))?;
+
+    // Only an open offer can be looked up; the sweeper may not have expired it yet
+    match TransferState::parse(&ownership_code.status)? {
+        TransferState::Offered if ownership_code.expires_at > Utc::now() => Ok(ownership_code),
+        TransferState::Offered | TransferState::Expired => {
+            Err(eyre::eyre!("Ownership code has expired"))
+        }
+        _ => Err(eyre::eyre!("Ownership code is no longer active")),
+    }
 }
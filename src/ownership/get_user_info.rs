@@ -1,5 +1,6 @@
 use crate::config::app_state::AppState;
-use crate::contract_models::UserInfo;
+use crate::contract_models::{UserInfo, UserProfile};
+use crate::ownership::user_profile::visible_profile;
 use crate::schema::users_info;
 use axum::{
     Json,
@@ -23,11 +24,27 @@ pub struct UserQuery {
 
 #[derive(Serialize, ToSchema)]
 pub struct UserResponse {
-    user_address: String,
-    username: String,
-    is_registered: bool,
-    created_at: String,
-    tnx_hash: String,
+    pub(crate) user_address: String,
+    pub(crate) username: String,
+    pub(crate) is_registered: bool,
+    pub(crate) created_at: String,
+    pub(crate) tnx_hash: String,
+    // absent when the profile is private
+    #[schema(nullable = true)]
+    pub(crate) profile: Option<UserProfile>,
+}
+
+impl UserResponse {
+    pub(crate) fn new(user: UserInfo, profile: Option<UserProfile>) -> Self {
+        UserResponse {
+            user_address: user.user_address,
+            username: user.username,
+            is_registered: user.is_registered,
+            created_at: user.created_at.to_rfc3339(),
+            tnx_hash: user.tnx_hash,
+            profile,
+        }
+    }
 }
 
 #[utoipa::path(
@@ -42,8 +59,16 @@ pub struct UserResponse {
             "user_address": "0x1234567890abcdef1234567890abcdef12345678",
             "username": "john_doe",
             "is_registered": true,
-            "created_at": "2025-08-25 19:22:00",
-            "tnx_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
+            "created_at": "2025-08-25T19:22:00Z",
+            "tnx_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890",
+            "profile": {
+                "display_name": "John Doe",
+                "avatar_url": "https://cdn.example.com/avatars/john.png",
+                "email": null,
+                "profile_visibility": "public",
+                "show_email": false,
+                "profile_updated_at": "2025-10-08T09:00:00Z"
+            }
         })),
         (status = 400, description = "Neither user_address nor username provided"),
         (status = 404, description = "User not found"),
@@ -87,9 +112,16 @@ pub async fn get_user(
         user_query = user_query.filter(users_info::username.eq(username));
     }
 
-    // Execute the query
-    match user_query.first::<UserInfo>(conn) {
-        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+    // Execute the query; others only get what the privacy settings allow
+    match user_query
+        .select((UserInfo::as_select(), UserProfile::as_select()))
+        .first::<(UserInfo, UserProfile)>(conn)
+    {
+        Ok((user, profile)) => (
+            StatusCode::OK,
+            Json(UserResponse::new(user, visible_profile(profile))),
+        )
+            .into_response(),
         Err(DieselError::NotFound) => (
             StatusCode::NOT_FOUND,
             Json(serde_json::json!({"error": "User not found"})),
@@ -1,10 +1,9 @@
 use axum::{extract::{Query, State}, http::StatusCode, response::IntoResponse, Json};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
-use diesel::prelude::*;
 use std::sync::Arc;
-use crate::schema::users_info;
 use crate::config::app_state::AppState;
+use crate::models::username_policy::{check_name, is_policy_rejection, is_taken, NameKind};
 
 #[derive(Deserialize, ToSchema)]
 pub struct UserExistsQuery {
@@ -14,7 +13,13 @@ pub struct UserExistsQuery {
 
 #[derive(Serialize, ToSchema)]
 pub struct UserExistsResponse {
+    // used by a user or manufacturer, ignoring case
     exists: bool,
+    // whether the name could be registered right now
+    available: bool,
+    // why it cannot, when it cannot
+    #[schema(nullable = true, example = "Invalid username: too similar to manufacturer SAMSUNG")]
+    reason: Option<String>,
 }
 
 #[utoipa::path(
@@ -24,8 +29,10 @@ pub struct UserExistsResponse {
         ("username" = String, Query, description = "User's username", example = "john_doe")
     ),
     responses(
-        (status = 200, description = "Check if user exists", body = UserExistsResponse, example = json!({
-            "exists": true
+        (status = 200, description = "Whether the username is taken and can be registered", body = UserExistsResponse, example = json!({
+            "exists": true,
+            "available": false,
+            "reason": "Username is already taken"
         })),
         (status = 400, description = "Username not provided"),
         (status = 500, description = "Internal server error")
@@ -44,22 +51,35 @@ pub async fn user_exists(
         ).into_response()
     }).unwrap();
 
-    // Check if user exists
-    let exists: bool = users_info::table
-        .filter(users_info::username.eq(&query.username))
-        .select(diesel::dsl::count_star())
-        .first::<i64>(conn)
-        .map(|count| count > 0)
-        .map_err(|e| {
+    // Check if the name is taken anywhere, then the rest of the naming policy
+    let exists = match is_taken(conn, &query.username, None) {
+        Ok(exists) => exists,
+        Err(e) => {
             eprintln!("Error checking user existence for {}: {:?}", query.username, e);
-            (
+            return (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(serde_json::json!({"error": format!("Internal server error: {}", e)})),
-            ).into_response()
-        }).unwrap();
+            ).into_response();
+        }
+    };
+    let reason = match check_name(conn, &query.username, NameKind::User, None) {
+        Ok(()) => None,
+        Err(e) if is_policy_rejection(&e) => Some(e.to_string()),
+        Err(e) => {
+            eprintln!("Error checking username policy for {}: {:?}", query.username, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Internal server error: {}", e)})),
+            ).into_response();
+        }
+    };
 
     (
         StatusCode::OK,
-        Json(UserExistsResponse { exists }),
+        Json(UserExistsResponse {
+            exists,
+            available: reason.is_none(),
+            reason,
+        }),
     ).into_response()
 }
\ No newline at end of file
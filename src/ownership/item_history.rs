@@ -0,0 +1,428 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::{Item, ItemHistoryEvent};
+use crate::models::pagination::{keyset_page, Page, SortBy, SortOrder};
+use crate::models::signed_document::{signed_document, SignedDocument};
+use crate::ownership::item_status::{item_status, ItemStatus};
+use crate::schema::{item_history_events, items, users_info};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::PgConnection;
+use ethers::providers::Middleware;
+use ethers::types::H256;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryEventKind {
+    Created,
+    Transferred,
+    Flagged,
+    FlagCleared,
+}
+
+impl HistoryEventKind {
+    // kind column of item_history_events
+    fn parse(kind: &str) -> eyre::Result<Self> {
+        match kind {
+            "created" => Ok(HistoryEventKind::Created),
+            "transferred" => Ok(HistoryEventKind::Transferred),
+            "flagged" => Ok(HistoryEventKind::Flagged),
+            "flag_cleared" => Ok(HistoryEventKind::FlagCleared),
+            _ => Err(eyre::eyre!("Unknown history event kind: {}", kind)),
+        }
+    }
+}
+
+// One entry of an item's chain of custody
+#[derive(Serialize, ToSchema, Clone)]
+pub struct HistoryEvent {
+    kind: HistoryEventKind,
+    #[schema(nullable = true, example = "0x1234567890abcdef1234567890abcdef12345678")]
+    from: Option<String>,
+    #[schema(nullable = true, example = "alice")]
+    from_username: Option<String>,
+    #[schema(nullable = true, example = "0xabcdef1234567890abcdef1234567890abcdef12")]
+    to: Option<String>,
+    #[schema(nullable = true, example = "bob")]
+    to_username: Option<String>,
+    // flag raised or cleared, for flag events
+    #[schema(nullable = true)]
+    status: Option<ItemStatus>,
+    #[schema(nullable = true)]
+    reason: Option<String>,
+    #[schema(nullable = true)]
+    tnx_hash: Option<String>,
+    #[schema(nullable = true, example = 18234567)]
+    block_number: Option<u64>,
+    // unix seconds of the block that included `tnx_hash`
+    #[schema(nullable = true, example = 1726563600)]
+    block_timestamp: Option<i64>,
+    // block time for on-chain events, otherwise when the backend recorded the event
+    #[schema(value_type = String, example = "2025-09-17T09:00:12Z")]
+    recorded_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct HistoryQuery {
+    pub(crate) cursor: Option<String>,
+    #[schema(example = 20)]
+    pub(crate) limit: Option<i64>,
+    // oldest first unless set to desc
+    pub(crate) order: Option<SortOrder>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ItemHistoryResponse {
+    item_id: String,
+    status: ItemStatus,
+    total: i64,
+    events: Vec<HistoryEvent>,
+    #[schema(nullable = true)]
+    next_cursor: Option<String>,
+}
+
+// Full history of an item, for export
+#[derive(Serialize)]
+struct HistoryDocument {
+    item_id: String,
+    name: String,
+    serial: String,
+    manufacturer: String,
+    owner: String,
+    status: ItemStatus,
+    events: Vec<HistoryEvent>,
+    generated_at: DateTime<Utc>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/item/{item_id}/history",
+    params(
+        ("item_id" = String, Path, description = "The unique ID of the item", example = "item123"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<i64>, Query, description = "Events per page (default 20, max 100)", example = 20),
+        ("order" = Option<SortOrder>, Query, description = "Sort order: `asc` (oldest first, default) or `desc`", example = "asc")
+    ),
+    responses(
+        (status = 200, description = "Chain of custody of the item", body = ItemHistoryResponse, example = json!({
+            "item_id": "item123",
+            "status": "active",
+            "total": 2,
+            "events": [
+                {
+                    "kind": "created",
+                    "from": null,
+                    "from_username": null,
+                    "to": "0x1234567890abcdef1234567890abcdef12345678",
+                    "to_username": "alice",
+                    "status": null,
+                    "reason": null,
+                    "tnx_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890",
+                    "block_number": 18234567,
+                    "block_timestamp": 1726563600,
+                    "recorded_at": "2025-09-17T09:00:12Z"
+                },
+                {
+                    "kind": "transferred",
+                    "from": "0x1234567890abcdef1234567890abcdef12345678",
+                    "from_username": "alice",
+                    "to": "0xabcdef1234567890abcdef1234567890abcdef12",
+                    "to_username": "bob",
+                    "status": null,
+                    "reason": null,
+                    "tnx_hash": "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+                    "block_number": 18240012,
+                    "block_timestamp": 1726629000,
+                    "recorded_at": "2025-09-18T03:10:04Z"
+                }
+            ],
+            "next_cursor": null
+        })),
+        (status = 400, description = "Invalid pagination parameters"),
+        (status = 404, description = "Item not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Items"
+)]
+pub async fn get_item_history(
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> impl IntoResponse {
+    match get_item_history_internal(&state, &item_id, &query).await {
+        Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
+        Err(e) => {
+            eprintln!("Error fetching history of item {}: {:?}", item_id, e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/item/{item_id}/history/export",
+    params(
+        ("item_id" = String, Path, description = "The unique ID of the item", example = "item123")
+    ),
+    responses(
+        (status = 200, description = "Complete history signed by the backend wallet, as a JSON download", body = SignedDocument),
+        (status = 404, description = "Item not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Items"
+)]
+pub async fn export_item_history(
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<String>,
+) -> impl IntoResponse {
+    match export_item_history_internal(&state, &item_id).await {
+        Ok(signed) => (
+            StatusCode::OK,
+            [(
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}-history.json\"", item_id),
+            )],
+            AxumJson(signed),
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("Error exporting history of item {}: {:?}", item_id, e);
+            error_response(e)
+        }
+    }
+}
+
+fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.contains("Invalid pagination") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Item not found") => (StatusCode::NOT_FOUND, e.to_string()),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, AxumJson(json!({"error": message}))).into_response()
+}
+
+async fn get_item_history_internal(
+    state: &Arc<AppState>,
+    item_id: &str,
+    query: &HistoryQuery,
+) -> eyre::Result<ItemHistoryResponse> {
+    let page = Page::new(
+        query.cursor.as_deref(),
+        query.limit,
+        Some(SortBy::CreatedAt),
+        Some(query.order.unwrap_or(SortOrder::Asc)),
+    )?;
+
+    let (mut events, status, total, next_cursor) = {
+        let conn = &mut state
+            .db_pool
+            .get()
+            .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+
+        load_item(conn, item_id)?;
+
+        let total = item_history_events::table
+            .filter(item_history_events::item_id.eq(item_id))
+            .count()
+            .get_result::<i64>(conn)
+            .map_err(|e| eyre::eyre!("Failed to count history events: {}", e))?;
+
+        // event_id doubles as the name key; history is only ever sorted by time
+        let rows = keyset_page!(
+            item_history_events::table
+                .filter(item_history_events::item_id.eq(item_id))
+                .select(ItemHistoryEvent::as_select())
+                .into_boxed(),
+            &page,
+            item_history_events::recorded_at,
+            item_history_events::event_id,
+            item_history_events::event_id,
+            String
+        )
+        .load::<ItemHistoryEvent>(conn)
+        .map_err(|e| eyre::eyre!("Failed to fetch history events: {}", e))?;
+
+        let (rows, next_cursor) = page.finish(rows, |row| {
+            (row.recorded_at, row.event_id.clone(), row.event_id.clone())
+        });
+        (
+            history_events(conn, rows)?,
+            item_status(conn, item_id)?,
+            total,
+            next_cursor,
+        )
+    };
+    attach_block_times(state, &mut events).await;
+
+    Ok(ItemHistoryResponse {
+        item_id: item_id.to_string(),
+        status,
+        total,
+        events,
+        next_cursor,
+    })
+}
+
+async fn export_item_history_internal(
+    state: &Arc<AppState>,
+    item_id: &str,
+) -> eyre::Result<SignedDocument> {
+    let (item, mut events, status) = {
+        let conn = &mut state
+            .db_pool
+            .get()
+            .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+
+        let item = load_item(conn, item_id)?;
+        let rows = item_history_events::table
+            .filter(item_history_events::item_id.eq(item_id))
+            .order((
+                item_history_events::recorded_at.asc(),
+                item_history_events::event_id.asc(),
+            ))
+            .select(ItemHistoryEvent::as_select())
+            .load::<ItemHistoryEvent>(conn)
+            .map_err(|e| eyre::eyre!("Failed to fetch history events: {}", e))?;
+        let events = history_events(conn, rows)?;
+        (item, events, item_status(conn, item_id)?)
+    };
+    attach_block_times(state, &mut events).await;
+
+    let wallet = state.ownership_contract.client().signer().clone();
+    signed_document(
+        &wallet,
+        &HistoryDocument {
+            item_id: item.item_id,
+            name: item.name,
+            serial: item.serial,
+            manufacturer: item.manufacturer,
+            owner: item.owner,
+            status,
+            events,
+            generated_at: Utc::now(),
+        },
+    )
+    .await
+}
+
+fn load_item(conn: &mut PgConnection, item_id: &str) -> eyre::Result<Item> {
+    items::table
+        .filter(items::item_id.eq(item_id))
+        .select(Item::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?
+        .ok_or_else(|| eyre::eyre!("Item not found"))
+}
+
+// Rows of item_history_events with the usernames of the addresses involved
+fn history_events(
+    conn: &mut PgConnection,
+    rows: Vec<ItemHistoryEvent>,
+) -> eyre::Result<Vec<HistoryEvent>> {
+    let addresses = rows
+        .iter()
+        .flat_map(|row| [row.from_address.clone(), row.to_address.clone()])
+        .flatten()
+        .collect();
+    let usernames = usernames(conn, addresses)?;
+    let username = |address: &Option<String>| {
+        address
+            .as_ref()
+            .and_then(|address| usernames.get(&address.to_lowercase()).cloned())
+    };
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(HistoryEvent {
+                kind: HistoryEventKind::parse(&row.kind)?,
+                from_username: username(&row.from_address),
+                from: row.from_address,
+                to_username: username(&row.to_address),
+                to: row.to_address,
+                status: row.status.as_deref().map(ItemStatus::parse).transpose()?,
+                reason: row.reason,
+                tnx_hash: row.tnx_hash,
+                block_number: row.block_number.map(|number| number as u64),
+                block_timestamp: row.block_time.map(|time| time.timestamp()),
+                recorded_at: row.recorded_at,
+            })
+        })
+        .collect()
+}
+
+// Registered usernames keyed by lowercased address
+fn usernames(
+    conn: &mut PgConnection,
+    addresses: Vec<String>,
+) -> eyre::Result<HashMap<String, String>> {
+    let users = users_info::table
+        .filter(users_info::user_address.eq_any(addresses))
+        .select((users_info::user_address, users_info::username))
+        .load::<(String, String)>(conn)
+        .map_err(|e| eyre::eyre!("Failed to query users: {}", e))?;
+
+    Ok(users
+        .into_iter()
+        .map(|(address, username)| (address.to_lowercase(), username))
+        .collect())
+}
+
+// Rows indexed before block data was stored have none; their block is looked up from the
+// transaction receipt. An event whose receipt cannot be fetched keeps only its recorded_at.
+async fn attach_block_times(state: &Arc<AppState>, events: &mut [HistoryEvent]) {
+    let client = state.ownership_contract.client();
+    let mut block_times: HashMap<u64, i64> = HashMap::new();
+
+    for event in events.iter_mut() {
+        if event.block_timestamp.is_some() {
+            continue;
+        }
+        let Some(tnx_hash) = event.tnx_hash.as_deref() else {
+            continue;
+        };
+        let Ok(hash) = tnx_hash.parse::<H256>() else {
+            continue;
+        };
+
+        let block_number = match client.get_transaction_receipt(hash).await {
+            Ok(Some(receipt)) => match receipt.block_number {
+                Some(number) => number.as_u64(),
+                None => continue,
+            },
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("Failed to fetch receipt for {}: {:?}", tnx_hash, e);
+                continue;
+            }
+        };
+        event.block_number = Some(block_number);
+
+        if let Some(timestamp) = block_times.get(&block_number) {
+            event.block_timestamp = Some(*timestamp);
+            continue;
+        }
+        match client.get_block(block_number).await {
+            Ok(Some(block)) => {
+                let timestamp = block.timestamp.as_u64() as i64;
+                block_times.insert(block_number, timestamp);
+                event.block_timestamp = Some(timestamp);
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("Failed to fetch block {}: {:?}", block_number, e),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use crate::contract_models::ItemFlag;
+use crate::schema::item_flags;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Lifecycle flag of an indexed item. `Active` is never stored: it is what an item is in
+// while none of its item_flags rows is open (cleared_at IS NULL).
+//
+//   owner:        active <-> stolen, active <-> lost
+//   manufacturer: active <-> recalled
+//   either:       active  -> destroyed (final, cannot be cleared)
+//
+// Any flag other than `Active` blocks ownership transfers.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemStatus {
+    Active,
+    Stolen,
+    Lost,
+    Recalled,
+    Destroyed,
+}
+
+// Who is raising or clearing a flag, relative to the item
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlagRole {
+    Owner,
+    Manufacturer,
+}
+
+impl FlagRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FlagRole::Owner => "owner",
+            FlagRole::Manufacturer => "manufacturer",
+        }
+    }
+}
+
+impl ItemStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemStatus::Active => "active",
+            ItemStatus::Stolen => "stolen",
+            ItemStatus::Lost => "lost",
+            ItemStatus::Recalled => "recalled",
+            ItemStatus::Destroyed => "destroyed",
+        }
+    }
+
+    pub fn parse(status: &str) -> Result<Self> {
+        match status {
+            "active" => Ok(ItemStatus::Active),
+            "stolen" => Ok(ItemStatus::Stolen),
+            "lost" => Ok(ItemStatus::Lost),
+            "recalled" => Ok(ItemStatus::Recalled),
+            "destroyed" => Ok(ItemStatus::Destroyed),
+            other => Err(eyre::eyre!("Unknown item status: {}", other)),
+        }
+    }
+
+    pub fn can_be_raised_by(&self, role: FlagRole) -> bool {
+        matches!(
+            (self, role),
+            (ItemStatus::Stolen, FlagRole::Owner)
+                | (ItemStatus::Lost, FlagRole::Owner)
+                | (ItemStatus::Recalled, FlagRole::Manufacturer)
+                | (ItemStatus::Destroyed, _)
+        )
+    }
+
+    pub fn can_be_cleared_by(&self, role: FlagRole) -> bool {
+        *self != ItemStatus::Destroyed && self.can_be_raised_by(role)
+    }
+}
+
+// The item's open flag, if any
+pub fn active_flag(conn: &mut PgConnection, item_id: &str) -> Result<Option<ItemFlag>> {
+    item_flags::table
+        .filter(item_flags::item_id.eq(item_id))
+        .filter(item_flags::cleared_at.is_null())
+        .select(ItemFlag::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query item flags: {}", e))
+}
+
+pub fn item_status(conn: &mut PgConnection, item_id: &str) -> Result<ItemStatus> {
+    match active_flag(conn, item_id)? {
+        Some(flag) => ItemStatus::parse(&flag.status),
+        None => Ok(ItemStatus::Active),
+    }
+}
+
+// Refuses to hand an item on while it is flagged
+pub fn ensure_transferable(conn: &mut PgConnection, item_id: &str) -> Result<()> {
+    match item_status(conn, item_id)? {
+        ItemStatus::Active => Ok(()),
+        status => Err(eyre::eyre!(
+            "Item is flagged as {} and cannot be transferred",
+            status.as_str()
+        )),
+    }
+}
@@ -0,0 +1,142 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::OwnershipClaim;
+use crate::models::pagination::{keyset_page, Page, SortBy, SortOrder};
+use crate::schema::ownership_claims;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct ClaimsQuery {
+    #[schema(example = "item123")]
+    pub item_id: Option<String>,
+    // matches either side of the transfer
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub owner: Option<String>,
+    #[schema(value_type = Option<String>, example = "2025-09-01T00:00:00Z")]
+    pub created_from: Option<DateTime<Utc>>,
+    #[schema(value_type = Option<String>, example = "2025-10-01T00:00:00Z")]
+    pub created_to: Option<DateTime<Utc>>,
+    pub cursor: Option<String>,
+    #[schema(example = 20)]
+    pub limit: Option<i64>,
+    pub sort: Option<SortBy>,
+    pub order: Option<SortOrder>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ClaimsResponse {
+    claims: Vec<OwnershipClaim>,
+    #[schema(example = 42)]
+    total: i64,
+    #[schema(nullable = true)]
+    next_cursor: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/claims",
+    params(
+        ("item_id" = Option<String>, Query, description = "Only claims of this item", example = "item123"),
+        ("owner" = Option<String>, Query, description = "Only claims where this address is the old or the new owner", example = "0x1234567890abcdef1234567890abcdef12345678"),
+        ("created_from" = Option<String>, Query, description = "Only claims at or after this time (RFC 3339)", example = "2025-09-01T00:00:00Z"),
+        ("created_to" = Option<String>, Query, description = "Only claims at or before this time (RFC 3339)", example = "2025-10-01T00:00:00Z"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<i64>, Query, description = "Claims per page (default 20, max 100)", example = 20),
+        ("sort" = Option<SortBy>, Query, description = "Sort field: `created_at` (default) or `name` (item_id)", example = "created_at"),
+        ("order" = Option<SortOrder>, Query, description = "Sort order: `desc` (default) or `asc`", example = "desc")
+    ),
+    responses(
+        (status = 200, description = "One page of ownership claims", body = ClaimsResponse),
+        (status = 400, description = "Invalid pagination"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Ownership"
+)]
+pub async fn list_claims(
+    Query(query): Query<ClaimsQuery>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match list_claims_internal(&state, &query).await {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(e) => {
+            eprintln!("Error listing ownership claims: {:?}", e);
+            let (status, message) = match e.to_string().as_str() {
+                s if s.contains("Invalid pagination") => (StatusCode::BAD_REQUEST, e.to_string()),
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Internal server error: {}", e),
+                ),
+            };
+            (status, Json(serde_json::json!({"error": message}))).into_response()
+        }
+    }
+}
+
+fn filtered_claims(query: &ClaimsQuery) -> ownership_claims::BoxedQuery<'_, Pg> {
+    let mut claims = ownership_claims::table.into_boxed();
+
+    if let Some(item_id) = &query.item_id {
+        claims = claims.filter(ownership_claims::item_id.eq(item_id));
+    }
+    if let Some(owner) = &query.owner {
+        claims = claims.filter(
+            ownership_claims::old_owner
+                .ilike(owner)
+                .or(ownership_claims::new_owner.ilike(owner)),
+        );
+    }
+    if let Some(created_from) = query.created_from {
+        claims = claims.filter(ownership_claims::created_at.ge(created_from));
+    }
+    if let Some(created_to) = query.created_to {
+        claims = claims.filter(ownership_claims::created_at.le(created_to));
+    }
+
+    claims
+}
+
+async fn list_claims_internal(state: &Arc<AppState>, query: &ClaimsQuery) -> Result<ClaimsResponse> {
+    let page = Page::new(query.cursor.as_deref(), query.limit, query.sort, query.order)?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let total = filtered_claims(query)
+        .count()
+        .get_result::<i64>(conn)
+        .map_err(|e| eyre::eyre!("Failed to count ownership claims: {}", e))?;
+
+    let rows = keyset_page!(
+        filtered_claims(query).select(OwnershipClaim::as_select()),
+        &page,
+        ownership_claims::created_at,
+        ownership_claims::item_id,
+        ownership_claims::id,
+        i32
+    )
+    .load::<OwnershipClaim>(conn)
+    .map_err(|e| eyre::eyre!("Failed to fetch ownership claims: {}", e))?;
+
+    let (claims, next_cursor) = page.finish(rows, |claim| {
+        (claim.created_at, claim.item_id.clone(), claim.id.to_string())
+    });
+
+    Ok(ClaimsResponse {
+        claims,
+        total,
+        next_cursor,
+    })
+}
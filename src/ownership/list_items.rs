@@ -0,0 +1,90 @@
+use crate::config::app_state::AppState;
+use crate::models::pagination::{Page, SortBy, SortOrder};
+use crate::ownership::get_my_items::{items_error_response, load_items_page, ItemFilter, ItemsResponse};
+use crate::ownership::item_status::ItemStatus;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use eyre::Result;
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+// Same filters as /api/items/owner with the owner optional, e.g. all items of a manufacturer
+#[derive(Deserialize, ToSchema)]
+pub struct ListItemsQuery {
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub owner: Option<String>,
+    #[schema(example = "SAMSUNG")]
+    pub manufacturer: Option<String>,
+    #[schema(value_type = Option<String>, example = "2025-09-01T00:00:00Z")]
+    pub created_from: Option<DateTime<Utc>>,
+    #[schema(value_type = Option<String>, example = "2025-10-01T00:00:00Z")]
+    pub created_to: Option<DateTime<Utc>>,
+    pub status: Option<ItemStatus>,
+    #[schema(example = "color:gold,storage_gb:128")]
+    pub attributes: Option<String>,
+    pub cursor: Option<String>,
+    #[schema(example = 20)]
+    pub limit: Option<i64>,
+    pub sort: Option<SortBy>,
+    pub order: Option<SortOrder>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/items",
+    params(
+        ("owner" = Option<String>, Query, description = "Owner's blockchain address", example = "0x1234567890abcdef1234567890abcdef12345678"),
+        ("manufacturer" = Option<String>, Query, description = "Manufacturer name, case-insensitive", example = "SAMSUNG"),
+        ("created_from" = Option<String>, Query, description = "Only items created at or after this time (RFC 3339)", example = "2025-09-01T00:00:00Z"),
+        ("created_to" = Option<String>, Query, description = "Only items created at or before this time (RFC 3339)", example = "2025-10-01T00:00:00Z"),
+        ("status" = Option<ItemStatus>, Query, description = "Lifecycle status", example = "active"),
+        ("attributes" = Option<String>, Query, description = "Only items whose metadata has all of these comma-separated key:value attributes", example = "color:gold,storage_gb:128"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<i64>, Query, description = "Items per page (default 20, max 100)", example = 20),
+        ("sort" = Option<SortBy>, Query, description = "Sort field: `created_at` (default) or `name`", example = "created_at"),
+        ("order" = Option<SortOrder>, Query, description = "Sort order: `desc` (default) or `asc`", example = "desc")
+    ),
+    responses(
+        (status = 200, description = "One page of matching items", body = ItemsResponse),
+        (status = 400, description = "Malformed attributes filter or invalid pagination"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Items"
+)]
+pub async fn list_items(
+    Query(query): Query<ListItemsQuery>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match list_items_internal(&state, &query).await {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(e) => {
+            eprintln!("Error listing items: {:?}", e);
+            items_error_response(e)
+        }
+    }
+}
+
+async fn list_items_internal(state: &Arc<AppState>, query: &ListItemsQuery) -> Result<ItemsResponse> {
+    let page = Page::new(query.cursor.as_deref(), query.limit, query.sort, query.order)?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let filter = ItemFilter {
+        owner: query.owner.as_deref(),
+        manufacturer: query.manufacturer.as_deref(),
+        created_from: query.created_from,
+        created_to: query.created_to,
+        status: query.status,
+        attributes: query.attributes.as_deref(),
+    };
+    load_items_page(conn, &filter, &page)
+}
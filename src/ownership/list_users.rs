@@ -0,0 +1,136 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::{UserInfo, UserProfile};
+use crate::models::pagination::{keyset_page, Page, SortBy, SortOrder};
+use crate::ownership::get_user_info::UserResponse;
+use crate::ownership::user_profile::visible_profile;
+use crate::schema::users_info;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct UsersQuery {
+    #[schema(example = true)]
+    pub is_registered: Option<bool>,
+    #[schema(value_type = Option<String>, example = "2025-09-01T00:00:00Z")]
+    pub created_from: Option<DateTime<Utc>>,
+    #[schema(value_type = Option<String>, example = "2025-10-01T00:00:00Z")]
+    pub created_to: Option<DateTime<Utc>>,
+    pub cursor: Option<String>,
+    #[schema(example = 20)]
+    pub limit: Option<i64>,
+    pub sort: Option<SortBy>,
+    pub order: Option<SortOrder>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct UsersResponse {
+    users: Vec<UserResponse>,
+    #[schema(example = 42)]
+    total: i64,
+    #[schema(nullable = true)]
+    next_cursor: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users",
+    params(
+        ("is_registered" = Option<bool>, Query, description = "Only registered (or unregistered) users", example = true),
+        ("created_from" = Option<String>, Query, description = "Only users registered at or after this time (RFC 3339)", example = "2025-09-01T00:00:00Z"),
+        ("created_to" = Option<String>, Query, description = "Only users registered at or before this time (RFC 3339)", example = "2025-10-01T00:00:00Z"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<i64>, Query, description = "Users per page (default 20, max 100)", example = 20),
+        ("sort" = Option<SortBy>, Query, description = "Sort field: `created_at` (default) or `name` (username)", example = "created_at"),
+        ("order" = Option<SortOrder>, Query, description = "Sort order: `desc` (default) or `asc`", example = "desc")
+    ),
+    responses(
+        (status = 200, description = "One page of users", body = UsersResponse),
+        (status = 400, description = "Invalid pagination"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Users"
+)]
+pub async fn list_users(
+    Query(query): Query<UsersQuery>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match list_users_internal(&state, &query).await {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(e) => {
+            eprintln!("Error listing users: {:?}", e);
+            let (status, message) = match e.to_string().as_str() {
+                s if s.contains("Invalid pagination") => (StatusCode::BAD_REQUEST, e.to_string()),
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Internal server error: {}", e),
+                ),
+            };
+            (status, Json(serde_json::json!({"error": message}))).into_response()
+        }
+    }
+}
+
+fn filtered_users(query: &UsersQuery) -> users_info::BoxedQuery<'static, Pg> {
+    let mut users = users_info::table.into_boxed();
+
+    if let Some(is_registered) = query.is_registered {
+        users = users.filter(users_info::is_registered.eq(is_registered));
+    }
+    if let Some(created_from) = query.created_from {
+        users = users.filter(users_info::created_at.ge(created_from));
+    }
+    if let Some(created_to) = query.created_to {
+        users = users.filter(users_info::created_at.le(created_to));
+    }
+
+    users
+}
+
+async fn list_users_internal(state: &Arc<AppState>, query: &UsersQuery) -> Result<UsersResponse> {
+    let page = Page::new(query.cursor.as_deref(), query.limit, query.sort, query.order)?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let total = filtered_users(query)
+        .count()
+        .get_result::<i64>(conn)
+        .map_err(|e| eyre::eyre!("Failed to count users: {}", e))?;
+
+    let rows = keyset_page!(
+        filtered_users(query).select((UserInfo::as_select(), UserProfile::as_select())),
+        &page,
+        users_info::created_at,
+        users_info::username,
+        users_info::user_address,
+        String
+    )
+    .load::<(UserInfo, UserProfile)>(conn)
+    .map_err(|e| eyre::eyre!("Failed to fetch users: {}", e))?;
+
+    let (users, next_cursor) = page.finish(rows, |(user, _)| {
+        (user.created_at, user.username.clone(), user.user_address.clone())
+    });
+
+    Ok(UsersResponse {
+        users: users
+            .into_iter()
+            .map(|(user, profile)| UserResponse::new(user, visible_profile(profile)))
+            .collect(),
+        total,
+        next_cursor,
+    })
+}
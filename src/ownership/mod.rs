@@ -6,4 +6,17 @@ pub mod get_my_items;
 pub mod transfer_ownership_code;
 pub mod get_transfer_code;
 pub mod revoke_ownership_code;
-pub mod get_item;
\ No newline at end of file
+pub mod get_item;
+pub mod expire_ownership_codes;
+pub mod ownership_code;
+pub mod transfer_state;
+pub mod onchain_ownership_code;
+pub mod ownership_mismatch;
+pub mod item_status;
+pub mod flag_item;
+pub mod item_history;
+pub mod ownership_proof;
+pub mod list_items;
+pub mod list_users;
+pub mod list_claims;
+pub mod user_profile;
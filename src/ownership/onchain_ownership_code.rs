@@ -0,0 +1,324 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::OwnershipCode;
+use crate::ownership::item_status::ensure_transferable;
+use crate::ownership::ownership_code::{hash_code, normalize_code, CodeFormat};
+use crate::ownership::transfer_ownership_code::{
+    generate_ownership_code_internal, GenerateOwnershipCodeQuery,
+};
+use crate::ownership::transfer_state::TransferState;
+use crate::schema::ownership_codes;
+use axum::{
+    extract::{Json, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use ethers::types::{Address, Bytes};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+// With on-chain codes the contract checks msg.sender, so the backend cannot send these
+// transactions itself. Each endpoint validates against the index and returns the call
+// for the owner's (or temp owner's) wallet to sign and send.
+
+#[derive(Deserialize, ToSchema)]
+pub struct CommitOwnershipCodeRequest {
+    #[schema(example = "item123")]
+    pub item_id: String,
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub caller: String,
+    #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef12")]
+    pub temp_owner: String,
+    pub format: Option<CodeFormat>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ClaimWithCodeRequest {
+    #[schema(example = "item123")]
+    pub item_id: String,
+    #[schema(example = "7KQ2-M9XD-4TPR-VH3C")]
+    pub ownership_code: String,
+    #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef12")]
+    pub caller: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RevokeOnchainCodeRequest {
+    #[schema(example = "item123")]
+    pub item_id: String,
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub caller: String,
+}
+
+// Unsigned transaction for the caller's wallet
+#[derive(Serialize, ToSchema)]
+pub struct PreparedTransaction {
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    from: String,
+    #[schema(example = "0x9876543210fedcba9876543210fedcba98765432")]
+    to: String,
+    #[schema(example = "0x5c1b5d0e")]
+    data: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CommitOwnershipCodeResponse {
+    ownership_code: String,
+    format: CodeFormat,
+    #[schema(example = "0x5d1f6a0e2b0c4f3e9a7d8c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f")]
+    code_hash: String,
+    #[schema(value_type = String)]
+    expires_at: DateTime<Utc>,
+    transaction: PreparedTransaction,
+}
+
+// Define the error response struct
+#[derive(Serialize, ToSchema)]
+struct ErrorResponse {
+    error: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ownership/code/commit",
+    request_body = CommitOwnershipCodeRequest,
+    responses(
+        (status = 200, description = "Code generated; the owner must send `transaction` to commit its hash on-chain", body = CommitOwnershipCodeResponse),
+        (status = 400, description = "Invalid input (e.g., caller is temp_owner or caller not registered)", body = ErrorResponse),
+        (status = 404, description = "Item not found or caller is not the owner", body = ErrorResponse),
+        (status = 409, description = "Item already has an active transfer code or is flagged (stolen, lost, recalled, destroyed)", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Ownership"
+)]
+pub async fn commit_ownership_code(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<CommitOwnershipCodeRequest>,
+) -> impl IntoResponse {
+    match commit_ownership_code_internal(&state, request).await {
+        Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
+        Err(e) => {
+            eprintln!("Error preparing generateCode transaction: {:?}", e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ownership/code/claim",
+    request_body = ClaimWithCodeRequest,
+    responses(
+        (status = 200, description = "The temp owner must send `claimWithCode` from their wallet", body = PreparedTransaction),
+        (status = 400, description = "Invalid ownership code", body = ErrorResponse),
+        (status = 403, description = "Caller does not match temp_owner", body = ErrorResponse),
+        (status = 404, description = "Ownership code not found for this item", body = ErrorResponse),
+        (status = 409, description = "Item is flagged (stolen, lost, recalled, destroyed) and cannot be transferred", body = ErrorResponse),
+        (status = 410, description = "Ownership code has expired or is no longer active", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Ownership"
+)]
+pub async fn claim_with_code(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ClaimWithCodeRequest>,
+) -> impl IntoResponse {
+    match claim_with_code_internal(&state, &request).await {
+        Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
+        Err(e) => {
+            eprintln!(
+                "Error preparing claimWithCode transaction for item {}: {:?}",
+                request.item_id, e
+            );
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ownership/code/revoke",
+    request_body = RevokeOnchainCodeRequest,
+    responses(
+        (status = 200, description = "The owner must send `revokeCode` from their wallet", body = PreparedTransaction),
+        (status = 400, description = "Caller is not the item owner", body = ErrorResponse),
+        (status = 404, description = "No active transfer code for this item", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Ownership"
+)]
+pub async fn revoke_onchain_code(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<RevokeOnchainCodeRequest>,
+) -> impl IntoResponse {
+    match revoke_onchain_code_internal(&state, &request).await {
+        Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
+        Err(e) => {
+            eprintln!(
+                "Error preparing revokeCode transaction for item {}: {:?}",
+                request.item_id, e
+            );
+            error_response(e)
+        }
+    }
+}
+
+pub(crate) fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.contains("Caller cannot be the temporary owner") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Caller is not registered") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Caller is not the item owner") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Invalid ownership_code") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Invalid address") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Transfer is no longer in the offered state") => (StatusCode::CONFLICT, e.to_string()),
+        s if s.contains("Caller does not match temp_owner") => (StatusCode::FORBIDDEN, e.to_string()),
+        s if s.contains("not found") => (StatusCode::NOT_FOUND, e.to_string()),
+        s if s.contains("No active transfer code") => (StatusCode::NOT_FOUND, e.to_string()),
+        s if s.contains("Item already has an active transfer code") => (StatusCode::CONFLICT, e.to_string()),
+        s if s.contains("Item ownership is out of sync with the chain") => (StatusCode::CONFLICT, e.to_string()),
+        s if s.contains("cannot be transferred") => (StatusCode::CONFLICT, e.to_string()),
+        s if s.contains("Failed to confirm ownership on chain") => (StatusCode::BAD_GATEWAY, e.to_string()),
+        s if s.contains("Ownership code has expired") => (StatusCode::GONE, e.to_string()),
+        s if s.contains("Ownership code is no longer active") => (StatusCode::GONE, e.to_string()),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, AxumJson(json!({"error": message}))).into_response()
+}
+
+pub(crate) async fn commit_ownership_code_internal(
+    state: &Arc<AppState>,
+    request: CommitOwnershipCodeRequest,
+) -> eyre::Result<CommitOwnershipCodeResponse> {
+    let temp_owner: Address = request
+        .temp_owner
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid address: temp_owner"))?;
+
+    let query = GenerateOwnershipCodeQuery {
+        item_id: request.item_id,
+        caller: request.caller,
+        temp_owner: request.temp_owner,
+        format: request.format,
+    };
+
+    // Same checks and offer bookkeeping as the off-chain flow; the indexer attaches the
+    // commit transaction once OwnershipCode is emitted
+    let generated = generate_ownership_code_internal(state, &query).await?;
+    let code_hash = hash_code(&normalize_code(&generated.ownership_code)?);
+
+    let code_hash_bytes: [u8; 32] = hex::decode(code_hash.trim_start_matches("0x"))?
+        .try_into()
+        .map_err(|_| eyre::eyre!("Code hash must be 32 bytes"))?;
+
+    // the contract refuses the code past the same expiry the offer has here
+    let call = state.ownership_contract.generate_code(
+        query.item_id.clone(),
+        code_hash_bytes,
+        temp_owner,
+        generated.expires_at.timestamp() as u64,
+    );
+
+    Ok(CommitOwnershipCodeResponse {
+        ownership_code: generated.ownership_code,
+        format: generated.format,
+        code_hash,
+        expires_at: generated.expires_at,
+        transaction: prepare_transaction(&query.caller, state.ownership_contract.address(), call.calldata())?,
+    })
+}
+
+pub(crate) async fn claim_with_code_internal(
+    state: &Arc<AppState>,
+    request: &ClaimWithCodeRequest,
+) -> eyre::Result<PreparedTransaction> {
+    // The contract hashes exactly the revealed string, so the normalized form is sent
+    let normalized_code = normalize_code(&request.ownership_code)?;
+    let code_hash = hash_code(&normalized_code);
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let ownership_code = ownership_codes::table
+        .filter(ownership_codes::code_hash.eq(&code_hash))
+        .filter(ownership_codes::item_id.eq(&request.item_id))
+        .select(OwnershipCode::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?
+        .ok_or_else(|| eyre::eyre!("Ownership code not found for this item"))?;
+
+    if ownership_code.temp_owner.to_lowercase() != request.caller.to_lowercase() {
+        return Err(eyre::eyre!("Caller does not match temp_owner"));
+    }
+
+    match TransferState::parse(&ownership_code.status)? {
+        TransferState::Offered if ownership_code.expires_at > Utc::now() => {}
+        TransferState::Offered | TransferState::Expired => {
+            return Err(eyre::eyre!("Ownership code has expired"));
+        }
+        _ => return Err(eyre::eyre!("Ownership code is no longer active")),
+    }
+
+    ensure_transferable(conn, &request.item_id)?;
+
+    let call = state
+        .ownership_contract
+        .claim_with_code(request.item_id.clone(), normalized_code);
+
+    prepare_transaction(&request.caller, state.ownership_contract.address(), call.calldata())
+}
+
+async fn revoke_onchain_code_internal(
+    state: &Arc<AppState>,
+    request: &RevokeOnchainCodeRequest,
+) -> eyre::Result<PreparedTransaction> {
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let ownership_code = ownership_codes::table
+        .filter(ownership_codes::item_id.eq(&request.item_id))
+        .filter(ownership_codes::status.eq(TransferState::Offered.as_str()))
+        .select(OwnershipCode::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?
+        .ok_or_else(|| eyre::eyre!("No active transfer code for this item"))?;
+
+    if ownership_code.item_owner.to_lowercase() != request.caller.to_lowercase() {
+        return Err(eyre::eyre!("Caller is not the item owner"));
+    }
+
+    let call = state
+        .ownership_contract
+        .revoke_code(request.item_id.clone());
+
+    prepare_transaction(&request.caller, state.ownership_contract.address(), call.calldata())
+}
+
+pub(crate) fn prepare_transaction(
+    caller: &str,
+    to: Address,
+    calldata: Option<Bytes>,
+) -> eyre::Result<PreparedTransaction> {
+    let from: Address = caller
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid address: caller"))?;
+    let data = calldata.ok_or_else(|| eyre::eyre!("Failed to encode contract call"))?;
+
+    Ok(PreparedTransaction {
+        from: format!("{:?}", from),
+        to: format!("{:?}", to),
+        data: format!("0x{}", hex::encode(data)),
+    })
+}
@@ -0,0 +1,175 @@
+use eyre::Result;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use utoipa::ToSchema;
+
+// Crockford base32 alphabet: no I, L, O or U, so codes survive being read aloud or retyped
+const BASE32_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const BASE32_PAYLOAD_LEN: usize = 15;
+const BASE32_GROUP_LEN: usize = 4;
+
+#[derive(Deserialize, Serialize, ToSchema, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CodeFormat {
+    // 32 random bytes, 0x-prefixed hex
+    #[default]
+    Hex,
+    // 15 random base32 symbols plus a checksum symbol, e.g. 7KQ2-M9XD-4TPR-VH3C
+    Base32,
+}
+
+impl CodeFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CodeFormat::Hex => "hex",
+            CodeFormat::Base32 => "base32",
+        }
+    }
+}
+
+// Generates a new ownership code from the thread-local CSPRNG
+pub fn generate_code(format: CodeFormat) -> String {
+    let mut rng = rand::rng();
+
+    match format {
+        CodeFormat::Hex => {
+            let mut bytes = [0u8; 32];
+            rng.fill(&mut bytes);
+            format!("0x{}", hex::encode(bytes))
+        }
+        CodeFormat::Base32 => {
+            let mut symbols: Vec<u8> = (0..BASE32_PAYLOAD_LEN)
+                .map(|_| BASE32_ALPHABET[rng.random_range(0..BASE32_ALPHABET.len())])
+                .collect();
+            symbols.push(base32_checksum(&symbols));
+
+            symbols
+                .chunks(BASE32_GROUP_LEN)
+                .map(|group| String::from_utf8_lossy(group).into_owned())
+                .collect::<Vec<_>>()
+                .join("-")
+        }
+    }
+}
+
+// Brings user input into the canonical form that was hashed when the code was issued.
+// Base32 input is case-insensitive, ignores separators and maps the usual misreadings.
+pub fn normalize_code(input: &str) -> Result<String> {
+    let trimmed = input.trim();
+
+    if let Some(hex_part) = trimmed.strip_prefix("0x") {
+        if hex_part.len() != 64 || hex::decode(hex_part).is_err() {
+            return Err(eyre::eyre!("Invalid ownership_code format"));
+        }
+        return Ok(format!("0x{}", hex_part.to_lowercase()));
+    }
+
+    // checked up front: folding a wider char into a byte could alias a valid symbol
+    if !trimmed.is_ascii() {
+        return Err(eyre::eyre!("Invalid ownership_code format"));
+    }
+
+    let symbols: Vec<u8> = trimmed
+        .bytes()
+        .filter(|b| !matches!(b, b'-' | b' '))
+        .map(|b| match b.to_ascii_uppercase() {
+            b'O' => b'0',
+            b'I' | b'L' => b'1',
+            other => other,
+        })
+        .collect();
+
+    if symbols.len() != BASE32_PAYLOAD_LEN + 1
+        || !symbols.iter().all(|s| BASE32_ALPHABET.contains(s))
+    {
+        return Err(eyre::eyre!("Invalid ownership_code format"));
+    }
+
+    let (payload, checksum) = symbols.split_at(BASE32_PAYLOAD_LEN);
+    if checksum[0] != base32_checksum(payload) {
+        return Err(eyre::eyre!("Invalid ownership_code checksum"));
+    }
+
+    Ok(String::from_utf8_lossy(&symbols).into_owned())
+}
+
+// Only this hash is persisted; the plain code is returned to the owner once
+pub fn hash_code(normalized_code: &str) -> String {
+    format!(
+        "0x{}",
+        hex::encode(Keccak256::digest(normalized_code.as_bytes()))
+    )
+}
+
+fn base32_checksum(payload: &[u8]) -> u8 {
+    let digest = Keccak256::digest(payload);
+    BASE32_ALPHABET[(digest[0] & 0x1f) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_base32_codes_are_grouped_and_normalize_without_separators() {
+        let code = generate_code(CodeFormat::Base32);
+        let groups: Vec<&str> = code.split('-').collect();
+
+        assert_eq!(groups.len(), 4);
+        assert!(groups.iter().all(|group| group.len() == BASE32_GROUP_LEN));
+        assert_eq!(normalize_code(&code).unwrap(), code.replace('-', ""));
+    }
+
+    #[test]
+    fn base32_input_ignores_case_and_separators() {
+        let code = generate_code(CodeFormat::Base32);
+        let retyped = code.to_lowercase().replace('-', " ");
+
+        assert_eq!(normalize_code(&retyped).unwrap(), normalize_code(&code).unwrap());
+    }
+
+    #[test]
+    fn misread_letters_fold_onto_digits() {
+        let mut payload: Vec<u8> = b"0O1IL0000000000".to_vec();
+        let folded: Vec<u8> = b"001110000000000".to_vec();
+        payload.push(base32_checksum(&folded));
+
+        let normalized = normalize_code(&String::from_utf8(payload).unwrap()).unwrap();
+        assert!(normalized.starts_with("001110000000000"));
+    }
+
+    #[test]
+    fn wrong_checksum_is_rejected() {
+        let code = normalize_code(&generate_code(CodeFormat::Base32)).unwrap();
+        let last = code.as_bytes()[BASE32_PAYLOAD_LEN];
+        let wrong = BASE32_ALPHABET.iter().find(|&&symbol| symbol != last).unwrap();
+        let tampered = format!("{}{}", &code[..BASE32_PAYLOAD_LEN], *wrong as char);
+
+        assert_eq!(
+            normalize_code(&tampered).unwrap_err().to_string(),
+            "Invalid ownership_code checksum"
+        );
+    }
+
+    #[test]
+    fn non_ascii_input_is_rejected() {
+        let code = normalize_code(&generate_code(CodeFormat::Base32)).unwrap();
+        // U+0130 and U+0141 would fold onto valid bytes if truncated
+        for lookalike in ['\u{130}', '\u{141}', 'Ａ'] {
+            let input = format!("{}{}", lookalike, &code[1..]);
+            assert_eq!(
+                normalize_code(&input).unwrap_err().to_string(),
+                "Invalid ownership_code format"
+            );
+        }
+    }
+
+    #[test]
+    fn hex_codes_are_lowercased_and_length_checked() {
+        let code = generate_code(CodeFormat::Hex);
+
+        assert_eq!(normalize_code(&code.to_uppercase().replacen("0X", "0x", 1)).unwrap(), code);
+        assert!(normalize_code(&code[..code.len() - 2]).is_err());
+    }
+}
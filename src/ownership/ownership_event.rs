@@ -1,17 +1,26 @@
 use crate::config::app_state::AppState;
+use crate::events::block_times::{BlockTimes, LogPosition};
 use crate::contract_models::{
-    NewAuthenticitySetting, NewContract, NewItem, NewOwnershipClaim, UserInfo,
+    NewAuthenticitySetting, NewCodeRevokation, NewContract, NewItem, NewOwnershipClaim,
+    OwnershipCode, UserInfo,
 };
 use crate::ownership::ownership_abi::{
-    AuthenticitySetFilter, ItemCreatedFilter, OwnershipCreatedFilter, OwnershipTransferredFilter,
-    UserRegisteredFilter,
+    AuthenticitySetFilter, CodeRevokedFilter, ItemCreatedFilter, OwnershipCodeFilter,
+    OwnershipCreatedFilter, OwnershipTransferredFilter, UserRegisteredFilter, UsernameChangedFilter,
 };
 use crate::ownership::ownership_abi::{Ownership, OwnershipEvents};
+use crate::ownership::ownership_mismatch::resolve_ownership_mismatches;
+use crate::products::product_template::certificate_product;
+use crate::recalls::recall_campaign::attach_open_recalls;
+use crate::ownership::transfer_state::{
+    cancel_offer, cancel_open_offers, settle_offers_after_transfer, TransferState,
+};
 use crate::schema::users_info::username;
 use crate::schema::{
-    authenticity_settings, contracts, items, ownership_claims, ownership_codes, users_info,
+    authenticity_settings, code_revokations, contracts, items, ownership_claims, ownership_codes,
+    users_info,
 };
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use diesel::prelude::*;
 use diesel::{PgConnection, RunQueryDsl};
 use ecdsa::SigningKey;
@@ -25,6 +34,7 @@ use crate::schema::items::manufacturer;
 pub async fn listen_for_ownership_events(state: &Arc<AppState>) -> Result<()> {
     let contract = state.ownership_contract.clone();
     let client = contract.client();
+    let mut block_times = BlockTimes::default();
 
     // Fetch historical events from the last 1,000 blocks in chunks
     let latest_block = client.get_block_number().await.map_err(|e| {
@@ -45,113 +55,32 @@ pub async fn listen_for_ownership_events(state: &Arc<AppState>) -> Result<()> {
             to_block - current_block + 1
         );
 
-        // Event filters for the chunk
-        let ownership_created_filter = contract
-            .event::<OwnershipCreatedFilter>()
-            .from_block(current_block)
-            .to_block(to_block);
-        let user_registered_filter = contract
-            .event::<UserRegisteredFilter>()
-            .from_block(current_block)
-            .to_block(to_block);
-        let item_created_filter = contract
-            .event::<ItemCreatedFilter>()
-            .from_block(current_block)
-            .to_block(to_block);
-        let ownership_transferred_filter = contract
-            .event::<OwnershipTransferredFilter>()
-            .from_block(current_block)
-            .to_block(to_block);
-        let authenticity_set_filter = contract
-            .event::<AuthenticitySetFilter>()
+        // All event types in one query, so they can be applied in chain order: a code
+        // committed after a transfer must not be cancelled by that transfer's settle pass
+        let mut logs = contract
+            .events()
             .from_block(current_block)
-            .to_block(to_block);
-
-        // Fetch historical events with metadata
-        let ownership_created_logs =
-            ownership_created_filter
-                .query_with_meta()
-                .await
-                .map_err(|e| {
-                    eprintln!(
-                        "Failed to query OwnershipCreated events for blocks {} to {}: {:?}",
-                        current_block,
-                        to_block,
-                        e.to_string()
-                    );
-                    eyre::eyre!("Failed to query OwnershipCreated events: {}", e)
-                })?;
-        let user_registered_logs = user_registered_filter
-            .query_with_meta()
-            .await
-            .map_err(|e| {
-                eprintln!(
-                    "Failed to query UserRegistered events for blocks {} to {}: {:?}",
-                    current_block,
-                    to_block,
-                    e.to_string()
-                );
-                eyre::eyre!("Failed to query UserRegistered events: {}", e)
-            })?;
-        let item_created_logs = item_created_filter.query_with_meta().await.map_err(|e| {
-            eprintln!(
-                "Failed to query ItemCreated events for blocks {} to {}: {:?}",
-                current_block,
-                to_block,
-                e.to_string()
-            );
-            eyre::eyre!("Failed to query ItemCreated events: {}", e)
-        })?;
-        let ownership_transferred_logs = ownership_transferred_filter
+            .to_block(to_block)
             .query_with_meta()
             .await
             .map_err(|e| {
                 eprintln!(
-                    "Failed to query OwnershipTransferred events for blocks {} to {}: {:?}",
+                    "Failed to query Ownership events for blocks {} to {}: {:?}",
                     current_block,
                     to_block,
                     e.to_string()
                 );
-                eyre::eyre!("Failed to query OwnershipTransferred events: {}", e)
+                eyre::eyre!("Failed to query Ownership events: {}", e)
             })?;
-        let authenticity_set_logs =
-            authenticity_set_filter
-                .query_with_meta()
-                .await
-                .map_err(|e| {
-                    eprintln!(
-                        "Failed to query AuthenticitySet events for blocks {} to {}: {:?}",
-                        current_block,
-                        to_block,
-                        e.to_string()
-                    );
-                    eyre::eyre!("Failed to query AuthenticitySet events: {}", e)
-                })?;
+        logs.sort_by_key(|(_, meta)| (meta.block_number, meta.log_index));
 
         // Process historical events
         let conn = &mut state.db_pool.get().map_err(|e| {
             eprintln!("Failed to get DB connection: {:?}", e);
             eyre::eyre!("Failed to get DB connection: {}", e)
         })?;
-        for (event, meta) in ownership_created_logs {
-            let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-            process_ownership_created_event(&event, conn, txn_hash)?;
-        }
-        for (event, meta) in user_registered_logs {
-            let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-            process_user_registered_event(&event, conn, txn_hash, &contract).await?;
-        }
-        for (event, meta) in item_created_logs {
-            let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-            process_item_created_event(&event, conn, txn_hash, &contract).await?;
-        }
-        for (event, meta) in ownership_transferred_logs {
-            let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-            process_ownership_transferred_event(&event, conn, txn_hash)?;
-        }
-        for (event, meta) in authenticity_set_logs {
-            let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-            process_authenticity_set_event(&event, conn, txn_hash)?;
+        for (event, meta) in logs {
+            process_event(state, &contract, conn, &mut block_times, event, &meta).await?;
         }
 
         current_block = to_block + 1;
@@ -170,45 +99,12 @@ pub async fn listen_for_ownership_events(state: &Arc<AppState>) -> Result<()> {
 
     loop {
         match stream.next().await {
-            Some(Ok((OwnershipEvents::OwnershipCreatedFilter(event), meta))) => {
-                let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-                let conn = &mut state.db_pool.get().map_err(|e| {
-                    eprintln!("Failed to get DB connection: {:?}", e);
-                    eyre::eyre!("Failed to get DB connection: {}", e)
-                })?;
-                process_ownership_created_event(&event, conn, txn_hash)?;
-            }
-            Some(Ok((OwnershipEvents::UserRegisteredFilter(event), meta))) => {
-                let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
+            Some(Ok((event, meta))) => {
                 let conn = &mut state.db_pool.get().map_err(|e| {
                     eprintln!("Failed to get DB connection: {:?}", e);
                     eyre::eyre!("Failed to get DB connection: {}", e)
                 })?;
-                process_user_registered_event(&event, conn, txn_hash, &contract).await?;
-            }
-            Some(Ok((OwnershipEvents::ItemCreatedFilter(event), meta))) => {
-                let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-                let conn = &mut state.db_pool.get().map_err(|e| {
-                    eprintln!("Failed to get DB connection: {:?}", e);
-                    eyre::eyre!("Failed to get DB connection: {}", e)
-                })?;
-                process_item_created_event(&event, conn, txn_hash, &contract).await?;
-            }
-            Some(Ok((OwnershipEvents::OwnershipTransferredFilter(event), meta))) => {
-                let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-                let conn = &mut state.db_pool.get().map_err(|e| {
-                    eprintln!("Failed to get DB connection: {:?}", e);
-                    eyre::eyre!("Failed to get DB connection: {}", e)
-                })?;
-                process_ownership_transferred_event(&event, conn, txn_hash)?;
-            }
-            Some(Ok((OwnershipEvents::AuthenticitySetFilter(event), meta))) => {
-                let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
-                let conn = &mut state.db_pool.get().map_err(|e| {
-                    eprintln!("Failed to get DB connection: {:?}", e);
-                    eyre::eyre!("Failed to get DB connection: {}", e)
-                })?;
-                process_authenticity_set_event(&event, conn, txn_hash)?;
+                process_event(state, &contract, conn, &mut block_times, event, &meta).await?;
             }
             Some(Err(e)) => {
                 eprintln!("Event stream error: {:?}", e.to_string());
@@ -224,10 +120,56 @@ pub async fn listen_for_ownership_events(state: &Arc<AppState>) -> Result<()> {
     }
 }
 
+// Applies one decoded log; the backfill and the stream both go through here
+async fn process_event(
+    state: &Arc<AppState>,
+    contract: &Ownership<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
+    conn: &mut PgConnection,
+    block_times: &mut BlockTimes,
+    event: OwnershipEvents,
+    meta: &LogMeta,
+) -> Result<()> {
+    let client = contract.client();
+    let txn_hash = Some(format!("0x{}", hex::encode(meta.transaction_hash)));
+    let position = block_times.position(client.as_ref(), meta).await?;
+
+    match event {
+        OwnershipEvents::OwnershipCreatedFilter(event) => {
+            process_ownership_created_event(&event, conn, txn_hash, &position)
+        }
+        OwnershipEvents::UserRegisteredFilter(event) => {
+            process_user_registered_event(&event, conn, txn_hash, &position, contract).await
+        }
+        OwnershipEvents::UsernameChangedFilter(event) => {
+            process_username_changed_event(&event, conn, txn_hash, &position)
+        }
+        OwnershipEvents::ItemCreatedFilter(event) => {
+            process_item_created_event(&event, conn, txn_hash, &position, contract).await
+        }
+        OwnershipEvents::OwnershipTransferredFilter(event) => {
+            process_ownership_transferred_event(&event, conn, txn_hash, &position)
+        }
+        OwnershipEvents::AuthenticitySetFilter(event) => {
+            process_authenticity_set_event(&event, conn, txn_hash, &position)
+        }
+        OwnershipEvents::OwnershipCodeFilter(event) => process_ownership_code_event(
+            &event,
+            conn,
+            txn_hash,
+            &position,
+            state.ownership_code_ttl_secs,
+        ),
+        OwnershipEvents::CodeRevokedFilter(event) => {
+            process_code_revoked_event(&event, conn, txn_hash, &position)
+        }
+    }
+}
+
 fn process_ownership_created_event(
     event: &OwnershipCreatedFilter,
     conn: &mut PgConnection,
     txn_hash: Option<String>,
+    position: &LogPosition,
 ) -> Result<()> {
     let contract_address = to_checksum(&event.contract_address, None);
     let owner = to_checksum(&event.owner, None);
@@ -260,7 +202,10 @@ fn process_ownership_created_event(
             contract_address,
             owner,
             tnx_hash: txn_hash.unwrap(),
-            created_at: Utc::now().to_rfc3339(),
+            created_at: position.block_time,
+            block_number: position.block_number,
+            log_index: position.log_index,
+            block_time: position.block_time,
         })
         .execute(conn)
         .map_err(|e| {
@@ -275,6 +220,7 @@ async fn process_user_registered_event(
     event: &UserRegisteredFilter,
     conn: &mut PgConnection,
     txn_hash: Option<String>,
+    position: &LogPosition,
     ownership_contract: &Ownership<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
 ) -> Result<()> {
     let user_address = to_checksum(&event.user_address, None);
@@ -304,8 +250,11 @@ async fn process_user_registered_event(
             user_address,
             username: event.username.to_string(),
             is_registered: true,
-            created_at: Utc::now().to_rfc3339(),
+            created_at: position.block_time,
             tnx_hash: txn_hash.unwrap(),
+            block_number: Some(position.block_number),
+            log_index: Some(position.log_index),
+            block_time: Some(position.block_time),
         })
         .execute(conn)
         .map_err(|e| {
@@ -316,10 +265,57 @@ async fn process_user_registered_event(
     Ok(())
 }
 
+fn process_username_changed_event(
+    event: &UsernameChangedFilter,
+    conn: &mut PgConnection,
+    txn_hash: Option<String>,
+    position: &LogPosition,
+) -> Result<()> {
+    let user_address = to_checksum(&event.user_address, None);
+
+    let user = users_info::table
+        .filter(users_info::user_address.eq(&user_address))
+        .select((users_info::block_number, users_info::log_index))
+        .first::<(Option<i64>, Option<i32>)>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query user: {}", e))?;
+    let Some((block_number, log_index)) = user else {
+        eprintln!(
+            "Skipping username change for unknown user {} (tx: {:?})",
+            user_address, txn_hash
+        );
+        return Ok(());
+    };
+    // a replayed or backfilled rename must not undo a later one
+    if !position.is_after(block_number, log_index) {
+        eprintln!(
+            "Skipping replayed username change for {} (tx: {:?})",
+            user_address, txn_hash
+        );
+        return Ok(());
+    }
+
+    diesel::update(users_info::table.filter(users_info::user_address.eq(&user_address)))
+        .set((
+            users_info::username.eq(event.new_username.to_string()),
+            users_info::block_number.eq(Some(position.block_number)),
+            users_info::log_index.eq(Some(position.log_index)),
+            users_info::block_time.eq(Some(position.block_time)),
+        ))
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!("Failed to update username of {}: {:?}", user_address, e);
+            eyre::eyre!("Failed to update username: {}", e)
+        })?;
+
+    Ok(())
+}
+
 async fn process_item_created_event(
     event: &ItemCreatedFilter,
     conn: &mut PgConnection,
     txn_hash: Option<String>,
+    position: &LogPosition,
     contract: &Ownership<SignerMiddleware<Provider<Http>, Wallet<SigningKey<Secp256k1>>>>,
 ) -> Result<()> {
     let item_id = event.item_id.to_string();
@@ -359,6 +355,9 @@ async fn process_item_created_event(
     }
 
 
+    // set when the certificate was created from a product template
+    let product_id = certificate_product(conn, &item_id)?;
+
     // Insert the item
     diesel::insert_into(items::table)
         .values(NewItem {
@@ -372,8 +371,12 @@ async fn process_item_created_event(
             owner: to_checksum(&item.owner, None),
             manufacturer: item.manufacturer,
             metadata: item.metadata,
-            created_at: Utc::now().to_rfc3339(),
+            created_at: position.block_time,
             tnx_hash: txn_hash.unwrap(),
+            product_id,
+            block_number: position.block_number,
+            log_index: position.log_index,
+            block_time: position.block_time,
         })
         .execute(conn)
         .map_err(|e| {
@@ -381,6 +384,9 @@ async fn process_item_created_event(
             eyre::eyre!("Failed to insert item: {}", e)
         })?;
 
+    // recalls opened before the item was claimed still apply to it
+    attach_open_recalls(conn, &item_id)?;
+
     Ok(())
 }
 
@@ -461,6 +467,7 @@ pub fn process_ownership_transferred_event(
     event: &OwnershipTransferredFilter,
     conn: &mut PgConnection,
     txn_hash: Option<String>,
+    position: &LogPosition,
 ) -> Result<()> {
     let item_id = event.item_id.clone();
     let new_owner = to_checksum(&event.new_onwer, None);
@@ -533,22 +540,16 @@ pub fn process_ownership_transferred_event(
             eprintln!("Warning: item_id {} not found in items table", item_id);
         }
 
-        // Fetch and delete associated ownership codes for the item_id
-        let deleted_rows =
-            diesel::delete(ownership_codes::table.filter(ownership_codes::item_id.eq(&item_id)))
-                .execute(conn)
-                .map_err(|e| {
-                    eprintln!(
-                        "Failed to delete ownership codes for item {}: {:?}",
-                        item_id, e
-                    );
-                    eyre::eyre!("Failed to delete ownership codes: {}", e)
-                })?;
+        // The index now agrees with the chain for this item
+        resolve_ownership_mismatches(conn, &item_id)?;
 
-        if deleted_rows > 0 {
+        // The item changed hands, so its open offers are settled (claimed or cancelled)
+        let (claimed, cancelled) = settle_offers_after_transfer(conn, &item_id, &new_owner)?;
+
+        if claimed + cancelled > 0 {
             eprintln!(
-                "Deleted {} ownership code(s) for item {}",
-                deleted_rows, item_id
+                "Settled ownership codes for item {}: {} claimed, {} cancelled",
+                item_id, claimed, cancelled
             );
         }
 
@@ -559,7 +560,10 @@ pub fn process_ownership_transferred_event(
                 new_owner,
                 old_owner,
                 tnx_hash: txn_hash,
-                created_at: Utc::now().to_rfc3339(),
+                created_at: position.block_time,
+                block_number: position.block_number,
+                log_index: position.log_index,
+                block_time: position.block_time,
             })
             .execute(conn)
             .map_err(|e| {
@@ -574,10 +578,133 @@ pub fn process_ownership_transferred_event(
 }
 
 
+fn process_ownership_code_event(
+    event: &OwnershipCodeFilter,
+    conn: &mut PgConnection,
+    txn_hash: Option<String>,
+    position: &LogPosition,
+    code_ttl_secs: i64,
+) -> Result<()> {
+    let item_id = event.item_id.clone();
+    let code_hash = format!("0x{}", hex::encode(event.code_hash));
+    let txn_hash = txn_hash.ok_or_else(|| eyre::eyre!("Transaction hash is required"))?;
+
+    conn.transaction::<_, eyre::Error, _>(|conn| {
+        let existing = ownership_codes::table
+            .filter(ownership_codes::code_hash.eq(&code_hash))
+            .select(ownership_codes::commit_tnx_hash)
+            .first::<Option<String>>(conn)
+            .optional()
+            .map_err(|e| {
+                eprintln!("Failed to check ownership code {}: {:?}", code_hash, e);
+                eyre::eyre!("Failed to check existing ownership code: {}", e)
+            })?;
+
+        match existing {
+            Some(Some(_)) => {
+                eprintln!(
+                    "Skipping duplicate ownership code commit for item {} (tx: {})",
+                    item_id, txn_hash
+                );
+            }
+            // issued through /api/ownership/code/commit, now confirmed on-chain
+            Some(None) => {
+                diesel::update(ownership_codes::table.filter(ownership_codes::code_hash.eq(&code_hash)))
+                    .set(ownership_codes::commit_tnx_hash.eq(&txn_hash))
+                    .execute(conn)
+                    .map_err(|e| {
+                        eprintln!("Failed to update ownership code {}: {:?}", code_hash, e);
+                        eyre::eyre!("Failed to update ownership code: {}", e)
+                    })?;
+            }
+            // committed straight from a wallet; the chain allows a single code per item
+            None => {
+                cancel_open_offers(conn, &item_id)?;
+
+                // the TTL runs from the commit, so a backfilled code may already be expired
+                let committed_at = position.block_time;
+                diesel::insert_into(ownership_codes::table)
+                    .values(OwnershipCode {
+                        code_hash: code_hash.clone(),
+                        item_id: item_id.clone(),
+                        item_owner: to_checksum(&event.owner, None),
+                        temp_owner: to_checksum(&event.temp_owner, None),
+                        created_at: committed_at,
+                        expires_at: committed_at + Duration::seconds(code_ttl_secs),
+                        code_format: "external".to_string(),
+                        status: TransferState::Offered.as_str().to_string(),
+                        status_changed_at: Utc::now(),
+                        commit_tnx_hash: Some(txn_hash.clone()),
+                    })
+                    .execute(conn)
+                    .map_err(|e| {
+                        eprintln!("Failed to insert ownership code: {:?}", e);
+                        eyre::eyre!("Failed to insert ownership code: {}", e)
+                    })?;
+            }
+        }
+
+        Ok(())
+    })
+}
+
+fn process_code_revoked_event(
+    event: &CodeRevokedFilter,
+    conn: &mut PgConnection,
+    txn_hash: Option<String>,
+    position: &LogPosition,
+) -> Result<()> {
+    let item_hash = format!("0x{}", hex::encode(event.item_hash));
+    let txn_hash = txn_hash.ok_or_else(|| eyre::eyre!("Transaction hash is required"))?;
+
+    // Check if code revocation exists
+    let exists: bool = code_revokations::table
+        .filter(code_revokations::item_hash.eq(&item_hash))
+        .filter(code_revokations::tnx_hash.eq(&txn_hash))
+        .select(diesel::dsl::count_star())
+        .first::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(|e| {
+            eprintln!("Failed to check existing code revocation {}: {:?}", item_hash, e);
+            eyre::eyre!("Failed to check existing code revocation: {}", e)
+        })?;
+
+    if exists {
+        eprintln!(
+            "Skipping duplicate code revocation for {} (tx: {})",
+            item_hash, txn_hash
+        );
+        return Ok(());
+    }
+
+    conn.transaction::<_, eyre::Error, _>(|conn| {
+        diesel::insert_into(code_revokations::table)
+            .values(NewCodeRevokation {
+                item_hash: item_hash.clone(),
+                tnx_hash: txn_hash,
+                created_at: position.block_time,
+                block_number: position.block_number,
+                log_index: position.log_index,
+                block_time: position.block_time,
+            })
+            .execute(conn)
+            .map_err(|e| {
+                eprintln!("Failed to insert code revocation: {:?}", e);
+                eyre::eyre!("Failed to insert code revocation: {}", e)
+            })?;
+
+        // offered -> cancelled, unless the owner already revoked it through the API
+        cancel_offer(conn, &item_hash)?;
+
+        Ok(())
+    })
+}
+
 fn process_authenticity_set_event(
     event: &AuthenticitySetFilter,
     conn: &mut PgConnection,
     txn_hash: Option<String>,
+    position: &LogPosition,
 ) -> Result<()> {
     let authenticity_address = to_checksum(&event.authenticity_address, None);
 
@@ -608,7 +735,10 @@ fn process_authenticity_set_event(
         .values(NewAuthenticitySetting {
             authenticity_address,
             tnx_hash: txn_hash.unwrap(),
-            created_at: Utc::now().to_rfc3339(),
+            created_at: position.block_time,
+            block_number: position.block_number,
+            log_index: position.log_index,
+            block_time: position.block_time,
         })
         .execute(conn)
         .map_err(|e| {
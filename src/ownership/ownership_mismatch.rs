@@ -0,0 +1,115 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::NewOwnershipMismatch;
+use crate::ownership::transfer_state::settle_offers_after_transfer;
+use crate::schema::{items, ownership_mismatches};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use ethers::core::utils::to_checksum;
+use ethers::types::Address;
+use eyre::Result;
+use std::sync::Arc;
+
+// Outcome of asking the Ownership contract who owns an item
+pub enum ChainOwnership {
+    Confirmed,
+    // chain_owner is None when the contract doesn't know the item at all
+    Mismatch { chain_owner: Option<String> },
+}
+
+// The items table is only as fresh as the indexer, so a missed OwnershipTransferred
+// would let a previous owner keep issuing codes. isOwner on the contract is authoritative.
+pub async fn check_chain_ownership(
+    state: &Arc<AppState>,
+    item_id: &str,
+    caller: &str,
+) -> Result<ChainOwnership> {
+    let caller_address: Address = caller
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid address: caller"))?;
+
+    let is_owner = state
+        .ownership_contract
+        .is_owner(caller_address, item_id.to_string())
+        .call()
+        .await
+        .map_err(|e| {
+            eprintln!(
+                "Failed to call isOwner for item_id {}: {:?}",
+                item_id,
+                e.to_string()
+            );
+            eyre::eyre!("Failed to confirm ownership on chain")
+        })?;
+
+    if is_owner {
+        return Ok(ChainOwnership::Confirmed);
+    }
+
+    // Only used to make the repair record useful; getItem reverts for unknown items
+    let chain_owner = state
+        .ownership_contract
+        .get_item(item_id.to_string())
+        .call()
+        .await
+        .ok()
+        .map(|item| to_checksum(&item.owner, None));
+
+    Ok(ChainOwnership::Mismatch { chain_owner })
+}
+
+// Records an open mismatch for the item; a second report while one is open is a no-op
+pub fn flag_ownership_mismatch(
+    conn: &mut PgConnection,
+    item_id: &str,
+    db_owner: &str,
+    chain_owner: Option<String>,
+) -> Result<()> {
+    diesel::insert_into(ownership_mismatches::table)
+        .values(NewOwnershipMismatch {
+            item_id: item_id.to_string(),
+            db_owner: db_owner.to_string(),
+            chain_owner,
+        })
+        // uq_ownership_mismatches_open_item
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .map_err(|e| {
+            eprintln!("Failed to flag ownership mismatch for item {}: {:?}", item_id, e);
+            eyre::eyre!("Failed to flag ownership mismatch: {}", e)
+        })?;
+
+    Ok(())
+}
+
+// Repairs the index from the chain: the item gets the chain's owner, offers made by the
+// stale owner are settled as if the missed transfer had been indexed, and the mismatch is
+// closed. Its row stays behind as the record of the repair.
+pub fn reconcile_item_owner(conn: &mut PgConnection, item_id: &str, chain_owner: &str) -> Result<()> {
+    conn.transaction::<_, eyre::Error, _>(|conn| {
+        diesel::update(items::table.filter(items::item_id.eq(item_id)))
+            .set(items::owner.eq(chain_owner))
+            .execute(conn)
+            .map_err(|e| eyre::eyre!("Failed to update items table: {}", e))?;
+
+        settle_offers_after_transfer(conn, item_id, chain_owner)?;
+        resolve_ownership_mismatches(conn, item_id)?;
+
+        Ok(())
+    })?;
+
+    eprintln!("Reconciled owner of item {} to {} from the chain", item_id, chain_owner);
+    Ok(())
+}
+
+// Closes open mismatches once the indexer has written the item's current owner
+pub fn resolve_ownership_mismatches(conn: &mut PgConnection, item_id: &str) -> Result<usize> {
+    diesel::update(
+        ownership_mismatches::table
+            .filter(ownership_mismatches::item_id.eq(item_id))
+            .filter(ownership_mismatches::resolved_at.is_null()),
+    )
+    .set(ownership_mismatches::resolved_at.eq(Utc::now()))
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to resolve ownership mismatches: {}", e))
+}
@@ -0,0 +1,300 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::Item;
+use crate::models::signed_document::{signed_document, SignedDocument};
+use crate::ownership::item_status::{item_status, ItemStatus};
+use crate::ownership::ownership_mismatch::{flag_ownership_mismatch, reconcile_item_owner};
+use crate::schema::items;
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use ethers::core::utils::to_checksum;
+use ethers::providers::Middleware;
+use ethers::signers::Signer;
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+// A proof is a SignedDocument stating that `owner` held `item_id` on the Ownership contract
+// at `block_number`. Offline: check the signature as for any SignedDocument and check
+// `expires_at`. Online: call verifyOwnership(item_id) on `verifying_contract`, or POST the
+// proof to /api/ownership-proof/verify.
+
+#[derive(Deserialize, ToSchema)]
+pub struct OwnershipProofQuery {
+    // must be the current owner of the item
+    #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef12")]
+    pub(crate) caller: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct OwnershipProofDocument {
+    #[schema(example = "item123")]
+    pub item_id: String,
+    #[schema(example = "Galaxy S24")]
+    pub name: String,
+    #[schema(example = "SN100042")]
+    pub serial: String,
+    #[schema(example = "Samsung")]
+    pub manufacturer: String,
+    #[schema(example = "0xAbCdEf1234567890aBcDeF1234567890AbCdEf12")]
+    pub owner: String,
+    pub status: ItemStatus,
+    #[schema(example = 18240012)]
+    pub block_number: u64,
+    #[schema(example = 84532)]
+    pub chain_id: u64,
+    #[schema(example = "0x1234567890AbcdEF1234567890aBcdef12345678")]
+    pub verifying_contract: String,
+    // unix seconds
+    #[schema(example = 1726629000)]
+    pub issued_at: i64,
+    #[schema(example = 1727233800)]
+    pub expires_at: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OwnershipProofVerification {
+    // signature, expiry and on-chain owner all check out
+    pub valid: bool,
+    // signed by this backend for this contract and chain
+    pub authentic: bool,
+    pub expired: bool,
+    // whether the owner in the proof still owns the item according to verifyOwnership
+    pub still_owner: bool,
+    #[schema(example = "item123")]
+    pub item_id: String,
+    #[schema(example = "0xAbCdEf1234567890aBcDeF1234567890AbCdEf12")]
+    pub owner: String,
+    #[schema(nullable = true, example = "0xAbCdEf1234567890aBcDeF1234567890AbCdEf12")]
+    pub current_owner: Option<String>,
+    #[schema(example = 1727233800)]
+    pub expires_at: i64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/item/{item_id}/ownership-proof",
+    params(
+        ("item_id" = String, Path, description = "The unique ID of the item", example = "item123"),
+        ("caller" = String, Query, description = "Address of the current owner", example = "0xabcdef1234567890abcdef1234567890abcdef12")
+    ),
+    responses(
+        (status = 200, description = "Proof of ownership signed by the backend wallet, as a JSON download", body = SignedDocument),
+        (status = 400, description = "Invalid caller address"),
+        (status = 403, description = "Caller is not the item owner"),
+        (status = 404, description = "Item not found"),
+        (status = 409, description = "Item ownership is out of sync with the chain"),
+        (status = 500, description = "Internal server error"),
+        (status = 502, description = "Ownership could not be confirmed on chain")
+    ),
+    tag = "Ownership"
+)]
+pub async fn get_ownership_proof(
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<String>,
+    Query(query): Query<OwnershipProofQuery>,
+) -> impl IntoResponse {
+    match get_ownership_proof_internal(&state, &item_id, &query).await {
+        Ok(proof) => (
+            StatusCode::OK,
+            [(
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}-ownership-proof.json\"", item_id),
+            )],
+            AxumJson(proof),
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("Error issuing ownership proof for item {}: {:?}", item_id, e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ownership-proof/verify",
+    request_body = SignedDocument,
+    responses(
+        (status = 200, description = "Verdict on the proof", body = OwnershipProofVerification, example = json!({
+            "valid": true,
+            "authentic": true,
+            "expired": false,
+            "still_owner": true,
+            "item_id": "item123",
+            "owner": "0xAbCdEf1234567890aBcDeF1234567890AbCdEf12",
+            "current_owner": "0xAbCdEf1234567890aBcDeF1234567890AbCdEf12",
+            "expires_at": 1727233800
+        })),
+        (status = 400, description = "Malformed proof or signature"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Ownership"
+)]
+pub async fn verify_ownership_proof(
+    State(state): State<Arc<AppState>>,
+    AxumJson(proof): AxumJson<SignedDocument>,
+) -> impl IntoResponse {
+    match verify_ownership_proof_internal(&state, &proof).await {
+        Ok(verification) => (StatusCode::OK, AxumJson(verification)).into_response(),
+        Err(e) => {
+            eprintln!("Error verifying ownership proof: {:?}", e);
+            error_response(e)
+        }
+    }
+}
+
+fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.contains("Invalid address") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Malformed proof") => (StatusCode::BAD_REQUEST, e.to_string()),
+        "Caller is not the item owner" => (StatusCode::FORBIDDEN, e.to_string()),
+        "Item not found" => (StatusCode::NOT_FOUND, e.to_string()),
+        "Item ownership is out of sync with the chain" => (StatusCode::CONFLICT, e.to_string()),
+        "Failed to confirm ownership on chain" => (StatusCode::BAD_GATEWAY, e.to_string()),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, AxumJson(json!({"error": message}))).into_response()
+}
+
+// Current owner according to the contract; None when verifyOwnership reverts
+// because the item doesn't exist there
+async fn chain_owner(state: &Arc<AppState>, item_id: &str) -> eyre::Result<Option<Address>> {
+    match state
+        .ownership_contract
+        .verify_ownership(item_id.to_string())
+        .call()
+        .await
+    {
+        Ok(owner) => Ok(Some(owner.owner)),
+        Err(e) if e.is_revert() => Ok(None),
+        Err(e) => {
+            eprintln!("Failed to call verifyOwnership for item_id {}: {:?}", item_id, e);
+            Err(eyre::eyre!("Failed to confirm ownership on chain"))
+        }
+    }
+}
+
+async fn get_ownership_proof_internal(
+    state: &Arc<AppState>,
+    item_id: &str,
+    query: &OwnershipProofQuery,
+) -> eyre::Result<SignedDocument> {
+    let caller: Address = query
+        .caller
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid address: caller"))?;
+
+    let (item, status) = {
+        let conn = &mut state.db_pool.get().map_err(|e| {
+            eprintln!("Failed to get DB connection: {:?}", e);
+            eyre::eyre!("Failed to get DB connection: {}", e)
+        })?;
+
+        let item = items::table
+            .filter(items::item_id.eq(item_id))
+            .select(Item::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?
+            .ok_or_else(|| eyre::eyre!("Item not found"))?;
+        let status = item_status(conn, item_id)?;
+        (item, status)
+    };
+
+    if !item.owner.eq_ignore_ascii_case(&query.caller) {
+        return Err(eyre::eyre!("Caller is not the item owner"));
+    }
+
+    // the block is read first so the contract answer is at least as new as the proof claims
+    let client = state.ownership_contract.client();
+    let block_number = client
+        .get_block_number()
+        .await
+        .map_err(|e| eyre::eyre!("Failed to fetch block number: {}", e))?
+        .as_u64();
+    let on_chain = chain_owner(state, item_id).await?;
+    if on_chain != Some(caller) {
+        let on_chain = on_chain.map(|owner| to_checksum(&owner, None));
+        let conn = &mut state.db_pool.get().map_err(|e| {
+            eprintln!("Failed to get DB connection: {:?}", e);
+            eyre::eyre!("Failed to get DB connection: {}", e)
+        })?;
+        flag_ownership_mismatch(conn, item_id, &item.owner, on_chain.clone())?;
+        if let Some(on_chain) = on_chain {
+            reconcile_item_owner(conn, item_id, &on_chain)?;
+        }
+        return Err(eyre::eyre!("Item ownership is out of sync with the chain"));
+    }
+
+    let wallet = client.signer().clone();
+    let issued_at = Utc::now();
+    let expires_at = issued_at + Duration::seconds(state.ownership_proof_ttl_secs);
+
+    signed_document(
+        &wallet,
+        &OwnershipProofDocument {
+            item_id: item.item_id,
+            name: item.name,
+            serial: item.serial,
+            manufacturer: item.manufacturer,
+            owner: to_checksum(&caller, None),
+            status,
+            block_number,
+            chain_id: wallet.chain_id(),
+            verifying_contract: to_checksum(&state.ownership_contract.address(), None),
+            issued_at: issued_at.timestamp(),
+            expires_at: expires_at.timestamp(),
+        },
+    )
+    .await
+}
+
+async fn verify_ownership_proof_internal(
+    state: &Arc<AppState>,
+    proof: &SignedDocument,
+) -> eyre::Result<OwnershipProofVerification> {
+    let document: OwnershipProofDocument = serde_json::from_value(proof.document.clone())
+        .map_err(|e| eyre::eyre!("Malformed proof document: {}", e))?;
+    let owner: Address = document
+        .owner
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid address: owner"))?;
+
+    let signer = proof
+        .recover_signer()
+        .map_err(|e| eyre::eyre!("Malformed proof: {}", e))?;
+    let wallet = state.ownership_contract.client().signer().clone();
+    let authentic = signer == wallet.address()
+        && document.chain_id == wallet.chain_id()
+        && document
+            .verifying_contract
+            .parse::<Address>()
+            .is_ok_and(|contract| contract == state.ownership_contract.address());
+
+    let expired = document.expires_at <= Utc::now().timestamp();
+
+    let current_owner = chain_owner(state, &document.item_id).await?;
+    let still_owner = current_owner == Some(owner);
+
+    Ok(OwnershipProofVerification {
+        valid: authentic && !expired && still_owner,
+        authentic,
+        expired,
+        still_owner,
+        item_id: document.item_id,
+        owner: document.owner,
+        current_owner: current_owner.map(|address| to_checksum(&address, None)),
+        expires_at: document.expires_at,
+    })
+}
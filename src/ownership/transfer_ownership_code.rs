@@ -1,5 +1,15 @@
 use crate::config::app_state::AppState;
 use crate::contract_models::OwnershipCode;
+use crate::ownership::item_status::ensure_transferable;
+use crate::ownership::onchain_ownership_code::{
+    commit_ownership_code_internal, error_response, CommitOwnershipCodeRequest,
+    CommitOwnershipCodeResponse,
+};
+use crate::ownership::ownership_mismatch::{
+    check_chain_ownership, flag_ownership_mismatch, reconcile_item_owner, ChainOwnership,
+};
+use crate::ownership::ownership_code::{generate_code, hash_code, normalize_code, CodeFormat};
+use crate::ownership::transfer_state::{expire_stale_offers, item_transfer_state, TransferState};
 use crate::schema::{items, ownership_codes, users_info};
 use axum::{
     extract::{Query, State},
@@ -7,41 +17,46 @@ use axum::{
     response::IntoResponse,
     Json,
 };
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
 use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use eyre::Result;
-use serde::{Deserialize, Serialize};
-use sha3::{Digest, Keccak256};
+use serde::Deserialize;
 use std::sync::Arc;
 use utoipa::ToSchema;
 
-#[derive(Serialize, ToSchema)]
 pub struct OwnershipCodeResponse {
-    ownership_code: String,
+    pub(crate) ownership_code: String,
+    pub(crate) format: CodeFormat,
+    pub(crate) expires_at: DateTime<Utc>,
 }
 
 #[derive(Deserialize, ToSchema)]
 pub struct GenerateOwnershipCodeQuery {
-    item_id: String,
-    caller: String,
-    temp_owner: String,
+    pub(crate) item_id: String,
+    pub(crate) caller: String,
+    pub(crate) temp_owner: String,
+    pub(crate) format: Option<CodeFormat>,
 }
 
+// Kept for older clients; same as POST /api/ownership/code/commit. The code only becomes
+// claimable once the owner sends the returned `generateCode` transaction.
 #[utoipa::path(
     get,
     path = "/api/transfer_ownership",
     params(
         ("item_id" = String, Query, description = "ID of the item", example = "item_001"),
         ("caller" = String, Query, description = "Address of the caller", example = "0x1234567890abcdef1234567890abcdef12345678"),
-        ("temp_owner" = String, Query, description = "Address of the temporary owner", example = "0xabcdef1234567890abcdef1234567890abcdef12")
+        ("temp_owner" = String, Query, description = "Address of the temporary owner", example = "0xabcdef1234567890abcdef1234567890abcdef12"),
+        ("format" = Option<CodeFormat>, Query, description = "Code format: `hex` (default) or `base32` for a short code that can be read aloud", example = "base32")
     ),
     responses(
-        (status = 200, description = "Ownership code generated successfully", body = OwnershipCodeResponse, example = json!({
-            "ownership_code": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
-        })),
+        (status = 200, description = "Code generated; the owner must send `transaction` to commit its hash on-chain", body = CommitOwnershipCodeResponse),
         (status = 400, description = "Invalid input (e.g., caller is temp_owner or caller not registered)"),
         (status = 404, description = "Item not found"),
-        (status = 500, description = "Internal server error")
+        (status = 409, description = "Item already has an active transfer code, is flagged (stolen, lost, recalled, destroyed), or its indexed owner disagrees with the chain"),
+        (status = 500, description = "Internal server error"),
+        (status = 502, description = "Ownership could not be confirmed on chain")
     ),
     tag = "Ownership"
 )]
@@ -49,35 +64,29 @@ pub async fn transfer_ownership_code(
     Query(query): Query<GenerateOwnershipCodeQuery>,
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
-    match generate_ownership_code_internal(&state, &query).await {
-        Ok(ownership_code) => (
-            StatusCode::OK,
-            Json(OwnershipCodeResponse { ownership_code }),
-        )
-            .into_response(),
+    let item_id = query.item_id.clone();
+    let request = CommitOwnershipCodeRequest {
+        item_id: query.item_id,
+        caller: query.caller,
+        temp_owner: query.temp_owner,
+        format: query.format,
+    };
+    match commit_ownership_code_internal(&state, request).await {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
         Err(e) => {
             eprintln!(
                 "Error generating ownership code for item {}: {:?}",
-                query.item_id, e
+                item_id, e
             );
-            let (status, message) = match e.to_string().as_str() {
-                "Caller cannot be the temporary owner" => (StatusCode::BAD_REQUEST, e.to_string()),
-                "Caller is not registered" => (StatusCode::BAD_REQUEST, e.to_string()),
-                "Item not found" => (StatusCode::NOT_FOUND, e.to_string()),
-                _ => (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Internal server error: {}", e),
-                ),
-            };
-            (status, Json(serde_json::json!({"error": message}))).into_response()
+            error_response(e)
         }
     }
 }
 
-async fn generate_ownership_code_internal(
+pub(crate) async fn generate_ownership_code_internal(
     state: &Arc<AppState>,
     query: &GenerateOwnershipCodeQuery,
-) -> Result<String> {
+) -> Result<OwnershipCodeResponse> {
     if query.caller == query.temp_owner {
         return Err(eyre::eyre!("Caller cannot be the temporary owner"));
     }
@@ -115,24 +124,53 @@ async fn generate_ownership_code_internal(
         return Err(eyre::eyre!("Item not found or caller is not the owner"));
     }
 
-    // Generate keccak256 hash of caller, temp_owner, item_id, and current timestamp
-    let hash_input = format!(
-        "{}{}{}{}",
-        query.caller,
-        query.temp_owner,
-        query.item_id,
-        Utc::now().to_rfc3339()
-    );
-    let hash = Keccak256::digest(hash_input.as_bytes());
-    let ownership_code = format!("0x{}", hex::encode(hash));
+    // stolen, lost, recalled and destroyed items stay where they are
+    ensure_transferable(conn, &query.item_id)?;
+
+    // A stale owner column must not let a previous owner hand out the item again
+    if state.verify_ownership_on_chain
+        && let ChainOwnership::Mismatch { chain_owner } =
+            check_chain_ownership(state, &query.item_id, &query.caller).await?
+    {
+        eprintln!(
+            "Ownership mismatch for item {}: indexed owner {}, chain owner {:?}",
+            query.item_id, query.caller, chain_owner
+        );
+        flag_ownership_mismatch(conn, &query.item_id, &query.caller, chain_owner.clone())?;
+        // an item the contract doesn't know is left flagged for a person to look at
+        if let Some(chain_owner) = chain_owner {
+            reconcile_item_owner(conn, &query.item_id, &chain_owner)?;
+        }
+        return Err(eyre::eyre!("Item ownership is out of sync with the chain"));
+    }
+
+    // Only one offer may be open per item (idle -> offered); an offer whose TTL elapsed
+    // before the sweeper got to it must not block a new one
+    expire_stale_offers(conn, Some(&query.item_id))?;
+    if item_transfer_state(conn, &query.item_id)? != TransferState::Idle {
+        return Err(eyre::eyre!("Item already has an active transfer code"));
+    }
+
+    // The code itself is random and never stored; only its hash is kept for lookups
+    let format = query.format.unwrap_or_default();
+    let ownership_code = generate_code(format);
+    let code_hash = hash_code(&normalize_code(&ownership_code)?);
+    let created_at = Utc::now();
+    let expires_at = created_at + Duration::seconds(state.ownership_code_ttl_secs);
+
     // Save to ownership_codes table
     diesel::insert_into(ownership_codes::table)
         .values(OwnershipCode {
-            ownership_code: ownership_code.clone(),
+            code_hash,
             item_id: query.item_id.clone(),
             item_owner: query.caller.clone(),
             temp_owner: query.temp_owner.clone(),
-            created_at: Utc::now().to_rfc3339(),
+            created_at,
+            expires_at,
+            code_format: format.as_str().to_string(),
+            status: TransferState::Offered.as_str().to_string(),
+            status_changed_at: created_at,
+            commit_tnx_hash: None,
         })
         .execute(conn)
         .map_err(|e| {
@@ -140,8 +178,18 @@ async fn generate_ownership_code_internal(
                 "Error inserting ownership code for item {}: {:?}",
                 query.item_id, e
             );
-            eyre::eyre!("Failed to insert ownership code: {}", e)
+            match e {
+                // uq_ownership_codes_offered_item: a concurrent request opened an offer first
+                DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                    eyre::eyre!("Item already has an active transfer code")
+                }
+                _ => eyre::eyre!("Failed to insert ownership code: {}", e),
+            }
         })?;
 
-    Ok(ownership_code)
+    Ok(OwnershipCodeResponse {
+        ownership_code,
+        format,
+        expires_at,
+    })
 }
@@ -0,0 +1,184 @@
+use crate::schema::ownership_codes;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Transfer lifecycle of an item. `Idle` is never stored: it is what an item is in
+// while none of its ownership codes is `Offered`. The other states live on the code row.
+//
+//   idle --generate--> offered --claim---> claimed
+//                         |--revoke/transfer--> cancelled
+//                         '--ttl elapsed------> expired
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferState {
+    Idle,
+    Offered,
+    Claimed,
+    Cancelled,
+    Expired,
+}
+
+impl TransferState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferState::Idle => "idle",
+            TransferState::Offered => "offered",
+            TransferState::Claimed => "claimed",
+            TransferState::Cancelled => "cancelled",
+            TransferState::Expired => "expired",
+        }
+    }
+
+    pub fn parse(state: &str) -> Result<Self> {
+        match state {
+            "idle" => Ok(TransferState::Idle),
+            "offered" => Ok(TransferState::Offered),
+            "claimed" => Ok(TransferState::Claimed),
+            "cancelled" => Ok(TransferState::Cancelled),
+            "expired" => Ok(TransferState::Expired),
+            other => Err(eyre::eyre!("Unknown transfer state: {}", other)),
+        }
+    }
+
+    pub fn can_transition_to(&self, next: TransferState) -> bool {
+        matches!(
+            (self, next),
+            (TransferState::Idle, TransferState::Offered)
+                | (TransferState::Offered, TransferState::Claimed)
+                | (TransferState::Offered, TransferState::Cancelled)
+                | (TransferState::Offered, TransferState::Expired)
+        )
+    }
+}
+
+// Current state of an item, derived from its offered code (if any)
+pub fn item_transfer_state(conn: &mut PgConnection, item_id: &str) -> Result<TransferState> {
+    let offered = ownership_codes::table
+        .filter(ownership_codes::item_id.eq(item_id))
+        .filter(ownership_codes::status.eq(TransferState::Offered.as_str()))
+        .select(diesel::dsl::count_star())
+        .first::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(|e| eyre::eyre!("Failed to query transfer state: {}", e))?;
+
+    Ok(if offered {
+        TransferState::Offered
+    } else {
+        TransferState::Idle
+    })
+}
+
+// Moves offered codes whose TTL has elapsed to `expired`, for one item or for all of them
+pub fn expire_stale_offers(conn: &mut PgConnection, item_id: Option<&str>) -> Result<usize> {
+    let now = Utc::now();
+    let stale = ownership_codes::table
+        .filter(ownership_codes::status.eq(TransferState::Offered.as_str()))
+        .filter(ownership_codes::expires_at.le(now));
+    let changes = (
+        ownership_codes::status.eq(TransferState::Expired.as_str()),
+        ownership_codes::status_changed_at.eq(now),
+    );
+
+    match item_id {
+        Some(item_id) => diesel::update(stale.filter(ownership_codes::item_id.eq(item_id)))
+            .set(changes)
+            .execute(conn),
+        None => diesel::update(stale).set(changes).execute(conn),
+    }
+    .map_err(|e| eyre::eyre!("Failed to expire ownership codes: {}", e))
+}
+
+// Cancels a single open offer, e.g. once its on-chain commitment was revoked
+pub fn cancel_offer(conn: &mut PgConnection, code_hash: &str) -> Result<usize> {
+    diesel::update(
+        ownership_codes::table
+            .filter(ownership_codes::code_hash.eq(code_hash))
+            .filter(ownership_codes::status.eq(TransferState::Offered.as_str())),
+    )
+    .set((
+        ownership_codes::status.eq(TransferState::Cancelled.as_str()),
+        ownership_codes::status_changed_at.eq(Utc::now()),
+    ))
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to cancel ownership code: {}", e))
+}
+
+// Settles open offers once an item changed hands on-chain: the offer made to the new owner
+// was claimed (offered -> claimed), anything else is void (offered -> cancelled)
+pub fn settle_offers_after_transfer(
+    conn: &mut PgConnection,
+    item_id: &str,
+    new_owner: &str,
+) -> Result<(usize, usize)> {
+    let claimed = diesel::update(
+        ownership_codes::table
+            .filter(ownership_codes::item_id.eq(item_id))
+            .filter(ownership_codes::status.eq(TransferState::Offered.as_str()))
+            .filter(ownership_codes::temp_owner.ilike(new_owner)),
+    )
+    .set((
+        ownership_codes::status.eq(TransferState::Claimed.as_str()),
+        ownership_codes::status_changed_at.eq(Utc::now()),
+    ))
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to settle ownership codes: {}", e))?;
+
+    let cancelled = cancel_open_offers(conn, item_id)?;
+
+    Ok((claimed, cancelled))
+}
+
+// Cancels any open offer for an item
+pub fn cancel_open_offers(conn: &mut PgConnection, item_id: &str) -> Result<usize> {
+    diesel::update(
+        ownership_codes::table
+            .filter(ownership_codes::item_id.eq(item_id))
+            .filter(ownership_codes::status.eq(TransferState::Offered.as_str())),
+    )
+    .set((
+        ownership_codes::status.eq(TransferState::Cancelled.as_str()),
+        ownership_codes::status_changed_at.eq(Utc::now()),
+    ))
+    .execute(conn)
+    .map_err(|e| eyre::eyre!("Failed to cancel ownership codes: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [TransferState; 5] = [
+        TransferState::Idle,
+        TransferState::Offered,
+        TransferState::Claimed,
+        TransferState::Cancelled,
+        TransferState::Expired,
+    ];
+
+    #[test]
+    fn claimed_is_final() {
+        for next in ALL {
+            assert!(!TransferState::Claimed.can_transition_to(next), "claimed -> {:?}", next);
+        }
+    }
+
+    #[test]
+    fn offered_moves_to_every_terminal_state() {
+        for next in [TransferState::Claimed, TransferState::Cancelled, TransferState::Expired] {
+            assert!(TransferState::Offered.can_transition_to(next));
+        }
+        assert!(TransferState::Idle.can_transition_to(TransferState::Offered));
+        assert!(!TransferState::Idle.can_transition_to(TransferState::Claimed));
+    }
+
+    #[test]
+    fn states_round_trip_through_their_names() {
+        for state in ALL {
+            assert_eq!(TransferState::parse(state.as_str()).unwrap(), state);
+        }
+    }
+}
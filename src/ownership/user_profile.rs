@@ -0,0 +1,238 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::{UserInfo, UserProfile, UserProfileChanges};
+use crate::models::username_policy::{check_name, NameKind};
+use crate::models::wallet_auth::WalletAuth;
+use crate::ownership::get_user_info::UserResponse;
+use crate::ownership::onchain_ownership_code::{prepare_transaction, PreparedTransaction};
+use crate::schema::users_info;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProfileVisibility {
+    // display name, avatar and (if shown) email are returned to anyone
+    #[default]
+    Public,
+    // only the on-chain username and registration are returned
+    Private,
+}
+
+impl ProfileVisibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProfileVisibility::Public => "public",
+            ProfileVisibility::Private => "private",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateUserProfileRequest {
+    #[validate(length(max = 64))]
+    #[schema(example = "John Doe")]
+    pub display_name: Option<String>,
+    #[validate(url, length(max = 2048))]
+    #[schema(example = "https://cdn.example.com/avatars/john.png")]
+    pub avatar_url: Option<String>,
+    #[validate(email)]
+    #[schema(example = "john@example.com")]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub profile_visibility: ProfileVisibility,
+    #[serde(default)]
+    #[schema(example = false)]
+    pub show_email: bool,
+    // signed by the user's wallet for `update-user-profile <user_address>`
+    pub auth: WalletAuth,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ChangeUsernameRequest {
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub caller: String,
+    #[schema(example = "john_doe_2")]
+    pub username: String,
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/user/{user_address}/profile",
+    params(
+        ("user_address" = String, Path, description = "Address of the user", example = "0x1234567890abcdef1234567890abcdef12345678")
+    ),
+    request_body = UpdateUserProfileRequest,
+    responses(
+        (status = 200, description = "Profile replaced; the full profile is returned to its owner", body = UserResponse),
+        (status = 400, description = "Invalid profile or caller address"),
+        (status = 401, description = "Invalid wallet signature"),
+        (status = 403, description = "Caller is not the user"),
+        (status = 404, description = "User not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Users"
+)]
+pub async fn update_user_profile(
+    State(state): State<Arc<AppState>>,
+    Path(user_address): Path<String>,
+    Json(request): Json<UpdateUserProfileRequest>,
+) -> impl IntoResponse {
+    match update_user_profile_internal(&state, &user_address, &request).await {
+        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+        Err(e) => {
+            eprintln!("Error updating profile of user {}: {:?}", user_address, e);
+            error_response(e)
+        }
+    }
+}
+
+// Ownership keeps the username per wallet (msg.sender), so the change is returned for the
+// user's wallet to send; users_info follows once UsernameChanged is indexed.
+#[utoipa::path(
+    post,
+    path = "/api/user/username",
+    request_body = ChangeUsernameRequest,
+    responses(
+        (status = 200, description = "The user must send `changeUsername` from their wallet", body = PreparedTransaction),
+        (status = 400, description = "Invalid caller address, or the username breaks the naming policy"),
+        (status = 403, description = "Caller is not a registered user"),
+        (status = 409, description = "Username is used by another user or manufacturer, ignoring case"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Users"
+)]
+pub async fn change_username(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ChangeUsernameRequest>,
+) -> impl IntoResponse {
+    match change_username_internal(&state, &request).await {
+        Ok(transaction) => (StatusCode::OK, Json(transaction)).into_response(),
+        Err(e) => {
+            eprintln!("Error preparing changeUsername transaction: {:?}", e);
+            error_response(e)
+        }
+    }
+}
+
+fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.starts_with("Invalid wallet signature") => (StatusCode::UNAUTHORIZED, e.to_string()),
+        s if s.starts_with("Invalid") => (StatusCode::BAD_REQUEST, e.to_string()),
+        "Caller is not the user" | "Caller is not a registered user" => {
+            (StatusCode::FORBIDDEN, e.to_string())
+        }
+        "User not found" => (StatusCode::NOT_FOUND, e.to_string()),
+        "Username is already taken" => (StatusCode::CONFLICT, e.to_string()),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, Json(serde_json::json!({"error": message}))).into_response()
+}
+
+// What others may see of a profile: nothing when private, the email only when shown
+pub fn visible_profile(profile: UserProfile) -> Option<UserProfile> {
+    if profile.profile_visibility != ProfileVisibility::Public.as_str() {
+        return None;
+    }
+    Some(UserProfile {
+        email: profile.email.filter(|_| profile.show_email),
+        ..profile
+    })
+}
+
+// Stored address and username, whatever the case of the given address
+fn find_user(conn: &mut PgConnection, user_address: &str) -> Result<Option<(String, String)>> {
+    users_info::table
+        .filter(users_info::user_address.ilike(user_address))
+        .select((users_info::user_address, users_info::username))
+        .first::<(String, String)>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query user: {}", e))
+}
+
+// Blank strings clear a field just like an absent one
+fn trimmed(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+async fn update_user_profile_internal(
+    state: &Arc<AppState>,
+    user_address: &str,
+    request: &UpdateUserProfileRequest,
+) -> Result<UserResponse> {
+    request
+        .validate()
+        .map_err(|e| eyre::eyre!("Invalid profile: {}", e))?;
+
+    let caller = request.auth.verify("update-user-profile", user_address, request)?;
+    if !format!("{:?}", caller).eq_ignore_ascii_case(user_address) {
+        return Err(eyre::eyre!("Caller is not the user"));
+    }
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let (stored_address, _) =
+        find_user(conn, user_address)?.ok_or_else(|| eyre::eyre!("User not found"))?;
+
+    let changes = UserProfileChanges {
+        display_name: trimmed(&request.display_name),
+        avatar_url: trimmed(&request.avatar_url),
+        email: trimmed(&request.email),
+        profile_visibility: request.profile_visibility.as_str().to_string(),
+        show_email: request.show_email,
+        profile_updated_at: Some(Utc::now()),
+    };
+
+    let (user, profile) = diesel::update(users_info::table.filter(users_info::user_address.eq(&stored_address)))
+        .set(&changes)
+        .returning((UserInfo::as_returning(), UserProfile::as_returning()))
+        .get_result::<(UserInfo, UserProfile)>(conn)
+        .map_err(|e| eyre::eyre!("Failed to update user profile: {}", e))?;
+
+    Ok(UserResponse::new(user, Some(profile)))
+}
+
+async fn change_username_internal(
+    state: &Arc<AppState>,
+    request: &ChangeUsernameRequest,
+) -> Result<PreparedTransaction> {
+    let username = request.username.trim();
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let (stored_address, current) = find_user(conn, &request.caller)?
+        .ok_or_else(|| eyre::eyre!("Caller is not a registered user"))?;
+    if current == username {
+        return Err(eyre::eyre!("Invalid username: unchanged"));
+    }
+    // the user's own name may change case, anyone else's is off limits
+    check_name(conn, username, NameKind::User, Some(&stored_address))?;
+
+    let call = state.ownership_contract.change_username(username.to_string());
+
+    prepare_transaction(&request.caller, state.ownership_contract.address(), call.calldata())
+}
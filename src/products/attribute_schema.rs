@@ -0,0 +1,140 @@
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeType {
+    String,
+    Number,
+    Boolean,
+}
+
+impl AttributeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttributeType::String => "string",
+            AttributeType::Number => "number",
+            AttributeType::Boolean => "boolean",
+        }
+    }
+}
+
+// One attribute a product's certificates may (or must) carry in their metadata
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub struct AttributeDefinition {
+    #[schema(example = "color")]
+    pub name: String,
+    #[serde(rename = "type")]
+    #[schema(example = "string")]
+    pub kind: AttributeType,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(nullable = true, example = json!(["black", "silver"]))]
+    pub allowed_values: Option<Vec<String>>,
+}
+
+// Reads the JSONB column back into definitions
+pub fn parse_schema(value: &serde_json::Value) -> Result<Vec<AttributeDefinition>> {
+    serde_json::from_value(value.clone())
+        .map_err(|e| eyre::eyre!("Invalid attribute schema: {}", e))
+}
+
+// Checks a schema submitted by a manufacturer before it is stored
+pub fn check_schema(schema: &[AttributeDefinition]) -> Result<()> {
+    let mut names = HashSet::new();
+
+    for attribute in schema {
+        let name = attribute.name.trim();
+        if name.is_empty() || name.contains(':') {
+            return Err(eyre::eyre!(
+                "Invalid attribute schema: attribute names must be non-empty and contain no ':'"
+            ));
+        }
+        if !names.insert(name.to_lowercase()) {
+            return Err(eyre::eyre!(
+                "Invalid attribute schema: duplicate attribute {}",
+                name
+            ));
+        }
+        if let Some(allowed) = &attribute.allowed_values {
+            if allowed.is_empty() {
+                return Err(eyre::eyre!(
+                    "Invalid attribute schema: allowed_values for {} is empty",
+                    name
+                ));
+            }
+            for value in allowed {
+                check_value(attribute, value)
+                    .map_err(|_| eyre::eyre!("Invalid attribute schema: {} is not a valid {} value", value, name))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Metadata entries are "key: value" strings; every key must be declared by the schema,
+// required keys must be present and values must match the declared type
+pub fn validate_metadata(schema: &[AttributeDefinition], metadata: &[String]) -> Result<()> {
+    let mut seen = HashSet::new();
+
+    for entry in metadata {
+        let (key, value) = entry
+            .split_once(':')
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .ok_or_else(|| eyre::eyre!("Invalid metadata: {} is not a \"key: value\" entry", entry))?;
+
+        let attribute = schema
+            .iter()
+            .find(|attribute| attribute.name.eq_ignore_ascii_case(key))
+            .ok_or_else(|| eyre::eyre!("Invalid metadata: unknown attribute {}", key))?;
+
+        if !seen.insert(attribute.name.to_lowercase()) {
+            return Err(eyre::eyre!("Invalid metadata: {} is set more than once", key));
+        }
+
+        check_value(attribute, value)?;
+    }
+
+    if let Some(missing) = schema
+        .iter()
+        .find(|attribute| attribute.required && !seen.contains(&attribute.name.to_lowercase()))
+    {
+        return Err(eyre::eyre!(
+            "Invalid metadata: missing required attribute {}",
+            missing.name
+        ));
+    }
+
+    Ok(())
+}
+
+fn check_value(attribute: &AttributeDefinition, value: &str) -> Result<()> {
+    let valid_type = match attribute.kind {
+        AttributeType::String => !value.is_empty(),
+        AttributeType::Number => value.parse::<f64>().is_ok_and(|number| number.is_finite()),
+        AttributeType::Boolean => matches!(value, "true" | "false"),
+    };
+    if !valid_type {
+        return Err(eyre::eyre!(
+            "Invalid metadata: {} must be a {} value",
+            attribute.name,
+            attribute.kind.as_str()
+        ));
+    }
+
+    if let Some(allowed) = &attribute.allowed_values
+        && !allowed.iter().any(|allowed| allowed == value)
+    {
+        return Err(eyre::eyre!(
+            "Invalid metadata: {} must be one of {}",
+            attribute.name,
+            allowed.join(", ")
+        ));
+    }
+
+    Ok(())
+}
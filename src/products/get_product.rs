@@ -0,0 +1,177 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::Product;
+use crate::models::pagination::{keyset_page, Page, SortBy, SortOrder};
+use crate::schema::products;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct ProductsQuery {
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub(crate) manufacturer: String,
+    pub(crate) cursor: Option<String>,
+    #[schema(example = 20)]
+    pub(crate) limit: Option<i64>,
+    pub(crate) sort: Option<SortBy>,
+    pub(crate) order: Option<SortOrder>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ProductsResponse {
+    products: Vec<Product>,
+    #[schema(example = 42)]
+    total: i64,
+    #[schema(nullable = true)]
+    next_cursor: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/products/{product_id}",
+    params(
+        ("product_id" = i32, Path, description = "ID of the product", example = 1)
+    ),
+    responses(
+        (status = 200, description = "Product retrieved successfully", body = Product, example = json!({
+            "id": 1,
+            "manufacturer_address": "0x1234567890abcdef1234567890abcdef12345678",
+            "sku": "GAL-S24-128-BLK",
+            "name": "Galaxy S24",
+            "attribute_schema": [
+                {"name": "color", "type": "string", "required": true, "allowed_values": ["black", "silver"]},
+                {"name": "storage_gb", "type": "number", "required": true}
+            ],
+            "images": ["https://cdn.example.com/s24-front.png"],
+            "created_at": "2025-09-12T09:00:00Z",
+            "updated_at": "2025-09-12T09:00:00Z"
+        })),
+        (status = 404, description = "Product not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Products"
+)]
+pub async fn get_product(
+    State(state): State<Arc<AppState>>,
+    Path(product_id): Path<i32>,
+) -> impl IntoResponse {
+    match get_product_internal(&state, product_id).await {
+        Ok(product) => (StatusCode::OK, AxumJson(product)).into_response(),
+        Err(e) => {
+            eprintln!("Error fetching product {}: {:?}", product_id, e);
+            let (status, message) = match e.to_string().as_str() {
+                "Product not found" => (StatusCode::NOT_FOUND, e.to_string()),
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Internal server error: {}", e),
+                ),
+            };
+            (status, AxumJson(json!({"error": message}))).into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/products",
+    params(
+        ("manufacturer" = String, Query, description = "Address of the manufacturer", example = "0x1234567890abcdef1234567890abcdef12345678"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<i64>, Query, description = "Products per page (default 20, max 100)", example = 20),
+        ("sort" = Option<SortBy>, Query, description = "Sort field: `created_at` (default) or `name` (product name)", example = "created_at"),
+        ("order" = Option<SortOrder>, Query, description = "Sort order: `desc` (default) or `asc`", example = "desc")
+    ),
+    responses(
+        (status = 200, description = "Products of the manufacturer", body = ProductsResponse),
+        (status = 400, description = "Invalid pagination"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Products"
+)]
+pub async fn list_products(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ProductsQuery>,
+) -> impl IntoResponse {
+    match list_products_internal(&state, &query).await {
+        Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
+        Err(e) => {
+            eprintln!(
+                "Error listing products for manufacturer {}: {:?}",
+                query.manufacturer, e
+            );
+            let (status, message) = match e.to_string().as_str() {
+                s if s.contains("Invalid pagination") => (StatusCode::BAD_REQUEST, e.to_string()),
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Internal server error: {}", e),
+                ),
+            };
+            (status, AxumJson(json!({"error": message}))).into_response()
+        }
+    }
+}
+
+async fn get_product_internal(state: &Arc<AppState>, product_id: i32) -> eyre::Result<Product> {
+    let conn = &mut state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+
+    products::table
+        .filter(products::id.eq(product_id))
+        .select(Product::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?
+        .ok_or_else(|| eyre::eyre!("Product not found"))
+}
+
+async fn list_products_internal(
+    state: &Arc<AppState>,
+    query: &ProductsQuery,
+) -> eyre::Result<ProductsResponse> {
+    let page = Page::new(query.cursor.as_deref(), query.limit, query.sort, query.order)?;
+
+    let conn = &mut state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+
+    let total = products::table
+        .filter(products::manufacturer_address.ilike(&query.manufacturer))
+        .count()
+        .get_result::<i64>(conn)
+        .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?;
+
+    let rows = keyset_page!(
+        products::table
+            .filter(products::manufacturer_address.ilike(&query.manufacturer))
+            .select(Product::as_select())
+            .into_boxed(),
+        &page,
+        products::created_at,
+        products::name,
+        products::id,
+        i32
+    )
+    .load::<Product>(conn)
+    .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?;
+
+    let (products, next_cursor) = page.finish(rows, |product| {
+        (product.created_at, product.name.clone(), product.id.to_string())
+    });
+
+    Ok(ProductsResponse {
+        products,
+        total,
+        next_cursor,
+    })
+}
@@ -0,0 +1,247 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::{NewProduct, Product};
+use crate::models::wallet_auth::WalletAuth;
+use crate::products::attribute_schema::{check_schema, AttributeDefinition};
+use crate::schema::{manufacturers, products};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use diesel::PgConnection;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateProductRequest {
+    #[schema(example = "GAL-S24-128-BLK")]
+    pub sku: String,
+    #[schema(example = "Galaxy S24")]
+    pub name: String,
+    #[serde(default)]
+    pub attribute_schema: Vec<AttributeDefinition>,
+    #[serde(default)]
+    #[schema(example = json!(["https://cdn.example.com/s24-front.png"]))]
+    pub images: Vec<String>,
+    // signed by the manufacturer wallet for `create-product <sku>`
+    pub auth: WalletAuth,
+}
+
+// Only the fields that are set are changed; the SKU is fixed once created
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UpdateProductRequest {
+    #[schema(example = "Galaxy S24")]
+    pub name: Option<String>,
+    pub attribute_schema: Option<Vec<AttributeDefinition>>,
+    pub images: Option<Vec<String>>,
+    // signed by the owning manufacturer for `update-product <product_id>`
+    pub auth: WalletAuth,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/products",
+    request_body(content = CreateProductRequest, example = json!({
+        "sku": "GAL-S24-128-BLK",
+        "name": "Galaxy S24",
+        "attribute_schema": [
+            {"name": "color", "type": "string", "required": true, "allowed_values": ["black", "silver"]},
+            {"name": "storage_gb", "type": "number", "required": true}
+        ],
+        "images": ["https://cdn.example.com/s24-front.png"],
+        "auth": {
+            "caller": "0x1234567890abcdef1234567890abcdef12345678",
+            "signature": "0x4f8e...1b",
+            "issued_at": 1759309200
+        }
+    })),
+    responses(
+        (status = 201, description = "Product created", body = Product),
+        (status = 400, description = "Invalid input (e.g., empty SKU, invalid attribute schema or caller address)"),
+        (status = 401, description = "Wallet signature missing, expired or not from the caller"),
+        (status = 403, description = "Caller is not a registered manufacturer"),
+        (status = 409, description = "SKU already exists for this manufacturer"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Products"
+)]
+pub async fn create_product(
+    State(state): State<Arc<AppState>>,
+    AxumJson(request): AxumJson<CreateProductRequest>,
+) -> impl IntoResponse {
+    match create_product_internal(&state, &request).await {
+        Ok(product) => (StatusCode::CREATED, AxumJson(product)).into_response(),
+        Err(e) => {
+            eprintln!("Error creating product {}: {:?}", request.sku, e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/products/{product_id}",
+    params(
+        ("product_id" = i32, Path, description = "ID of the product", example = 1)
+    ),
+    request_body = UpdateProductRequest,
+    responses(
+        (status = 200, description = "Product updated", body = Product),
+        (status = 400, description = "Invalid input (e.g., invalid attribute schema or caller address)"),
+        (status = 401, description = "Wallet signature missing, expired or not from the caller"),
+        (status = 403, description = "Caller does not own the product"),
+        (status = 404, description = "Product not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Products"
+)]
+pub async fn update_product(
+    State(state): State<Arc<AppState>>,
+    Path(product_id): Path<i32>,
+    AxumJson(request): AxumJson<UpdateProductRequest>,
+) -> impl IntoResponse {
+    match update_product_internal(&state, product_id, &request).await {
+        Ok(product) => (StatusCode::OK, AxumJson(product)).into_response(),
+        Err(e) => {
+            eprintln!("Error updating product {}: {:?}", product_id, e);
+            error_response(e)
+        }
+    }
+}
+
+fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.contains("cannot be empty") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Invalid attribute schema") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Invalid caller address") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Invalid wallet signature") => (StatusCode::UNAUTHORIZED, e.to_string()),
+        s if s.contains("Caller is not a registered manufacturer") => {
+            (StatusCode::FORBIDDEN, e.to_string())
+        }
+        s if s.contains("Caller does not own the product") => (StatusCode::FORBIDDEN, e.to_string()),
+        s if s.contains("Product not found") => (StatusCode::NOT_FOUND, e.to_string()),
+        s if s.contains("SKU already exists") => (StatusCode::CONFLICT, e.to_string()),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, AxumJson(json!({"error": message}))).into_response()
+}
+
+async fn create_product_internal(
+    state: &Arc<AppState>,
+    request: &CreateProductRequest,
+) -> eyre::Result<Product> {
+    if request.sku.trim().is_empty() {
+        return Err(eyre::eyre!("SKU cannot be empty"));
+    }
+    if request.name.trim().is_empty() {
+        return Err(eyre::eyre!("Product name cannot be empty"));
+    }
+    check_schema(&request.attribute_schema)?;
+    let caller = request.auth.verify("create-product", request.sku.trim(), request)?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let manufacturer_address = registered_manufacturer(conn, &format!("{:?}", caller))?;
+
+    diesel::insert_into(products::table)
+        .values(NewProduct {
+            manufacturer_address,
+            sku: request.sku.trim().to_string(),
+            name: request.name.trim().to_string(),
+            attribute_schema: serde_json::to_value(&request.attribute_schema)?,
+            images: request.images.clone(),
+        })
+        .returning(Product::as_returning())
+        .get_result(conn)
+        .map_err(|e| match e {
+            // uq_products_manufacturer_sku
+            DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                eyre::eyre!("SKU already exists for this manufacturer")
+            }
+            _ => eyre::eyre!("Failed to insert product: {}", e),
+        })
+}
+
+async fn update_product_internal(
+    state: &Arc<AppState>,
+    product_id: i32,
+    request: &UpdateProductRequest,
+) -> eyre::Result<Product> {
+    if request.name.as_ref().is_some_and(|name| name.trim().is_empty()) {
+        return Err(eyre::eyre!("Product name cannot be empty"));
+    }
+    if let Some(schema) = &request.attribute_schema {
+        check_schema(schema)?;
+    }
+    let caller = request
+        .auth
+        .verify("update-product", &product_id.to_string(), request)?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let product = products::table
+        .filter(products::id.eq(product_id))
+        .select(Product::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query product: {}", e))?
+        .ok_or_else(|| eyre::eyre!("Product not found"))?;
+
+    if !product.manufacturer_address.eq_ignore_ascii_case(&format!("{:?}", caller)) {
+        return Err(eyre::eyre!("Caller does not own the product"));
+    }
+
+    let name = request
+        .name
+        .as_ref()
+        .map(|name| name.trim().to_string())
+        .unwrap_or(product.name);
+    let attribute_schema = match &request.attribute_schema {
+        Some(schema) => serde_json::to_value(schema)?,
+        None => product.attribute_schema,
+    };
+    let images = request.images.clone().unwrap_or(product.images);
+
+    // certificates already issued keep the metadata they were signed with
+    diesel::update(products::table.filter(products::id.eq(product_id)))
+        .set((
+            products::name.eq(name),
+            products::attribute_schema.eq(attribute_schema),
+            products::images.eq(images),
+            products::updated_at.eq(Utc::now()),
+        ))
+        .returning(Product::as_returning())
+        .get_result(conn)
+        .map_err(|e| eyre::eyre!("Failed to update product: {}", e))
+}
+
+// Address of the caller as stored for its manufacturer record
+pub(crate) fn registered_manufacturer(conn: &mut PgConnection, caller: &str) -> eyre::Result<String> {
+    if caller.is_empty() {
+        return Err(eyre::eyre!("Caller address cannot be empty"));
+    }
+
+    manufacturers::table
+        .filter(manufacturers::manufacturer_address.ilike(caller))
+        .filter(manufacturers::is_registered.eq(true))
+        .select(manufacturers::manufacturer_address)
+        .first::<String>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query manufacturer: {}", e))?
+        .ok_or_else(|| eyre::eyre!("Caller is not a registered manufacturer"))
+}
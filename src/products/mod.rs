@@ -0,0 +1,4 @@
+pub mod attribute_schema;
+pub mod product_template;
+pub mod manage_product;
+pub mod get_product;
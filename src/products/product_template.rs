@@ -0,0 +1,77 @@
+use crate::contract_models::{NewCertificateProduct, Product};
+use crate::models::certificate_model::{Certificate, CertificateData};
+use crate::products::attribute_schema::{parse_schema, validate_metadata};
+use crate::schema::{certificate_products, products};
+use diesel::prelude::*;
+use diesel::PgConnection;
+use eyre::Result;
+
+// Fetches a product the certificate owner is allowed to issue from
+pub fn load_product(conn: &mut PgConnection, product_id: i32, owner: &str) -> Result<Product> {
+    let product = products::table
+        .filter(products::id.eq(product_id))
+        .select(Product::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query product: {}", e))?
+        .ok_or_else(|| eyre::eyre!("Product not found"))?;
+
+    if !product.manufacturer_address.eq_ignore_ascii_case(owner) {
+        return Err(eyre::eyre!("Product does not belong to the certificate owner"));
+    }
+
+    Ok(product)
+}
+
+// Fills what the certificate leaves out from the product and checks its metadata
+// against the product's attribute schema
+pub fn apply_template(product: &Product, certificate: &mut CertificateData) -> Result<()> {
+    if certificate.name.is_empty() {
+        certificate.name = product.name.clone();
+    }
+
+    let schema = parse_schema(&product.attribute_schema)?;
+    validate_metadata(&schema, &certificate.metadata)
+}
+
+// Links a certificate whose manufacturer signature checked out to one of that manufacturer's
+// products, so the item is linked to it once it is claimed and indexed
+pub fn link_certificate_product(
+    conn: &mut PgConnection,
+    certificate: &Certificate,
+    product_id: i32,
+) -> Result<()> {
+    let product = load_product(conn, product_id, &format!("{:?}", certificate.owner))?;
+    validate_metadata(&parse_schema(&product.attribute_schema)?, &certificate.metadata)?;
+    record_certificate_product(conn, &certificate.unique_id, product_id)
+}
+
+// Remembers which product a certificate came from until its item is indexed
+fn record_certificate_product(
+    conn: &mut PgConnection,
+    unique_id: &str,
+    product_id: i32,
+) -> Result<()> {
+    diesel::insert_into(certificate_products::table)
+        .values(NewCertificateProduct {
+            unique_id: unique_id.to_string(),
+            product_id,
+        })
+        .on_conflict(certificate_products::unique_id)
+        .do_update()
+        .set(certificate_products::product_id.eq(product_id))
+        .execute(conn)
+        .map_err(|e| eyre::eyre!("Failed to record certificate product: {}", e))?;
+
+    Ok(())
+}
+
+// Product an item was issued from, if its certificate was created from one
+pub fn certificate_product(conn: &mut PgConnection, unique_id: &str) -> Result<Option<i32>> {
+    certificate_products::table
+        .filter(certificate_products::unique_id.eq(unique_id))
+        .select(certificate_products::product_id)
+        .first::<i32>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query certificate product: {}", e))
+}
@@ -0,0 +1,181 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::RecallCampaign;
+use crate::models::pagination::{keyset_page, Page, SortBy, SortOrder};
+use crate::recalls::recall_campaign::{load_campaign, recall_report, RecallReport};
+use crate::schema::recall_campaigns;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct RecallsQuery {
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub(crate) manufacturer: String,
+    pub(crate) cursor: Option<String>,
+    #[schema(example = 20)]
+    pub(crate) limit: Option<i64>,
+    pub(crate) sort: Option<SortBy>,
+    pub(crate) order: Option<SortOrder>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RecallsResponse {
+    recalls: Vec<RecallReport>,
+    #[schema(example = 42)]
+    total: i64,
+    #[schema(nullable = true)]
+    next_cursor: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/recalls/{campaign_id}",
+    params(
+        ("campaign_id" = i32, Path, description = "ID of the recall campaign", example = 1)
+    ),
+    responses(
+        (status = 200, description = "Campaign with affected and acknowledged unit counts", body = RecallReport, example = json!({
+            "id": 1,
+            "manufacturer_address": "0x1234567890abcdef1234567890abcdef12345678",
+            "title": "Battery overheating",
+            "reason": "Cells from one supplier batch may overheat while charging",
+            "remedy": "Free battery replacement at any service center",
+            "serial_from": "SN100000",
+            "serial_to": "SN100999",
+            "product_id": null,
+            "produced_from": null,
+            "produced_to": null,
+            "created_at": "2025-09-17T09:00:00Z",
+            "closed_at": null,
+            "affected": 1000,
+            "acknowledged": 412
+        })),
+        (status = 404, description = "Recall campaign not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Recalls"
+)]
+pub async fn get_recall(
+    State(state): State<Arc<AppState>>,
+    Path(campaign_id): Path<i32>,
+) -> impl IntoResponse {
+    match get_recall_internal(&state, campaign_id).await {
+        Ok(report) => (StatusCode::OK, AxumJson(report)).into_response(),
+        Err(e) => {
+            eprintln!("Error fetching recall {}: {:?}", campaign_id, e);
+            let (status, message) = match e.to_string().as_str() {
+                "Recall campaign not found" => (StatusCode::NOT_FOUND, e.to_string()),
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Internal server error: {}", e),
+                ),
+            };
+            (status, AxumJson(json!({"error": message}))).into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/recalls",
+    params(
+        ("manufacturer" = String, Query, description = "Address of the manufacturer", example = "0x1234567890abcdef1234567890abcdef12345678"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<i64>, Query, description = "Campaigns per page (default 20, max 100)", example = 20),
+        ("sort" = Option<SortBy>, Query, description = "Sort field: `created_at` (default) or `name` (title)", example = "created_at"),
+        ("order" = Option<SortOrder>, Query, description = "Sort order: `desc` (default) or `asc`", example = "desc")
+    ),
+    responses(
+        (status = 200, description = "Recall campaigns of the manufacturer, newest first by default", body = RecallsResponse),
+        (status = 400, description = "Invalid pagination"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Recalls"
+)]
+pub async fn list_recalls(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<RecallsQuery>,
+) -> impl IntoResponse {
+    match list_recalls_internal(&state, &query).await {
+        Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
+        Err(e) => {
+            eprintln!(
+                "Error listing recalls for manufacturer {}: {:?}",
+                query.manufacturer, e
+            );
+            let (status, message) = match e.to_string().as_str() {
+                s if s.contains("Invalid pagination") => (StatusCode::BAD_REQUEST, e.to_string()),
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Internal server error: {}", e),
+                ),
+            };
+            (status, AxumJson(json!({"error": message}))).into_response()
+        }
+    }
+}
+
+async fn get_recall_internal(state: &Arc<AppState>, campaign_id: i32) -> eyre::Result<RecallReport> {
+    let conn = &mut state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+
+    let campaign = load_campaign(conn, campaign_id)?;
+    recall_report(conn, campaign)
+}
+
+async fn list_recalls_internal(
+    state: &Arc<AppState>,
+    query: &RecallsQuery,
+) -> eyre::Result<RecallsResponse> {
+    let page = Page::new(query.cursor.as_deref(), query.limit, query.sort, query.order)?;
+
+    let conn = &mut state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+
+    let total = recall_campaigns::table
+        .filter(recall_campaigns::manufacturer_address.ilike(&query.manufacturer))
+        .count()
+        .get_result::<i64>(conn)
+        .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?;
+
+    let rows = keyset_page!(
+        recall_campaigns::table
+            .filter(recall_campaigns::manufacturer_address.ilike(&query.manufacturer))
+            .select(RecallCampaign::as_select())
+            .into_boxed(),
+        &page,
+        recall_campaigns::created_at,
+        recall_campaigns::title,
+        recall_campaigns::id,
+        i32
+    )
+    .load::<RecallCampaign>(conn)
+    .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?;
+
+    let (campaigns, next_cursor) = page.finish(rows, |campaign| {
+        (campaign.created_at, campaign.title.clone(), campaign.id.to_string())
+    });
+
+    let recalls = campaigns
+        .into_iter()
+        .map(|campaign| recall_report(conn, campaign))
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    Ok(RecallsResponse {
+        recalls,
+        total,
+        next_cursor,
+    })
+}
@@ -0,0 +1,283 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::{NewRecallCampaign, RecallCampaign};
+use crate::models::wallet_auth::WalletAuth;
+use crate::products::manage_product::registered_manufacturer;
+use crate::recalls::recall_campaign::{
+    load_campaign, match_campaign, recall_report, serial_number_key, split_serial, RecallReport,
+    SERIAL_DIGITS,
+};
+use crate::schema::{products, recall_campaigns};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use chrono::Utc;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+// At least one of the serial range, SKU or production window must be set;
+// all of the ones that are set have to match. Serial bounds end in a number and share
+// their prefix; SN9 lies inside SN1..SN10
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateRecallRequest {
+    #[schema(example = "Battery overheating")]
+    pub title: String,
+    #[schema(example = "Cells from one supplier batch may overheat while charging")]
+    pub reason: String,
+    #[schema(example = "Free battery replacement at any service center")]
+    pub remedy: Option<String>,
+    #[schema(example = "SN100000")]
+    pub serial_from: Option<String>,
+    #[schema(example = "SN100999")]
+    pub serial_to: Option<String>,
+    // one of the caller's products
+    #[schema(example = "GAL-S24-128-BLK")]
+    pub sku: Option<String>,
+    // production date window, same unit as the certificate date
+    #[schema(example = 1725148800)]
+    pub produced_from: Option<i64>,
+    #[schema(example = 1727740799)]
+    pub produced_to: Option<i64>,
+    // signed by the manufacturer wallet for `create-recall <title>`
+    pub auth: WalletAuth,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CloseRecallRequest {
+    // signed by the owning manufacturer for `close-recall <campaign_id>`
+    pub auth: WalletAuth,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/recalls",
+    request_body = CreateRecallRequest,
+    responses(
+        (status = 201, description = "Campaign created and affected items matched", body = RecallReport),
+        (status = 400, description = "Invalid input (e.g., no criteria, an empty range or an invalid caller address)"),
+        (status = 401, description = "Wallet signature missing, expired or not from the caller"),
+        (status = 403, description = "Caller is not a registered manufacturer"),
+        (status = 404, description = "Product not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Recalls"
+)]
+pub async fn create_recall(
+    State(state): State<Arc<AppState>>,
+    AxumJson(request): AxumJson<CreateRecallRequest>,
+) -> impl IntoResponse {
+    match create_recall_internal(&state, &request).await {
+        Ok(report) => (StatusCode::CREATED, AxumJson(report)).into_response(),
+        Err(e) => {
+            eprintln!("Error creating recall {}: {:?}", request.title, e);
+            error_response(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/recalls/{campaign_id}/close",
+    params(
+        ("campaign_id" = i32, Path, description = "ID of the recall campaign", example = 1)
+    ),
+    request_body = CloseRecallRequest,
+    responses(
+        (status = 200, description = "Campaign closed; its items no longer show the recall", body = RecallReport),
+        (status = 400, description = "Invalid caller address"),
+        (status = 401, description = "Wallet signature missing, expired or not from the caller"),
+        (status = 403, description = "Caller does not own the campaign"),
+        (status = 404, description = "Recall campaign not found"),
+        (status = 409, description = "Recall campaign is already closed"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Recalls"
+)]
+pub async fn close_recall(
+    State(state): State<Arc<AppState>>,
+    Path(campaign_id): Path<i32>,
+    AxumJson(request): AxumJson<CloseRecallRequest>,
+) -> impl IntoResponse {
+    match close_recall_internal(&state, campaign_id, &request).await {
+        Ok(report) => (StatusCode::OK, AxumJson(report)).into_response(),
+        Err(e) => {
+            eprintln!("Error closing recall {}: {:?}", campaign_id, e);
+            error_response(e)
+        }
+    }
+}
+
+fn error_response(e: eyre::Report) -> axum::response::Response {
+    let (status, message) = match e.to_string().as_str() {
+        s if s.contains("cannot be empty") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Invalid recall criteria") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Invalid caller address") => (StatusCode::BAD_REQUEST, e.to_string()),
+        s if s.contains("Invalid wallet signature") => (StatusCode::UNAUTHORIZED, e.to_string()),
+        s if s.contains("Caller is not a registered manufacturer") => {
+            (StatusCode::FORBIDDEN, e.to_string())
+        }
+        s if s.contains("Caller does not own the recall campaign") => {
+            (StatusCode::FORBIDDEN, e.to_string())
+        }
+        s if s.contains("not found") => (StatusCode::NOT_FOUND, e.to_string()),
+        s if s.contains("already closed") => (StatusCode::CONFLICT, e.to_string()),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Internal server error: {}", e),
+        ),
+    };
+    (status, AxumJson(json!({"error": message}))).into_response()
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+async fn create_recall_internal(
+    state: &Arc<AppState>,
+    request: &CreateRecallRequest,
+) -> eyre::Result<RecallReport> {
+    if request.title.trim().is_empty() {
+        return Err(eyre::eyre!("Title cannot be empty"));
+    }
+    if request.reason.trim().is_empty() {
+        return Err(eyre::eyre!("Reason cannot be empty"));
+    }
+
+    let serial_from = non_empty(&request.serial_from);
+    let serial_to = non_empty(&request.serial_to);
+    let sku = non_empty(&request.sku);
+
+    if serial_from.is_none()
+        && serial_to.is_none()
+        && sku.is_none()
+        && request.produced_from.is_none()
+        && request.produced_to.is_none()
+    {
+        return Err(eyre::eyre!(
+            "Invalid recall criteria: set a serial range, a SKU or a production window"
+        ));
+    }
+    let bounds = [("serial_from", &serial_from), ("serial_to", &serial_to)]
+        .into_iter()
+        .filter_map(|(field, serial)| serial.as_deref().map(|serial| (field, serial)))
+        .map(|(field, serial)| match split_serial(serial) {
+            Some((_, digits)) if digits.len() > SERIAL_DIGITS => Err(eyre::eyre!(
+                "Invalid recall criteria: {} has more than {} digits",
+                field,
+                SERIAL_DIGITS
+            )),
+            Some(parts) => Ok(parts),
+            None => Err(eyre::eyre!("Invalid recall criteria: {} must end in a number", field)),
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+    if let [(from_prefix, from), (to_prefix, to)] = bounds[..] {
+        if from_prefix != to_prefix {
+            return Err(eyre::eyre!(
+                "Invalid recall criteria: serial_from and serial_to have different prefixes"
+            ));
+        }
+        if serial_number_key(from) > serial_number_key(to) {
+            return Err(eyre::eyre!("Invalid recall criteria: serial_from is after serial_to"));
+        }
+    }
+    if let (Some(from), Some(to)) = (request.produced_from, request.produced_to)
+        && from > to
+    {
+        return Err(eyre::eyre!(
+            "Invalid recall criteria: produced_from is after produced_to"
+        ));
+    }
+    let caller = request.auth.verify("create-recall", request.title.trim(), request)?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let manufacturer_address = registered_manufacturer(conn, &format!("{:?}", caller))?;
+
+    let product_id = match &sku {
+        Some(sku) => Some(
+            products::table
+                .filter(products::manufacturer_address.eq(&manufacturer_address))
+                .filter(products::sku.eq(sku))
+                .select(products::id)
+                .first::<i32>(conn)
+                .optional()
+                .map_err(|e| eyre::eyre!("Failed to query product: {}", e))?
+                .ok_or_else(|| eyre::eyre!("Product not found"))?,
+        ),
+        None => None,
+    };
+
+    // campaign and its notices appear together or not at all
+    conn.transaction::<_, eyre::Error, _>(|conn| {
+        let campaign = diesel::insert_into(recall_campaigns::table)
+            .values(NewRecallCampaign {
+                manufacturer_address,
+                title: request.title.trim().to_string(),
+                reason: request.reason.trim().to_string(),
+                remedy: non_empty(&request.remedy),
+                serial_from,
+                serial_to,
+                product_id,
+                produced_from: request.produced_from,
+                produced_to: request.produced_to,
+            })
+            .returning(RecallCampaign::as_returning())
+            .get_result(conn)
+            .map_err(|e| eyre::eyre!("Failed to insert recall campaign: {}", e))?;
+
+        let matched = match_campaign(conn, &campaign)?;
+        eprintln!("Recall campaign {} matched {} item(s)", campaign.id, matched);
+
+        recall_report(conn, campaign)
+    })
+}
+
+async fn close_recall_internal(
+    state: &Arc<AppState>,
+    campaign_id: i32,
+    request: &CloseRecallRequest,
+) -> eyre::Result<RecallReport> {
+    let caller = request
+        .auth
+        .verify("close-recall", &campaign_id.to_string(), request)?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let campaign = load_campaign(conn, campaign_id)?;
+    if !campaign
+        .manufacturer_address
+        .eq_ignore_ascii_case(&format!("{:?}", caller))
+    {
+        return Err(eyre::eyre!("Caller does not own the recall campaign"));
+    }
+
+    let campaign = diesel::update(
+        recall_campaigns::table
+            .filter(recall_campaigns::id.eq(campaign_id))
+            .filter(recall_campaigns::closed_at.is_null()),
+    )
+    .set(recall_campaigns::closed_at.eq(Some(Utc::now())))
+    .returning(RecallCampaign::as_returning())
+    .get_result(conn)
+    .optional()
+    .map_err(|e| eyre::eyre!("Failed to close recall campaign: {}", e))?
+    .ok_or_else(|| eyre::eyre!("Recall campaign is already closed"))?;
+
+    recall_report(conn, campaign)
+}
@@ -0,0 +1,4 @@
+pub mod recall_campaign;
+pub mod manage_recall;
+pub mod get_recall;
+pub mod recall_notice;
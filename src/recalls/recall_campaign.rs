@@ -0,0 +1,231 @@
+use crate::contract_models::{RecallCampaign, RecallItem};
+use crate::models::username_policy::lower;
+use crate::schema::{items, manufacturers, recall_campaigns, recall_items};
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel::sql_types::{Int4, Nullable, Text};
+use diesel::PgConnection;
+use eyre::Result;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+// numeric serial suffixes are zero-padded to this width before they are compared
+pub const SERIAL_DIGITS: usize = 40;
+// trailing run of digits, e.g. "100042" in "SN-A100042"
+const SERIAL_NUMBER_PATTERN: &str = "[0-9]+$";
+
+define_sql_function!(fn regexp_replace(source: Text, pattern: Text, replacement: Text) -> Text);
+define_sql_function!(fn substring(source: Text, pattern: Text) -> Nullable<Text>);
+define_sql_function!(fn ltrim(source: Nullable<Text>, characters: Text) -> Nullable<Text>);
+define_sql_function!(fn lpad(source: Nullable<Text>, length: Int4, fill: Text) -> Nullable<Text>);
+
+// Campaign plus how far it got: `affected` units matched, `acknowledged` of them
+// confirmed by their owner
+#[derive(Serialize, ToSchema)]
+pub struct RecallReport {
+    #[serde(flatten)]
+    pub campaign: RecallCampaign,
+    #[schema(example = 1000)]
+    pub affected: i64,
+    #[schema(example = 412)]
+    pub acknowledged: i64,
+}
+
+// What verification and item lookups show for an open recall affecting the item
+#[derive(Serialize, ToSchema, Clone)]
+pub struct ItemRecall {
+    pub campaign_id: i32,
+    #[schema(example = "Battery overheating")]
+    pub title: String,
+    #[schema(example = "Cells from one supplier batch may overheat while charging")]
+    pub reason: String,
+    #[schema(nullable = true, example = "Free battery replacement at any service center")]
+    pub remedy: Option<String>,
+    pub acknowledged: bool,
+}
+
+// Splits a serial into its prefix and numeric suffix ("SN09" -> ("SN", "09")); None when
+// it doesn't end in a digit
+pub fn split_serial(serial: &str) -> Option<(&str, &str)> {
+    let prefix = serial.trim_end_matches(|c: char| c.is_ascii_digit());
+    if prefix.len() == serial.len() {
+        return None;
+    }
+    Some((prefix, &serial[prefix.len()..]))
+}
+
+// Numeric suffix in the zero-padded form the range filter compares, so SN9 < SN10
+pub fn serial_number_key(digits: &str) -> String {
+    format!("{:0>width$}", digits.trim_start_matches('0'), width = SERIAL_DIGITS)
+}
+
+// Units of the campaign's manufacturer that fall inside every criterion it sets.
+// A serial range covers the serials with the bounds' prefix whose numeric suffix lies
+// between theirs; serials without a numeric suffix never match a range.
+fn matching_items<'a>(
+    campaign: &'a RecallCampaign,
+    manufacturer_name: &'a str,
+) -> items::BoxedQuery<'a, diesel::pg::Pg> {
+    let mut query = items::table
+        .filter(lower(items::manufacturer).eq(manufacturer_name.to_lowercase()))
+        .into_boxed();
+
+    let serial_number = || {
+        lpad(
+            ltrim(substring(items::serial, SERIAL_NUMBER_PATTERN), "0"),
+            SERIAL_DIGITS as i32,
+            "0",
+        )
+    };
+    for (bound, is_lower) in [(&campaign.serial_from, true), (&campaign.serial_to, false)] {
+        // the bounds were checked when the campaign was created
+        let Some((prefix, digits)) = bound.as_deref().and_then(split_serial) else {
+            continue;
+        };
+        query = query.filter(
+            regexp_replace(items::serial, SERIAL_NUMBER_PATTERN, "").eq(prefix.to_string()),
+        );
+        query = if is_lower {
+            query.filter(serial_number().ge(serial_number_key(digits)))
+        } else {
+            query.filter(serial_number().le(serial_number_key(digits)))
+        };
+    }
+    if let Some(product_id) = campaign.product_id {
+        query = query.filter(items::product_id.eq(product_id));
+    }
+    if let Some(produced_from) = campaign.produced_from {
+        query = query.filter(items::date.ge(produced_from));
+    }
+    if let Some(produced_to) = campaign.produced_to {
+        query = query.filter(items::date.le(produced_to));
+    }
+
+    query
+}
+
+fn manufacturer_name(conn: &mut PgConnection, manufacturer_address: &str) -> Result<String> {
+    manufacturers::table
+        .filter(manufacturers::manufacturer_address.ilike(manufacturer_address))
+        .select(manufacturers::manufacturer_name)
+        .first::<String>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query manufacturer: {}", e))?
+        .ok_or_else(|| eyre::eyre!("Manufacturer not found"))
+}
+
+// Attaches every indexed unit the campaign covers; each row is the owner's notice
+pub fn match_campaign(conn: &mut PgConnection, campaign: &RecallCampaign) -> Result<usize> {
+    let manufacturer_name = manufacturer_name(conn, &campaign.manufacturer_address)?;
+
+    let affected = matching_items(campaign, &manufacturer_name)
+        .select((campaign.id.into_sql::<Int4>(), items::item_id));
+
+    diesel::insert_into(recall_items::table)
+        .values(affected)
+        .into_columns((recall_items::campaign_id, recall_items::item_id))
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .map_err(|e| eyre::eyre!("Failed to match recall campaign: {}", e))
+}
+
+// Called by the indexer for a newly created item: certificates issued before a recall
+// can still be claimed after it was opened
+pub fn attach_open_recalls(conn: &mut PgConnection, item_id: &str) -> Result<usize> {
+    let manufacturer_name = items::table
+        .filter(items::item_id.eq(item_id))
+        .select(items::manufacturer)
+        .first::<String>(conn)
+        .map_err(|e| eyre::eyre!("Failed to query item: {}", e))?;
+
+    let addresses = manufacturers::table
+        .filter(lower(manufacturers::manufacturer_name).eq(manufacturer_name.to_lowercase()))
+        .select(manufacturers::manufacturer_address)
+        .load::<String>(conn)
+        .map_err(|e| eyre::eyre!("Failed to query manufacturer: {}", e))?;
+
+    let campaigns = recall_campaigns::table
+        .filter(recall_campaigns::manufacturer_address.eq_any(addresses))
+        .filter(recall_campaigns::closed_at.is_null())
+        .select(RecallCampaign::as_select())
+        .load(conn)
+        .map_err(|e| eyre::eyre!("Failed to query recall campaigns: {}", e))?;
+
+    let mut attached = 0;
+    for campaign in campaigns {
+        let covered = matching_items(&campaign, &manufacturer_name)
+            .filter(items::item_id.eq(item_id))
+            .select(items::item_id)
+            .first::<String>(conn)
+            .optional()
+            .map_err(|e| eyre::eyre!("Failed to match recall campaign: {}", e))?
+            .is_some();
+
+        if covered {
+            attached += diesel::insert_into(recall_items::table)
+                .values((
+                    recall_items::campaign_id.eq(campaign.id),
+                    recall_items::item_id.eq(item_id),
+                ))
+                .on_conflict_do_nothing()
+                .execute(conn)
+                .map_err(|e| eyre::eyre!("Failed to attach recall: {}", e))?;
+        }
+    }
+
+    Ok(attached)
+}
+
+pub fn recall_report(conn: &mut PgConnection, campaign: RecallCampaign) -> Result<RecallReport> {
+    let affected = recall_items::table
+        .filter(recall_items::campaign_id.eq(campaign.id))
+        .select(count_star())
+        .first::<i64>(conn)
+        .map_err(|e| eyre::eyre!("Failed to count recalled items: {}", e))?;
+
+    let acknowledged = recall_items::table
+        .filter(recall_items::campaign_id.eq(campaign.id))
+        .filter(recall_items::acknowledged_at.is_not_null())
+        .select(count_star())
+        .first::<i64>(conn)
+        .map_err(|e| eyre::eyre!("Failed to count acknowledged items: {}", e))?;
+
+    Ok(RecallReport {
+        campaign,
+        affected,
+        acknowledged,
+    })
+}
+
+pub fn load_campaign(conn: &mut PgConnection, campaign_id: i32) -> Result<RecallCampaign> {
+    recall_campaigns::table
+        .filter(recall_campaigns::id.eq(campaign_id))
+        .select(RecallCampaign::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query recall campaign: {}", e))?
+        .ok_or_else(|| eyre::eyre!("Recall campaign not found"))
+}
+
+// Open recalls covering the item
+pub fn item_recalls(conn: &mut PgConnection, item_id: &str) -> Result<Vec<ItemRecall>> {
+    let rows = recall_items::table
+        .inner_join(recall_campaigns::table.on(recall_campaigns::id.eq(recall_items::campaign_id)))
+        .filter(recall_items::item_id.eq(item_id))
+        .filter(recall_campaigns::closed_at.is_null())
+        .order(recall_campaigns::created_at.desc())
+        .select((RecallItem::as_select(), RecallCampaign::as_select()))
+        .load::<(RecallItem, RecallCampaign)>(conn)
+        .map_err(|e| eyre::eyre!("Failed to query item recalls: {}", e))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(recall_item, campaign)| ItemRecall {
+            campaign_id: campaign.id,
+            title: campaign.title,
+            reason: campaign.reason,
+            remedy: campaign.remedy,
+            acknowledged: recall_item.acknowledged_at.is_some(),
+        })
+        .collect())
+}
@@ -0,0 +1,226 @@
+use crate::config::app_state::AppState;
+use crate::contract_models::{RecallCampaign, RecallItem};
+use crate::schema::{items, recall_campaigns, recall_items};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use chrono::Utc;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+// Owners are notified through their recall inbox: every open campaign covering an item
+// they currently hold shows up here until they acknowledge it.
+
+#[derive(Deserialize, ToSchema)]
+pub struct RecallNoticesQuery {
+    #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef12")]
+    pub(crate) owner: String,
+    // also list notices that were already acknowledged
+    #[serde(default)]
+    pub(crate) include_acknowledged: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RecallNotice {
+    #[schema(example = "item123")]
+    item_id: String,
+    #[schema(example = "Galaxy S24")]
+    item_name: String,
+    #[schema(example = "SN100042")]
+    serial: String,
+    recall: RecallCampaign,
+    #[schema(nullable = true, value_type = Option<String>)]
+    acknowledged_at: Option<chrono::DateTime<Utc>>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RecallNoticesResponse {
+    notices: Vec<RecallNotice>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct AcknowledgeRecallRequest {
+    #[schema(example = "item123")]
+    pub item_id: String,
+    // current owner of the item
+    #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef12")]
+    pub caller: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/recalls/notices",
+    params(
+        ("owner" = String, Query, description = "Owner's blockchain address", example = "0xabcdef1234567890abcdef1234567890abcdef12"),
+        ("include_acknowledged" = Option<bool>, Query, description = "Also return notices the owner already acknowledged", example = false)
+    ),
+    responses(
+        (status = 200, description = "Open recalls affecting the owner's items", body = RecallNoticesResponse),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Recalls"
+)]
+pub async fn get_recall_notices(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<RecallNoticesQuery>,
+) -> impl IntoResponse {
+    match get_recall_notices_internal(&state, &query).await {
+        Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
+        Err(e) => {
+            eprintln!("Error fetching recall notices for {}: {:?}", query.owner, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                AxumJson(json!({"error": format!("Internal server error: {}", e)})),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/recalls/{campaign_id}/acknowledge",
+    params(
+        ("campaign_id" = i32, Path, description = "ID of the recall campaign", example = 1)
+    ),
+    request_body = AcknowledgeRecallRequest,
+    responses(
+        (status = 200, description = "Recall acknowledged for the item", body = RecallItem),
+        (status = 403, description = "Caller is not the item owner"),
+        (status = 404, description = "Item is not affected by this recall"),
+        (status = 409, description = "Recall already acknowledged for this item, or the campaign is closed"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Recalls"
+)]
+pub async fn acknowledge_recall(
+    State(state): State<Arc<AppState>>,
+    Path(campaign_id): Path<i32>,
+    AxumJson(request): AxumJson<AcknowledgeRecallRequest>,
+) -> impl IntoResponse {
+    match acknowledge_recall_internal(&state, campaign_id, &request).await {
+        Ok(recall_item) => (StatusCode::OK, AxumJson(recall_item)).into_response(),
+        Err(e) => {
+            eprintln!(
+                "Error acknowledging recall {} for item {}: {:?}",
+                campaign_id, request.item_id, e
+            );
+            let (status, message) = match e.to_string().as_str() {
+                s if s.contains("Caller is not the item owner") => {
+                    (StatusCode::FORBIDDEN, e.to_string())
+                }
+                s if s.contains("not affected") => (StatusCode::NOT_FOUND, e.to_string()),
+                s if s.contains("already acknowledged") => (StatusCode::CONFLICT, e.to_string()),
+                s if s.contains("closed") => (StatusCode::CONFLICT, e.to_string()),
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Internal server error: {}", e),
+                ),
+            };
+            (status, AxumJson(json!({"error": message}))).into_response()
+        }
+    }
+}
+
+async fn get_recall_notices_internal(
+    state: &Arc<AppState>,
+    query: &RecallNoticesQuery,
+) -> eyre::Result<RecallNoticesResponse> {
+    let conn = &mut state
+        .db_pool
+        .get()
+        .map_err(|e| eyre::eyre!("Failed to get database connection: {}", e))?;
+
+    let mut notices_query = recall_items::table
+        .inner_join(items::table.on(items::item_id.eq(recall_items::item_id)))
+        .inner_join(recall_campaigns::table.on(recall_campaigns::id.eq(recall_items::campaign_id)))
+        .filter(items::owner.ilike(&query.owner))
+        .filter(recall_campaigns::closed_at.is_null())
+        .into_boxed();
+    if !query.include_acknowledged {
+        notices_query = notices_query.filter(recall_items::acknowledged_at.is_null());
+    }
+
+    let rows = notices_query
+        .order(recall_campaigns::created_at.desc())
+        .select((
+            items::item_id,
+            items::name,
+            items::serial,
+            RecallCampaign::as_select(),
+            recall_items::acknowledged_at,
+        ))
+        .load::<(String, String, String, RecallCampaign, Option<chrono::DateTime<Utc>>)>(conn)
+        .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?;
+
+    let notices = rows
+        .into_iter()
+        .map(|(item_id, item_name, serial, recall, acknowledged_at)| RecallNotice {
+            item_id,
+            item_name,
+            serial,
+            recall,
+            acknowledged_at,
+        })
+        .collect();
+
+    Ok(RecallNoticesResponse { notices })
+}
+
+async fn acknowledge_recall_internal(
+    state: &Arc<AppState>,
+    campaign_id: i32,
+    request: &AcknowledgeRecallRequest,
+) -> eyre::Result<RecallItem> {
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let (recall_item, closed_at, owner) = recall_items::table
+        .inner_join(items::table.on(items::item_id.eq(recall_items::item_id)))
+        .inner_join(recall_campaigns::table.on(recall_campaigns::id.eq(recall_items::campaign_id)))
+        .filter(recall_items::campaign_id.eq(campaign_id))
+        .filter(recall_items::item_id.eq(&request.item_id))
+        .select((
+            RecallItem::as_select(),
+            recall_campaigns::closed_at,
+            items::owner,
+        ))
+        .first::<(RecallItem, Option<chrono::DateTime<Utc>>, String)>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query database: {}", e))?
+        .ok_or_else(|| eyre::eyre!("Item is not affected by this recall"))?;
+
+    if !owner.eq_ignore_ascii_case(&request.caller) {
+        return Err(eyre::eyre!("Caller is not the item owner"));
+    }
+    if closed_at.is_some() {
+        return Err(eyre::eyre!("Recall campaign is closed"));
+    }
+    if recall_item.acknowledged_at.is_some() {
+        return Err(eyre::eyre!("Recall already acknowledged for this item"));
+    }
+
+    diesel::update(
+        recall_items::table
+            .filter(recall_items::campaign_id.eq(campaign_id))
+            .filter(recall_items::item_id.eq(&request.item_id))
+            .filter(recall_items::acknowledged_at.is_null()),
+    )
+    .set((
+        recall_items::acknowledged_by.eq(Some(request.caller.clone())),
+        recall_items::acknowledged_at.eq(Some(Utc::now())),
+    ))
+    .returning(RecallItem::as_returning())
+    .get_result(conn)
+    .optional()
+    .map_err(|e| eyre::eyre!("Failed to acknowledge recall: {}", e))?
+    .ok_or_else(|| eyre::eyre!("Recall already acknowledged for this item"))
+}
@@ -1,11 +1,43 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_keys (key_hash) {
+        key_hash -> Text,
+        name -> Text,
+        created_by -> Text,
+        created_at -> Timestamptz,
+        revoked_at -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     authenticity_settings (id) {
         id -> Int4,
         authenticity_address -> Text,
         tnx_hash -> Text,
-        created_at -> Text,
+        created_at -> Timestamptz,
+        block_number -> Nullable<Int8>,
+        log_index -> Nullable<Int4>,
+        block_time -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    certificate_products (unique_id) {
+        unique_id -> Text,
+        product_id -> Int4,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    certificate_revocations (cert_hash) {
+        cert_hash -> Text,
+        unique_id -> Text,
+        manufacturer_address -> Text,
+        reason -> Text,
+        revoked_by -> Text,
+        revoked_at -> Timestamptz,
     }
 }
 
@@ -14,7 +46,10 @@ diesel::table! {
         id -> Int4,
         item_hash -> Text,
         tnx_hash -> Text,
-        created_at -> Text,
+        created_at -> Timestamptz,
+        block_number -> Nullable<Int8>,
+        log_index -> Nullable<Int4>,
+        block_time -> Nullable<Timestamptz>,
     }
 }
 
@@ -23,7 +58,84 @@ diesel::table! {
         contract_address -> Text,
         owner -> Text,
         tnx_hash -> Text,
-        created_at -> Text,
+        created_at -> Timestamptz,
+        block_number -> Nullable<Int8>,
+        log_index -> Nullable<Int4>,
+        block_time -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    gas_budgets (sponsor_kind, sponsor) {
+        sponsor_kind -> Text,
+        sponsor -> Text,
+        monthly_limit_wei -> Int8,
+        updated_by -> Text,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    gas_ledger (id) {
+        id -> Int4,
+        tnx_hash -> Text,
+        operation -> Text,
+        sponsor_kind -> Text,
+        sponsor -> Text,
+        gas_used -> Int8,
+        gas_price_wei -> Int8,
+        fee_wei -> Int8,
+        succeeded -> Bool,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    gas_reservations (id) {
+        id -> Int4,
+        sponsor_kind -> Text,
+        sponsor -> Text,
+        fee_wei -> Int8,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    issued_certificates (unique_id) {
+        unique_id -> Text,
+        manufacturer_address -> Text,
+        created_at -> Timestamptz,
+        cert_hash -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    item_flags (id) {
+        id -> Int4,
+        item_id -> Text,
+        status -> Text,
+        reason -> Text,
+        evidence -> Array<Text>,
+        flagged_by -> Text,
+        created_at -> Timestamptz,
+        cleared_by -> Nullable<Text>,
+        cleared_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    item_history_events (event_id) {
+        item_id -> Text,
+        event_id -> Text,
+        kind -> Text,
+        from_address -> Nullable<Text>,
+        to_address -> Nullable<Text>,
+        status -> Nullable<Text>,
+        reason -> Nullable<Text>,
+        tnx_hash -> Nullable<Text>,
+        block_number -> Nullable<Int8>,
+        block_time -> Nullable<Timestamptz>,
+        recorded_at -> Timestamptz,
     }
 }
 
@@ -37,8 +149,12 @@ diesel::table! {
         owner -> Text,
         manufacturer -> Text,
         metadata -> Array<Nullable<Text>>,
-        created_at -> Text,
+        created_at -> Timestamptz,
         tnx_hash -> Text,
+        product_id -> Nullable<Int4>,
+        block_number -> Nullable<Int8>,
+        log_index -> Nullable<Int4>,
+        block_time -> Nullable<Timestamptz>,
     }
 }
 
@@ -47,8 +163,21 @@ diesel::table! {
         manufacturer_address -> Text,
         manufacturer_name -> Text,
         is_registered -> Bool,
-        registered_at -> Text,
+        registered_at -> Timestamptz,
         tnx_hash -> Text,
+        block_number -> Nullable<Int8>,
+        log_index -> Nullable<Int4>,
+        block_time -> Nullable<Timestamptz>,
+        legal_name -> Nullable<Text>,
+        logo_url -> Nullable<Text>,
+        website -> Nullable<Text>,
+        contact_email -> Nullable<Text>,
+        contact_phone -> Nullable<Text>,
+        profile_updated_at -> Nullable<Timestamptz>,
+        kyb_status -> Text,
+        kyb_note -> Nullable<Text>,
+        kyb_reviewed_by -> Nullable<Text>,
+        kyb_reviewed_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -59,17 +188,92 @@ diesel::table! {
         old_owner -> Text,
         new_owner -> Text,
         tnx_hash -> Text,
-        created_at -> Text,
+        created_at -> Timestamptz,
+        block_number -> Nullable<Int8>,
+        log_index -> Nullable<Int4>,
+        block_time -> Nullable<Timestamptz>,
     }
 }
 
 diesel::table! {
-    ownership_codes (ownership_code) {
-        ownership_code -> Text,
+    ownership_codes (code_hash) {
+        code_hash -> Text,
         item_id -> Text,
         item_owner -> Text,
         temp_owner -> Text,
-        created_at -> Text,
+        created_at -> Timestamptz,
+        expires_at -> Timestamptz,
+        code_format -> Text,
+        status -> Text,
+        status_changed_at -> Timestamptz,
+        commit_tnx_hash -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    ownership_mismatches (id) {
+        id -> Int4,
+        item_id -> Text,
+        db_owner -> Text,
+        chain_owner -> Nullable<Text>,
+        detected_at -> Timestamptz,
+        resolved_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    products (id) {
+        id -> Int4,
+        manufacturer_address -> Text,
+        sku -> Text,
+        name -> Text,
+        attribute_schema -> Jsonb,
+        images -> Array<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    recall_campaigns (id) {
+        id -> Int4,
+        manufacturer_address -> Text,
+        title -> Text,
+        reason -> Text,
+        remedy -> Nullable<Text>,
+        serial_from -> Nullable<Text>,
+        serial_to -> Nullable<Text>,
+        product_id -> Nullable<Int4>,
+        produced_from -> Nullable<Int8>,
+        produced_to -> Nullable<Int8>,
+        created_at -> Timestamptz,
+        closed_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    recall_items (campaign_id, item_id) {
+        campaign_id -> Int4,
+        item_id -> Text,
+        matched_at -> Timestamptz,
+        acknowledged_by -> Nullable<Text>,
+        acknowledged_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    signing_keys (key_address) {
+        key_address -> Text,
+        manufacturer_address -> Text,
+        valid_from -> Timestamptz,
+        valid_until -> Nullable<Timestamptz>,
+        revoked_at -> Nullable<Timestamptz>,
+        rotated_to -> Nullable<Text>,
+        tnx_hash -> Text,
+        block_number -> Int8,
+        log_index -> Int4,
+        block_time -> Timestamptz,
+        updated_at -> Timestamptz,
     }
 }
 
@@ -78,18 +282,73 @@ diesel::table! {
         user_address -> Text,
         username -> Text,
         is_registered -> Bool,
-        created_at -> Text,
+        created_at -> Timestamptz,
         tnx_hash -> Text,
+        block_number -> Nullable<Int8>,
+        log_index -> Nullable<Int4>,
+        block_time -> Nullable<Timestamptz>,
+        display_name -> Nullable<Text>,
+        avatar_url -> Nullable<Text>,
+        email -> Nullable<Text>,
+        profile_visibility -> Text,
+        show_email -> Bool,
+        profile_updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    verification_alerts (id) {
+        id -> Int4,
+        unique_id -> Text,
+        manufacturer_address -> Text,
+        kind -> Text,
+        details -> Text,
+        scan_count -> Int4,
+        first_seen -> Timestamptz,
+        last_seen -> Timestamptz,
+        resolved_by -> Nullable<Text>,
+        resolved_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    verification_logs (id) {
+        id -> Int4,
+        unique_id -> Text,
+        cert_hash -> Nullable<Text>,
+        claimed_manufacturer -> Text,
+        signer -> Nullable<Text>,
+        result -> Text,
+        region -> Nullable<Text>,
+        created_at -> Timestamptz,
+        client_ip -> Nullable<Text>,
+        api_key_hash -> Nullable<Text>,
     }
 }
 
 diesel::allow_tables_to_appear_in_same_query!(
+    api_keys,
     authenticity_settings,
+    certificate_products,
+    certificate_revocations,
     code_revokations,
     contracts,
+    gas_budgets,
+    gas_ledger,
+    gas_reservations,
+    issued_certificates,
+    item_flags,
+    item_history_events,
     items,
     manufacturers,
     ownership_claims,
     ownership_codes,
+    ownership_mismatches,
+    products,
+    recall_campaigns,
+    recall_items,
+    signing_keys,
     users_info,
+    verification_alerts,
+    verification_logs,
 );
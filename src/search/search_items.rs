@@ -0,0 +1,222 @@
+use crate::config::app_state::AppState;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use diesel::prelude::*;
+use diesel::sql_types::{Float8, Int8, Nullable, Text};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+// Fuzzy lookup for support staff. Items match on their own text (full-text and trigram),
+// their owner's username or their manufacturer name; manufacturers and users are also
+// returned on their own. Users with a private profile are never matched or shown. Scores are pg_trgm similarity / ts_rank in [0, 1], plus 1 for an
+// exact item_id or serial hit, so exact matches always come first.
+
+const DEFAULT_SEARCH_LIMIT: i64 = 20;
+const MAX_SEARCH_LIMIT: i64 = 50;
+const MIN_QUERY_LENGTH: usize = 2;
+
+#[derive(Deserialize, ToSchema)]
+pub struct SearchQuery {
+    #[schema(example = "SN1000")]
+    pub q: String,
+    // maximum hits per section
+    #[schema(example = 20)]
+    pub limit: Option<i64>,
+}
+
+#[derive(QueryableByName, Serialize, ToSchema)]
+pub struct ItemHit {
+    #[diesel(sql_type = Text)]
+    #[schema(example = "item123")]
+    item_id: String,
+    #[diesel(sql_type = Text)]
+    #[schema(example = "Galaxy S24")]
+    name: String,
+    #[diesel(sql_type = Text)]
+    #[schema(example = "SN100042")]
+    serial: String,
+    #[diesel(sql_type = Text)]
+    #[schema(example = "SAMSUNG")]
+    manufacturer: String,
+    #[diesel(sql_type = Text)]
+    #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef12")]
+    owner: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    #[schema(nullable = true, example = "john_doe")]
+    owner_username: Option<String>,
+    #[diesel(sql_type = Float8)]
+    #[schema(example = 0.62)]
+    score: f64,
+}
+
+#[derive(QueryableByName, Serialize, ToSchema)]
+pub struct ManufacturerHit {
+    #[diesel(sql_type = Text)]
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    manufacturer_address: String,
+    #[diesel(sql_type = Text)]
+    #[schema(example = "SAMSUNG")]
+    manufacturer_name: String,
+    #[diesel(sql_type = Float8)]
+    #[schema(example = 0.8)]
+    score: f64,
+}
+
+#[derive(QueryableByName, Serialize, ToSchema)]
+pub struct UserHit {
+    #[diesel(sql_type = Text)]
+    #[schema(example = "0xabcdef1234567890abcdef1234567890abcdef12")]
+    user_address: String,
+    #[diesel(sql_type = Text)]
+    #[schema(example = "john_doe")]
+    username: String,
+    #[diesel(sql_type = Float8)]
+    #[schema(example = 0.5)]
+    score: f64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SearchResponse {
+    #[schema(example = "SN1000")]
+    query: String,
+    items: Vec<ItemHit>,
+    manufacturers: Vec<ManufacturerHit>,
+    users: Vec<UserHit>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/search",
+    params(
+        ("q" = String, Query, description = "Partial or misspelled item name, serial, item ID, manufacturer name or username", example = "SN1000"),
+        ("limit" = Option<i64>, Query, description = "Maximum hits per section (default 20, max 50)", example = 20)
+    ),
+    responses(
+        (status = 200, description = "Ranked matches, best first", body = SearchResponse),
+        (status = 400, description = "Query too short or invalid limit"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Items"
+)]
+pub async fn search(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SearchQuery>,
+) -> impl IntoResponse {
+    match search_internal(&state, &query).await {
+        Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
+        Err(e) => {
+            eprintln!("Error searching for {:?}: {:?}", query.q, e);
+            let (status, message) = match e.to_string().as_str() {
+                s if s.contains("Invalid search") => (StatusCode::BAD_REQUEST, e.to_string()),
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Internal server error: {}", e),
+                ),
+            };
+            (status, AxumJson(json!({"error": message}))).into_response()
+        }
+    }
+}
+
+// `%term%` for ILIKE with the term's own wildcards taken literally
+fn substring_pattern(term: &str) -> String {
+    let escaped = term
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+async fn search_internal(state: &Arc<AppState>, query: &SearchQuery) -> eyre::Result<SearchResponse> {
+    let term = query.q.trim();
+    if term.chars().count() < MIN_QUERY_LENGTH {
+        return Err(eyre::eyre!(
+            "Invalid search: query must be at least {} characters",
+            MIN_QUERY_LENGTH
+        ));
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    if !(1..=MAX_SEARCH_LIMIT).contains(&limit) {
+        return Err(eyre::eyre!(
+            "Invalid search: limit must be between 1 and {}",
+            MAX_SEARCH_LIMIT
+        ));
+    }
+    let pattern = substring_pattern(term);
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let items = diesel::sql_query(
+        "SELECT i.item_id, i.name, i.serial, i.manufacturer, i.owner, \
+                u.username AS owner_username, \
+                (GREATEST( \
+                    similarity(i.name, $1), \
+                    similarity(i.serial, $1), \
+                    similarity(i.item_id, $1), \
+                    similarity(i.manufacturer, $1), \
+                    COALESCE(similarity(u.username, $1), 0), \
+                    ts_rank(to_tsvector('simple', i.name || ' ' || i.serial || ' ' || i.item_id), \
+                            plainto_tsquery('simple', $1)) \
+                 ) \
+                 + CASE WHEN LOWER(i.item_id) = LOWER($1) OR LOWER(i.serial) = LOWER($1) \
+                        THEN 1 ELSE 0 END)::float8 AS score \
+         FROM items i \
+         LEFT JOIN users_info u ON u.user_address = i.owner AND u.profile_visibility = 'public' \
+         WHERE to_tsvector('simple', i.name || ' ' || i.serial || ' ' || i.item_id) \
+                   @@ plainto_tsquery('simple', $1) \
+            OR i.name % $1 OR i.serial % $1 OR i.item_id % $1 OR i.manufacturer % $1 \
+            OR i.name ILIKE $2 OR i.serial ILIKE $2 OR i.item_id ILIKE $2 \
+            OR i.manufacturer ILIKE $2 \
+            OR u.username % $1 OR u.username ILIKE $2 \
+         ORDER BY score DESC, i.item_id \
+         LIMIT $3",
+    )
+    .bind::<Text, _>(term)
+    .bind::<Text, _>(&pattern)
+    .bind::<Int8, _>(limit)
+    .load::<ItemHit>(conn)
+    .map_err(|e| eyre::eyre!("Failed to search items: {}", e))?;
+
+    let manufacturers = diesel::sql_query(
+        "SELECT manufacturer_address, manufacturer_name, \
+                similarity(manufacturer_name, $1)::float8 AS score \
+         FROM manufacturers \
+         WHERE manufacturer_name % $1 OR manufacturer_name ILIKE $2 \
+         ORDER BY score DESC, manufacturer_name \
+         LIMIT $3",
+    )
+    .bind::<Text, _>(term)
+    .bind::<Text, _>(&pattern)
+    .bind::<Int8, _>(limit)
+    .load::<ManufacturerHit>(conn)
+    .map_err(|e| eyre::eyre!("Failed to search manufacturers: {}", e))?;
+
+    let users = diesel::sql_query(
+        "SELECT user_address, username, similarity(username, $1)::float8 AS score \
+         FROM users_info \
+         WHERE profile_visibility = 'public' AND (username % $1 OR username ILIKE $2) \
+         ORDER BY score DESC, username \
+         LIMIT $3",
+    )
+    .bind::<Text, _>(term)
+    .bind::<Text, _>(&pattern)
+    .bind::<Int8, _>(limit)
+    .load::<UserHit>(conn)
+    .map_err(|e| eyre::eyre!("Failed to search users: {}", e))?;
+
+    Ok(SearchResponse {
+        query: term.to_string(),
+        items,
+        manufacturers,
+        users,
+    })
+}
@@ -0,0 +1,555 @@
+use crate::config::app_state::AppState;
+use crate::models::certificate_model::{Certificate, CertificateData, SignedCertificate};
+use crate::models::metadata::{canonical_metadata, Attributes};
+use crate::models::wallet_auth::WalletAuth;
+use crate::analytics::issued_certificate::record_issued_certificates;
+use crate::schema::manufacturers;
+use crate::services::create_item::verify_signed_certificate;
+use crate::products::product_template::link_certificate_product;
+use crate::utility::to_meta_hash;
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use diesel::prelude::*;
+use diesel::PgConnection;
+use ethers::signers::Signer;
+use qrcode::render::svg;
+use qrcode::{EcLevel, QrCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+use std::env;
+use std::io::{Cursor, Write};
+use std::sync::Arc;
+use url::Url;
+use utoipa::ToSchema;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+// metadata entries inside a single CSV cell are separated by this character
+const CSV_METADATA_SEPARATOR: char = '|';
+// what fits a version 40 QR code at error correction level M
+const QR_MAX_BYTES: usize = 2331;
+
+#[derive(Deserialize, Serialize, ToSchema, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchFormat {
+    // header row: name,unique_id,serial,date,metadata[,signature] (metadata entries separated by |)
+    #[default]
+    Csv,
+    // one CertificateData JSON object per line (metadata or attributes, plus signature once
+    // signed); owner is ignored
+    Ndjson,
+}
+
+// A batch the manufacturer signed with its own key (e.g. certificates.ndjson from
+// `bulk-issue`, or a CSV with a signature column); the backend only checks and packages it,
+// it never signs
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct BulkIssuanceRequest {
+    #[serde(default)]
+    pub format: BatchFormat,
+    // the uploaded file as text; an optional product_id column links each row to a product
+    #[schema(example = "name,unique_id,serial,date,metadata,signature\nRedmi Note 14,XM1,SN7890,1727740800,color: black|storage: 256GB,0x4f8e...1b")]
+    pub rows: String,
+    // signed by the manufacturer wallet for action "bulk-issue-certificates"
+    pub auth: WalletAuth,
+}
+
+// One input row; owner always comes from the signing key (or the caller), never from the file
+#[derive(Deserialize, Debug)]
+struct BatchRow {
+    #[serde(default)]
+    name: String,
+    unique_id: String,
+    serial: String,
+    date: u64,
+    #[serde(default)]
+    metadata: Vec<String>,
+    #[serde(default)]
+    attributes: Option<Attributes>,
+    // only read by the upload endpoint; the CLI signs rows itself
+    #[serde(default)]
+    signature: String,
+    // product the row was created from, linked once its signature checks out (upload only)
+    #[serde(default)]
+    product_id: Option<i32>,
+}
+
+#[derive(Deserialize)]
+struct CsvRow {
+    #[serde(default)]
+    name: String,
+    unique_id: String,
+    serial: String,
+    date: u64,
+    #[serde(default)]
+    metadata: String,
+    #[serde(default)]
+    signature: String,
+    #[serde(default)]
+    product_id: Option<i32>,
+}
+
+// Outcome of a single row; error is None when the row was issued
+#[derive(Serialize, ToSchema)]
+pub struct RowReport {
+    // 1-based line (NDJSON) or record (CSV) number in the upload
+    pub row: usize,
+    #[schema(nullable = true)]
+    pub unique_id: Option<String>,
+    #[schema(nullable = true)]
+    pub error: Option<String>,
+}
+
+pub struct IssuedCertificate {
+    pub row: usize,
+    pub certificate: SignedCertificate,
+    pub qr_svg: String,
+}
+
+pub struct BatchOutcome {
+    pub issued: Vec<IssuedCertificate>,
+    pub report: Vec<RowReport>,
+}
+
+impl BatchOutcome {
+    pub fn failed(&self) -> usize {
+        self.report.iter().filter(|r| r.error.is_some()).count()
+    }
+
+    fn push(&mut self, row: usize, result: Result<IssuedCertificate, (Option<String>, String)>) {
+        match result {
+            Ok(issued) => {
+                self.report.push(RowReport {
+                    row,
+                    unique_id: Some(issued.certificate.unique_id.clone()),
+                    error: None,
+                });
+                self.issued.push(issued);
+            }
+            Err((unique_id, error)) => self.report.push(RowReport {
+                row,
+                unique_id,
+                error: Some(error),
+            }),
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/certificates/bulk",
+    request_body = BulkIssuanceRequest,
+    responses(
+        (status = 200, description = "Zip archive with the verified certificates (certificates/*.json), QR codes (qr/*.svg) and a per-row report (report.json)", content_type = "application/zip"),
+        (status = 400, description = "Invalid caller address, unreadable upload or no rows"),
+        (status = 401, description = "Wallet signature missing, expired or not from the caller"),
+        (status = 403, description = "Caller is not a registered manufacturer"),
+        (status = 413, description = "Too many rows in one batch"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Manufacturers"
+)]
+pub async fn bulk_issue_certificates(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<BulkIssuanceRequest>,
+) -> impl IntoResponse {
+    match bulk_issue_internal(&state, &request).await {
+        Ok((archive, outcome)) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "application/zip")
+            .header(
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"certificates.zip\"",
+            )
+            .header("X-Rows-Issued", outcome.issued.len())
+            .header("X-Rows-Failed", outcome.failed())
+            .body(Body::from(archive))
+            .unwrap_or_else(|e| {
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+            }),
+        Err(e) => {
+            eprintln!("Error issuing certificate batch: {:?}", e);
+            let (status, message) = match e.to_string().as_str() {
+                s if s.contains("Invalid caller address") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("Invalid wallet signature") => (StatusCode::UNAUTHORIZED, e.to_string()),
+                s if s.contains("Batch has no rows") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("Failed to read CSV header") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("Batch exceeds") => (StatusCode::PAYLOAD_TOO_LARGE, e.to_string()),
+                s if s.contains("not a registered manufacturer") => {
+                    (StatusCode::FORBIDDEN, e.to_string())
+                }
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Internal server error: {}", e),
+                ),
+            };
+            (status, Json(json!({"error": message}))).into_response()
+        }
+    }
+}
+
+async fn bulk_issue_internal(
+    state: &Arc<AppState>,
+    request: &BulkIssuanceRequest,
+) -> eyre::Result<(Vec<u8>, BatchOutcome)> {
+    let caller = request
+        .auth
+        .verify("bulk-issue-certificates", &request.auth.caller, request)?;
+    let owner = format!("{:?}", caller);
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    let is_manufacturer = manufacturers::table
+        .filter(manufacturers::manufacturer_address.ilike(&owner))
+        .filter(manufacturers::is_registered.eq(true))
+        .select(diesel::dsl::count_star())
+        .first::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(|e| eyre::eyre!("Failed to query manufacturer: {}", e))?;
+
+    if !is_manufacturer {
+        return Err(eyre::eyre!("Caller is not a registered manufacturer"));
+    }
+
+    let rows = parse_rows(request.format, &request.rows)?;
+    check_batch_size(rows.len())?;
+
+    let mut seen = HashSet::new();
+    let mut outcome = BatchOutcome {
+        issued: Vec::new(),
+        report: Vec::new(),
+    };
+
+    for (index, row) in rows.into_iter().enumerate() {
+        let row_number = index + 1;
+        let result = match row {
+            Ok(row) if !seen.insert(row.unique_id.clone()) => {
+                Err((Some(row.unique_id), "Duplicate unique_id in batch".to_string()))
+            }
+            Ok(row) => {
+                let unique_id = row.unique_id.clone();
+                verify_row(state, conn, &owner, row_number, row)
+                    .await
+                    .map_err(|e| (Some(unique_id), e.to_string()))
+            }
+            Err(e) => Err((None, e)),
+        };
+        outcome.push(row_number, result);
+    }
+
+    let archive = build_archive(&outcome)?;
+
+    // only certificates whose manufacturer signature checked out count as issued
+    let certificates = outcome
+        .issued
+        .iter()
+        .map(|issued| {
+            issued
+                .certificate
+                .clone()
+                .try_into()
+                .map_err(|_| eyre::eyre!("Owner address is invalid"))
+        })
+        .collect::<eyre::Result<Vec<Certificate>>>()?;
+    record_issued_certificates(conn, &certificates)?;
+
+    Ok((archive, outcome))
+}
+
+fn check_batch_size(rows: usize) -> eyre::Result<()> {
+    let max_rows = env::var("BULK_ISSUANCE_MAX_ROWS")
+        .ok()
+        .and_then(|max| max.parse::<usize>().ok())
+        .unwrap_or(10_000);
+
+    if rows == 0 {
+        return Err(eyre::eyre!("Batch has no rows"));
+    }
+    if rows > max_rows {
+        return Err(eyre::eyre!("Batch exceeds {} rows", max_rows));
+    }
+    Ok(())
+}
+
+// Parses the upload, then signs and renders every valid row with the manufacturer's key.
+// A bad row is reported and skipped; only an unreadable upload fails the whole batch.
+pub async fn issue_batch<S: Signer>(
+    signer: &S,
+    format: BatchFormat,
+    input: &str,
+) -> eyre::Result<BatchOutcome> {
+    let rows = parse_rows(format, input)?;
+    check_batch_size(rows.len())?;
+
+    let owner = format!("{:?}", signer.address());
+    let mut seen = HashSet::new();
+    let mut outcome = BatchOutcome {
+        issued: Vec::new(),
+        report: Vec::new(),
+    };
+
+    for (index, row) in rows.into_iter().enumerate() {
+        let row_number = index + 1;
+        let result = match row {
+            Ok(row) if !seen.insert(row.unique_id.clone()) => {
+                Err((Some(row.unique_id), "Duplicate unique_id in batch".to_string()))
+            }
+            Ok(row) => {
+                let unique_id = row.unique_id.clone();
+                issue_row(signer, &owner, row_number, row)
+                    .await
+                    .map_err(|e| (Some(unique_id), e.to_string()))
+            }
+            Err(e) => Err((None, e)),
+        };
+        outcome.push(row_number, result);
+    }
+
+    Ok(outcome)
+}
+
+// Same checks as a claim: signature by the caller or one of its signing keys, not revoked
+async fn verify_row(
+    state: &Arc<AppState>,
+    conn: &mut PgConnection,
+    owner: &str,
+    row_number: usize,
+    row: BatchRow,
+) -> eyre::Result<IssuedCertificate> {
+    if row.signature.is_empty() {
+        return Err(eyre::eyre!("Row is not signed"));
+    }
+
+    // metadata is taken as signed; attributes resolve to the same strings the signer hashed
+    let metadata = match &row.attributes {
+        Some(attributes) if row.metadata.is_empty() => canonical_metadata(attributes)?,
+        Some(_) => {
+            return Err(eyre::eyre!(
+                "Invalid metadata: send either metadata or attributes, not both"
+            ))
+        }
+        None => row.metadata,
+    };
+    let signed = SignedCertificate {
+        name: row.name,
+        unique_id: row.unique_id,
+        serial: row.serial,
+        date: row.date,
+        owner: owner.to_string(),
+        metadata,
+        signature: row.signature,
+    };
+
+    let (certificate, _) = verify_signed_certificate(state, conn, &signed).await?;
+    if let Some(product_id) = row.product_id {
+        link_certificate_product(conn, &certificate, product_id)?;
+    }
+    let qr_svg = render_qr(&signed, &certificate)?;
+
+    Ok(IssuedCertificate {
+        row: row_number,
+        certificate: signed,
+        qr_svg,
+    })
+}
+
+fn parse_rows(format: BatchFormat, input: &str) -> eyre::Result<Vec<Result<BatchRow, String>>> {
+    match format {
+        BatchFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new()
+                .trim(csv::Trim::All)
+                .from_reader(input.as_bytes());
+
+            let headers = reader
+                .headers()
+                .map_err(|e| eyre::eyre!("Failed to read CSV header: {}", e))?
+                .clone();
+
+            Ok(reader
+                .records()
+                .map(|record| {
+                    record
+                        .and_then(|record| record.deserialize::<CsvRow>(Some(&headers)))
+                        .map(|row| BatchRow {
+                            name: row.name,
+                            unique_id: row.unique_id,
+                            serial: row.serial,
+                            date: row.date,
+                            metadata: row
+                                .metadata
+                                .split(CSV_METADATA_SEPARATOR)
+                                .map(|entry| entry.trim().to_string())
+                                .filter(|entry| !entry.is_empty())
+                                .collect(),
+                            attributes: None,
+                            signature: row.signature,
+                            product_id: row.product_id,
+                        })
+                        .map_err(|e| format!("Invalid row: {}", e))
+                })
+                .collect())
+        }
+        BatchFormat::Ndjson => Ok(input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str::<BatchRow>(line).map_err(|e| format!("Invalid row: {}", e))
+            })
+            .collect()),
+    }
+}
+
+async fn issue_row<S: Signer>(
+    signer: &S,
+    owner: &str,
+    row_number: usize,
+    row: BatchRow,
+) -> eyre::Result<IssuedCertificate> {
+    let mut data = CertificateData {
+        name: row.name,
+        unique_id: row.unique_id,
+        serial: row.serial,
+        date: row.date,
+        owner: owner.to_string(),
+        metadata: row.metadata,
+        attributes: row.attributes,
+        product_id: None,
+    };
+    data.resolve_metadata()
+        .map_err(|e| eyre::eyre!("{}", e))?;
+
+    if data.name.is_empty() || data.unique_id.is_empty() || data.serial.is_empty() {
+        return Err(eyre::eyre!("name, unique_id and serial cannot be empty"));
+    }
+    if data.date == 0 {
+        return Err(eyre::eyre!("date cannot be zero"));
+    }
+
+    let certificate: Certificate = data
+        .clone()
+        .try_into()
+        .map_err(|e| eyre::eyre!("Invalid certificate: {}", e))?;
+
+    let signature = signer
+        .sign_typed_data(&certificate)
+        .await
+        .map_err(|e| eyre::eyre!("Failed to sign certificate: {}", e))?;
+
+    let signed = SignedCertificate {
+        name: data.name,
+        unique_id: data.unique_id,
+        serial: data.serial,
+        date: data.date,
+        owner: data.owner,
+        metadata: data.metadata,
+        signature: format!("0x{}", signature),
+    };
+
+    let qr_svg = render_qr(&signed, &certificate)?;
+
+    Ok(IssuedCertificate {
+        row: row_number,
+        certificate: signed,
+        qr_svg,
+    })
+}
+
+// QR codes point at the verification page with the certificate and signature in the query,
+// the same shape the frontend reads back
+fn render_qr(signed: &SignedCertificate, certificate: &Certificate) -> eyre::Result<String> {
+    let verify_url = env::var("VERIFY_URL")
+        .unwrap_or_else(|_| "https://eri-eth-ui.vercel.app/verify".to_string());
+
+    let cert = json!({
+        "name": signed.name,
+        "uniqueId": signed.unique_id,
+        "serial": signed.serial,
+        "date": signed.date,
+        "owner": signed.owner,
+        "metadataHash": format!("0x{}", hex::encode(to_meta_hash(&certificate.metadata))),
+        "metadata": signed.metadata,
+    });
+
+    let url = Url::parse_with_params(
+        &verify_url,
+        &[("cert", cert.to_string()), ("sig", signed.signature.clone())],
+    )
+    .map_err(|e| eyre::eyre!("Invalid VERIFY_URL: {}", e))?;
+
+    if url.as_str().len() > QR_MAX_BYTES {
+        return Err(eyre::eyre!("Certificate data too large for QR code"));
+    }
+
+    let qr_code = QrCode::with_error_correction_level(url.as_str().as_bytes(), EcLevel::M)
+        .map_err(|e| eyre::eyre!("Failed to render QR code: {}", e))?;
+
+    Ok(qr_code
+        .render::<svg::Color>()
+        .min_dimensions(200, 200)
+        .build())
+}
+
+// certificates/<unique_id>.json, qr/<unique_id>.svg, certificates.ndjson and report.json
+pub fn build_archive(outcome: &BatchOutcome) -> eyre::Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+    let mut ndjson = String::new();
+    let mut file_names = HashSet::new();
+
+    for issued in &outcome.issued {
+        let payload = serde_json::to_string_pretty(&issued.certificate)?;
+        let mut file_name = sanitize_file_name(&issued.certificate.unique_id);
+        if !file_names.insert(file_name.clone()) {
+            file_name = format!("{}-row{}", file_name, issued.row);
+        }
+
+        zip.start_file(format!("certificates/{}.json", file_name), options)?;
+        zip.write_all(payload.as_bytes())?;
+
+        zip.start_file(format!("qr/{}.svg", file_name), options)?;
+        zip.write_all(issued.qr_svg.as_bytes())?;
+
+        ndjson.push_str(&serde_json::to_string(&issued.certificate)?);
+        ndjson.push('\n');
+    }
+
+    zip.start_file("certificates.ndjson", options)?;
+    zip.write_all(ndjson.as_bytes())?;
+
+    zip.start_file("report.json", options)?;
+    zip.write_all(
+        serde_json::to_string_pretty(&json!({
+            "issued": outcome.issued.len(),
+            "failed": outcome.failed(),
+            "rows": outcome.report,
+        }))?
+        .as_bytes(),
+    )?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+// unique ids are manufacturer-chosen, keep them from escaping the archive folders
+fn sanitize_file_name(unique_id: &str) -> String {
+    unique_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim_start_matches('.')
+        .to_string()
+}
@@ -0,0 +1,221 @@
+use crate::analytics::issued_certificate::record_issued_certificates;
+use crate::config::app_state::AppState;
+use crate::contract_models::Item;
+use crate::models::certificate_model::SignedCertificate;
+use crate::ownership::onchain_ownership_code::{prepare_transaction, PreparedTransaction};
+use crate::schema::{items, users_info};
+use crate::services::create_item::verify_signed_certificate;
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json as AxumJson,
+};
+use diesel::prelude::*;
+use diesel::PgConnection;
+use ethers::types::{Address, Bytes};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+// Authenticity.userClaimOwnership mints the item to msg.sender, so the claimant's own
+// wallet has to send it. The certificate is checked here first so a doomed claim never
+// costs the claimant gas; the item row follows once ItemCreated is indexed, and
+// GET /api/items/claim/{item_id} returns it from then on.
+
+// Define the input struct for the endpoint
+#[derive(Deserialize, ToSchema)]
+pub struct ClaimItemRequest {
+    // wallet the item is minted to; it must be a registered user
+    #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
+    pub caller: String,
+    // certificate exactly as the manufacturer signed it
+    pub certificate: SignedCertificate,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ClaimItemResponse {
+    // poll GET /api/items/claim/{item_id} with it after sending `transaction`
+    #[schema(example = "item123")]
+    item_id: String,
+    transaction: PreparedTransaction,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PendingClaim {
+    #[schema(example = "item123")]
+    item_id: String,
+    #[schema(example = "pending")]
+    status: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/items/claim",
+    request_body(content = ClaimItemRequest, example = json!({
+        "caller": "0x1234567890abcdef1234567890abcdef12345678",
+        "certificate": {
+            "name": "Widget",
+            "unique_id": "item123",
+            "serial": "SN123456",
+            "date": 1693526400,
+            "owner": "0xF2E7E2f51D7C9eEa9B0313C2eCa12f8e43bd1855",
+            "metadata": ["color: blue", "size: medium"],
+            "signature": "0x4f6b...1b"
+        }
+    })),
+    responses(
+        (status = 200, description = "The claimant must send `transaction` (`userClaimOwnership`) from their wallet, then poll the claim status", body = ClaimItemResponse),
+        (status = 400, description = "Invalid caller, certificate or signature"),
+        (status = 403, description = "Certificate not signed by a registered manufacturer, or caller is not a registered user"),
+        (status = 409, description = "Item was already claimed"),
+        (status = 410, description = "Certificate was revoked by its manufacturer"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Items"
+)]
+pub async fn claim_item(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ClaimItemRequest>,
+) -> impl IntoResponse {
+    match claim_item_internal(&state, &request).await {
+        Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
+        Err(e) => {
+            eprintln!(
+                "Error claiming item with unique_id {}: {:?}",
+                request.certificate.unique_id, e
+            );
+            let (status, message) = match e.to_string().as_str() {
+                s if s.contains("Invalid caller address") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("Invalid certificate") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("Invalid signature") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("Owner address is invalid") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("Certificate was not signed by its owner") => {
+                    (StatusCode::BAD_REQUEST, e.to_string())
+                }
+                s if s.contains("Certificate owner is not a registered manufacturer") => {
+                    (StatusCode::FORBIDDEN, e.to_string())
+                }
+                s if s.contains("Caller is not a registered user") => {
+                    (StatusCode::FORBIDDEN, e.to_string())
+                }
+                s if s.contains("Certificate has been revoked") => (StatusCode::GONE, e.to_string()),
+                s if s.contains("Item already claimed") => (StatusCode::CONFLICT, e.to_string()),
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Internal server error: {}", e),
+                ),
+            };
+            (status, AxumJson(json!({"error": message}))).into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/items/claim/{item_id}",
+    params(
+        ("item_id" = String, Path, description = "unique_id of the claimed certificate", example = "item123")
+    ),
+    responses(
+        (status = 200, description = "ItemCreated was indexed; the claimed item", body = Item),
+        (status = 202, description = "Not indexed yet: the claim transaction is pending, failed or was never sent", body = PendingClaim),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Items"
+)]
+pub async fn get_claim_status(
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<String>,
+) -> impl IntoResponse {
+    match get_claim_status_internal(&state, &item_id) {
+        Ok(Some(item)) => (StatusCode::OK, AxumJson(item)).into_response(),
+        Ok(None) => (
+            StatusCode::ACCEPTED,
+            AxumJson(PendingClaim {
+                item_id,
+                status: "pending".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("Error fetching claim status of item {}: {:?}", item_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                AxumJson(json!({"error": format!("Internal server error: {}", e)})),
+            )
+                .into_response()
+        }
+    }
+}
+
+fn get_claim_status_internal(state: &Arc<AppState>, item_id: &str) -> eyre::Result<Option<Item>> {
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
+    find_item(conn, item_id)
+}
+
+async fn claim_item_internal(
+    state: &Arc<AppState>,
+    request: &ClaimItemRequest,
+) -> eyre::Result<ClaimItemResponse> {
+    let caller: Address = request
+        .caller
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid caller address"))?;
+
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+    let (certificate, _) = verify_signed_certificate(state, conn, &request.certificate).await?;
+
+    if find_item(conn, &certificate.unique_id)?.is_some() {
+        return Err(eyre::eyre!("Item already claimed"));
+    }
+    // Ownership.createItem reverts NOT_REGISTERED for unknown wallets
+    if !is_registered(conn, &format!("{:?}", caller))? {
+        return Err(eyre::eyre!("Caller is not a registered user"));
+    }
+    // the manufacturer's signature checked out, so the certificate counts as issued
+    record_issued_certificates(conn, std::slice::from_ref(&certificate))?;
+
+    let signature = hex::decode(request.certificate.signature.trim_start_matches("0x"))
+        .map_err(|_| eyre::eyre!("Invalid signature format"))?;
+
+    // Authenticity verifies the manufacturer's signature again and calls Ownership.createItem
+    let call = state
+        .authenticity_contract
+        .user_claim_ownership(certificate.into(), Bytes::from(signature));
+
+    Ok(ClaimItemResponse {
+        item_id: request.certificate.unique_id.clone(),
+        transaction: prepare_transaction(
+            &request.caller,
+            state.authenticity_contract.address(),
+            call.calldata(),
+        )?,
+    })
+}
+
+fn find_item(conn: &mut PgConnection, item_id: &str) -> eyre::Result<Option<Item>> {
+    items::table
+        .filter(items::item_id.eq(item_id))
+        .select(Item::as_select())
+        .first(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query database: {}", e))
+}
+
+fn is_registered(conn: &mut PgConnection, user_address: &str) -> eyre::Result<bool> {
+    users_info::table
+        .filter(users_info::user_address.ilike(user_address))
+        .select(diesel::dsl::count_star())
+        .first::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(|e| eyre::eyre!("Failed to query user: {}", e))
+}
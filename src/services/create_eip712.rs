@@ -1,13 +1,16 @@
 use crate::models::certificate_model::{
     Certificate, CertificateData, CustomEIP712Domain, Eip712Object,
 };
+use crate::config::app_state::AppState;
+use crate::products::product_template::{apply_template, load_product};
 use crate::utility::to_meta_hash;
-use axum::{Json, http::StatusCode};
+use axum::{Json, extract::State, http::StatusCode};
 use ethers::types::transaction::eip712::Eip712;
 use ethers::utils::hex::ToHexExt;
 use ethers::utils::keccak256;
 use ethers::{contract::EthEvent, prelude::*, signers::Signer};
 use std::error::Error;
+use std::sync::Arc;
 
 #[utoipa::path(
     post,
@@ -15,13 +18,44 @@ use std::error::Error;
     request_body = CertificateData,
     responses(
         (status = 200, description = "EIP-712 object created successfully", body = Eip712Object),
-        (status = 400, description = "Invalid input"),
+        (status = 400, description = "Invalid input, or metadata does not match the product's attribute schema"),
+        (status = 403, description = "Product does not belong to the certificate owner"),
+        (status = 404, description = "Product not found"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn create_certificate(
-    Json(cert): Json<CertificateData>,
+    State(state): State<Arc<AppState>>,
+    Json(mut cert): Json<CertificateData>,
 ) -> Result<Json<Eip712Object>, StatusCode> {
+    // Typed attributes become canonical metadata before anything looks at them
+    cert.resolve_metadata().map_err(|e| {
+        eprintln!("Invalid certificate metadata: {:?}", e);
+        StatusCode::BAD_REQUEST
+    })?;
+
+    // Pull defaults and the attribute schema from the product template
+    if let Some(product_id) = cert.product_id {
+        let conn = &mut state.db_pool.get().map_err(|e| {
+            eprintln!("Failed to get DB connection: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+        let product = load_product(conn, product_id, &cert.owner).map_err(|e| {
+            eprintln!("Product lookup error: {:?}", e);
+            match e.to_string().as_str() {
+                "Product not found" => StatusCode::NOT_FOUND,
+                "Product does not belong to the certificate owner" => StatusCode::FORBIDDEN,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }
+        })?;
+
+        apply_template(&product, &mut cert).map_err(|e| {
+            eprintln!("Certificate does not match product {}: {:?}", product_id, e);
+            StatusCode::BAD_REQUEST
+        })?;
+    }
+
     // Validate inputs
     if cert.name.is_empty() || cert.unique_id.is_empty() || cert.serial.is_empty() {
         eprintln!("Empty name, unique_id, or serial");
@@ -32,8 +66,6 @@ pub async fn create_certificate(
         return Err(StatusCode::BAD_REQUEST);
     }
 
-    println!("owner: {:?}", cert.owner);
-
     // Convert to Certificate
     let certificate: Certificate = cert.try_into().map_err(|e| {
         eprintln!("Certificate conversion error: {:?}", e);
@@ -76,6 +108,7 @@ pub async fn create_certificate(
         domain: custom_domain,
         types,
         value,
+        metadata: certificate.metadata.clone(),
     };
 
     eprintln!("EIP-712 object created: {:?}", eip712_object);
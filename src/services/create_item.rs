@@ -1,42 +1,43 @@
-use crate::ownership::ownership_abi::Ownership;
+use crate::analytics::issued_certificate::record_issued_certificates;
+use crate::authenticity::certificate_revocation::{certificate_hash, ensure_not_revoked};
+use crate::authenticity::signing_keys::{authorized_key, chain_issued_at};
+use crate::billing::gas_sponsorship::{reserve_gas, settle_gas, GasOperation, Sponsor};
 use crate::config::app_state::AppState;
-use crate::ownership::ownership_abi;
+use crate::models::certificate_model::{Certificate, SignedCertificate};
+use crate::products::product_template::link_certificate_product;
+use crate::schema::manufacturers;
 use axum::{
     Json as AxumJson,
     extract::{Json, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
 };
 use ethers::{
     prelude::*,
     types::{Address, U256},
 };
+use diesel::prelude::*;
+use diesel::PgConnection;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::Arc;
 use utoipa::ToSchema;
+use validator::Validate;
 
 
 
 // Define the input struct for the endpoint
 #[derive(Deserialize, ToSchema)]
 pub struct CreateItemRequest {
+    // address the item is minted to
     #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
     pub caller: String,
-    #[schema(example = "Widget")]
-    pub name: String,
-    #[schema(example = "item123")]
-    pub unique_id: String,
-    // #[schema(example = "SN123456")]
-    // pub serial: String,
-    // #[schema(example = 1693526400)]
-    // pub date: u64,
-    // #[schema(example = "0x1234567890abcdef1234567890abcdef12345678")]
-    // pub owner: String,
-    #[schema(example = json!(["color: blue", "size: medium"]))]
-    pub metadata: Vec<String>,
-    #[schema(example = "Acme Corp")]
-    pub manufacturer_name: String,
+    // certificate exactly as the manufacturer signed it
+    pub certificate: SignedCertificate,
+    // product the certificate was created from; must belong to the certificate owner
+    #[serde(default)]
+    #[schema(nullable = true, example = 1)]
+    pub product_id: Option<i32>,
 }
 
 // Define the response struct for successful transaction
@@ -54,27 +55,45 @@ struct ErrorResponse {
 #[utoipa::path(
     post,
     path = "/api/item/create",
-    request_body = CreateItemRequest,
+    request_body(content = CreateItemRequest, example = json!({
+        "caller": "0x1234567890abcdef1234567890abcdef12345678",
+        "certificate": {
+            "name": "Widget",
+            "unique_id": "item123",
+            "serial": "SN123456",
+            "date": 1693526400,
+            "owner": "0xF2E7E2f51D7C9eEa9B0313C2eCa12f8e43bd1855",
+            "metadata": ["color: blue", "size: medium"],
+            "signature": "0x4f6b...1b"
+        },
+        "product_id": 1
+    })),
     responses(
         (status = 200, description = "Item created successfully", body = CreateItemResponse, example = json!({
             "transaction_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
         })),
-        (status = 400, description = "Invalid input (e.g., empty fields or invalid addresses)", body = ErrorResponse, example = json!({"error": "Caller address is invalid"})),
-        (status = 403, description = "Unauthorized (e.g., caller not allowed to create item)", body = ErrorResponse, example = json!({"error": "Caller not authorized to create item"})),
+        (status = 400, description = "Invalid input (e.g., empty fields, invalid addresses, a bad signature or metadata that does not match the product)", body = ErrorResponse, example = json!({"error": "Caller address is invalid"})),
+        (status = 401, description = "X-API-Key was sent but is not a registered, unrevoked key", body = ErrorResponse, example = json!({"error": "Invalid API key"})),
+        (status = 402, description = "Gas budget of the manufacturer (or of the calling API key) is used up for this month", body = ErrorResponse, example = json!({"error": "Gas budget exhausted"})),
+        (status = 403, description = "Unauthorized (e.g., certificate not signed by a registered manufacturer, or product of another manufacturer)", body = ErrorResponse, example = json!({"error": "Certificate owner is not a registered manufacturer"})),
+        (status = 404, description = "Product not found", body = ErrorResponse, example = json!({"error": "Product not found"})),
+        (status = 409, description = "Item was already claimed", body = ErrorResponse, example = json!({"error": "Item already claimed"})),
+        (status = 410, description = "Certificate was revoked by its manufacturer", body = ErrorResponse, example = json!({"error": "Certificate has been revoked"})),
         (status = 500, description = "Internal server error (e.g., contract interaction failed)", body = ErrorResponse, example = json!({"error": "Internal server error: Failed to send transaction"}))
     ),
     tag = "Items"
 )]
 pub async fn create_item(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(request): Json<CreateItemRequest>,
 ) -> impl IntoResponse {
-    match create_item_internal(&state, &request).await {
+    match create_item_internal(&state, &headers, &request).await {
         Ok(response) => (StatusCode::OK, AxumJson(response)).into_response(),
         Err(e) => {
             eprintln!(
                 "Error creating item with unique_id {}: {:?}",
-                request.unique_id, e
+                request.certificate.unique_id, e
             );
             let (status, message) = match e.to_string().as_str() {
                 s if s.contains("Caller address is invalid") => {
@@ -83,13 +102,30 @@ pub async fn create_item(
                 s if s.contains("Owner address is invalid") => {
                     (StatusCode::BAD_REQUEST, e.to_string())
                 }
-                s if s.contains("Metadata hash is invalid") => {
+                s if s.contains("Invalid certificate") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("Invalid signature") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("Certificate was not signed by its owner") => {
                     (StatusCode::BAD_REQUEST, e.to_string())
                 }
-                s if s.contains("Field cannot be empty") => {
-                    (StatusCode::BAD_REQUEST, e.to_string())
+                s if s.contains("cannot be empty") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("Invalid metadata") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("Product not found") => (StatusCode::NOT_FOUND, e.to_string()),
+                s if s.contains("Product does not belong") => (StatusCode::FORBIDDEN, e.to_string()),
+                s if s.contains("Certificate owner is not a registered manufacturer") => {
+                    (StatusCode::FORBIDDEN, e.to_string())
+                }
+                s if s.contains("Certificate has been revoked") => (StatusCode::GONE, e.to_string()),
+                s if s.contains("Invalid API key") => (StatusCode::UNAUTHORIZED, e.to_string()),
+                s if s.contains("Gas budget exhausted") => {
+                    (StatusCode::PAYMENT_REQUIRED, e.to_string())
+                }
+                s if s.contains("ITEM_CLAIMED_ALREADY") => {
+                    (StatusCode::CONFLICT, "Item already claimed".to_string())
                 }
-                s if s.contains("Date cannot be zero") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("NOT_REGISTERED") => (
+                    StatusCode::BAD_REQUEST,
+                    "Caller is not registered".to_string(),
+                ),
                 s if s.contains("ADDRESS_ZERO") => (
                     StatusCode::BAD_REQUEST,
                     "Caller or owner address cannot be zero".to_string(),
@@ -114,84 +150,37 @@ pub async fn create_item(
 
 async fn create_item_internal(
     state: &Arc<AppState>,
+    headers: &HeaderMap,
     request: &CreateItemRequest,
 ) -> eyre::Result<CreateItemResponse> {
     // Validate inputs
     if request.caller.is_empty() {
         return Err(eyre::eyre!("Caller address cannot be empty"));
     }
-    if request.name.is_empty() {
-        return Err(eyre::eyre!("Certificate name cannot be empty"));
-    }
-    if request.unique_id.is_empty() {
-        return Err(eyre::eyre!("Certificate unique ID cannot be empty"));
-    }
-    // if request.serial.is_empty() {
-    //     return Err(eyre::eyre!("Certificate serial cannot be empty"));
-    // }
-    // if request.date == 0 {
-    //     return Err(eyre::eyre!("Certificate date cannot be zero"));
-    // }
-    // if request.owner.is_empty() {
-    //     return Err(eyre::eyre!("Certificate owner address cannot be empty"));
-    // }
-    // if request.metadata_hash.is_empty() {
-    //     return Err(eyre::eyre!("Certificate metadata hash cannot be empty"));
-    // }
-    if request.manufacturer_name.is_empty() {
-        return Err(eyre::eyre!("Manufacturer name cannot be empty"));
-    }
-
-    // Parse addresses and metadata hash
+    // Parse addresses
     let caller: Address = request
         .caller
         .parse()
         .map_err(|_| eyre::eyre!("Caller address is invalid"))?;
-    let owner: Address = "0xF2E7E2f51D7C9eEa9B0313C2eCa12f8e43bd1855"
-        .parse()
-        .map_err(|_| eyre::eyre!("Owner address is invalid"))?;
-    let metadata_hash: [u8; 32] =
-        hex::decode("0x5eaad066aaff0c6b04b35e501adc0a632a28e458c08f02309b866ead0e19f48f".trim_start_matches("0x"))
-            .map_err(|_| eyre::eyre!("Metadata hash is invalid"))?
-            .try_into()
-            .map_err(|_| eyre::eyre!("Metadata hash must be 32 bytes"))?;
 
-    // Get the contract and wallet details
-    let contract = &state.ownership_contract;
-    let wallet_address = contract.client().address();
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+    let (certificate, manufacturer_name) =
+        verify_signed_certificate(state, conn, &request.certificate).await?;
+    // only a certificate the manufacturer signed may be linked to one of its products
+    if let Some(product_id) = request.product_id {
+        link_certificate_product(conn, &certificate, product_id)?;
+    }
 
-    // Check wallet balance
-    let balance = contract
-        .client()
-        .get_balance(wallet_address, None)
-        .await
-        .map_err(|e| eyre::eyre!("Failed to check wallet balance: {}", e))?;
-
-    // Create the certificate struct for the contract call
-    let certificate = ownership_abi::Certificate {
-        name: request.name.clone(),
-        unique_id: request.unique_id.clone(),
-        serial: "12345".to_string(),
-        date: U256::from(123456789),
-        owner,
-        metadata_hash,
-        metadata: request.metadata.clone(),
-    };
+    // The backend wallet pays the gas; it is charged to the manufacturer minting the item
+    let sponsor = Sponsor::for_request(conn, headers, Sponsor::manufacturer(certificate.owner))?;
+    // the manufacturer's signature checked out, so the certificate counts as issued
+    record_issued_certificates(conn, std::slice::from_ref(&certificate))?;
 
-    // Estimate gas with a 20% buffer
-    // let gas_estimate = contract
-    //     .create_item(
-    //         caller,
-    //         certificate.clone(),
-    //         request.manufacturer_name.clone(),
-    //     )
-    //     .estimate_gas()
-    //     .await
-    //     .map_err(|e| {
-    //         let revert_reason = e.decode_revert().unwrap_or_else(|| e.to_string());
-    //         eyre::eyre!("Gas estimation failed: {}", revert_reason)
-    //     })?;
-    // let gas_limit = gas_estimate * 120 / 100;
+    let contract = &state.ownership_contract;
+    let wallet_address = contract.client().address();
 
     // Get current gas price with a fallback
     let gas_price = contract
@@ -200,35 +189,93 @@ async fn create_item_internal(
         .await
         .unwrap_or(U256::from(2_000_000_000u64));
 
-    // Check if sufficient funds are available
-    // let required_funds = gas_limit * gas_price;
-    // if balance < required_funds {
-    //     return Err(eyre::eyre!(
-    //         "Insufficient funds: have {} wei, need {} wei",
-    //         balance,
-    //         required_funds
-    //     ));
-    // }
-
-    // Prepare and send the transaction
-    let call = contract
-        .create_item(caller, certificate, request.manufacturer_name.clone())
-        // .gas(gas_limit)
-        .gas_price(gas_price);
-
-    let pending_tx = call.send().await.map_err(|e| {
+    // userClaimOwnership mints to msg.sender, so it only fits when the backend wallet is
+    // claiming for itself; any other caller is minted to through Ownership.createItem
+    let call = if caller == wallet_address {
+        let signature = hex::decode(request.certificate.signature.trim_start_matches("0x"))
+            .map_err(|_| eyre::eyre!("Invalid signature format"))?;
+
+        state
+            .authenticity_contract
+            .user_claim_ownership(certificate.into(), Bytes::from(signature))
+    } else {
+        contract.create_item(caller, certificate.into(), manufacturer_name)
+    }
+    .gas_price(gas_price);
+
+    // The estimate is what gets reserved against the sponsor's budget until the receipt is in
+    let gas_estimate = call.estimate_gas().await.map_err(|e| {
         // Attempt to parse revert reason
         let revert_reason = e.decode_revert().unwrap_or_else(|| e.to_string());
         eyre::eyre!("Failed to send transaction: {}", revert_reason)
     })?;
+    let reservation = reserve_gas(conn, &sponsor, gas_estimate * gas_price)?;
 
-    // Await transaction confirmation
-    let receipt = pending_tx
-        .await
-        .map_err(|e| eyre::eyre!("Failed to confirm transaction: {}", e))?
-        .ok_or_else(|| eyre::eyre!("Transaction receipt not found"))?;
+    let sent = async {
+        call.send()
+            .await
+            .map_err(|e| {
+                // Attempt to parse revert reason
+                let revert_reason = e.decode_revert().unwrap_or_else(|| e.to_string());
+                eyre::eyre!("Failed to send transaction: {}", revert_reason)
+            })?
+            // Await transaction confirmation
+            .await
+            .map_err(|e| eyre::eyre!("Failed to confirm transaction: {}", e))?
+            .ok_or_else(|| eyre::eyre!("Transaction receipt not found"))
+    }
+    .await;
+    let receipt = settle_gas(conn, reservation, GasOperation::CreateItem, sent, gas_price)?;
 
     Ok(CreateItemResponse {
         transaction_hash: format!("0x{}", hex::encode(receipt.transaction_hash)),
     })
 }
+
+// Validates a signed certificate and checks it was signed by a registered manufacturer.
+// Returns the contract-ready certificate and the manufacturer's name. Takes the caller's
+// connection so a batch holds one pooled connection, not one per row.
+pub(crate) async fn verify_signed_certificate(
+    state: &Arc<AppState>,
+    conn: &mut PgConnection,
+    signed: &SignedCertificate,
+) -> eyre::Result<(Certificate, String)> {
+    signed
+        .validate()
+        .map_err(|e| eyre::eyre!("Invalid certificate: {}", e))?;
+
+    // metadata_hash is computed here (to_meta_hash), never taken from the client
+    let certificate: Certificate = signed
+        .clone()
+        .try_into()
+        .map_err(|_| eyre::eyre!("Owner address is invalid"))?;
+
+    // Same check the Authenticity contract makes, done up front to avoid a reverted transaction
+    let signer = certificate
+        .recover_signer(&signed.signature)
+        .map_err(|e| eyre::eyre!("{}", e))?;
+    let issued_at = if signer != certificate.owner {
+        Some(chain_issued_at(state, &certificate_hash(&certificate)?).await?)
+    } else {
+        None
+    };
+
+    if let Some(issued_at) = issued_at {
+        let owner = format!("{:?}", certificate.owner);
+        if authorized_key(conn, &owner, &format!("{:?}", signer), issued_at)?.is_none() {
+            return Err(eyre::eyre!("Certificate was not signed by its owner"));
+        }
+    }
+    ensure_not_revoked(conn, &certificate)?;
+
+    let manufacturer_name = manufacturers::table
+        .filter(manufacturers::manufacturer_address.ilike(format!("{:?}", certificate.owner)))
+        .filter(manufacturers::is_registered.eq(true))
+        .select(manufacturers::manufacturer_name)
+        .first::<String>(conn)
+        .optional()
+        .map_err(|e| eyre::eyre!("Failed to query manufacturer: {}", e))?
+        .ok_or_else(|| eyre::eyre!("Certificate owner is not a registered manufacturer"))?;
+
+    Ok((certificate, manufacturer_name))
+}
@@ -5,4 +5,6 @@ pub(crate) mod qr_code;
 pub mod register_user;
 pub mod set_autheticity;
 pub mod claim_ownership;
-pub mod create_item;
\ No newline at end of file
+pub mod create_item;
+pub mod claim_item;
+pub mod bulk_issuance;
\ No newline at end of file
@@ -1,7 +1,7 @@
 
 use axum::{
     extract::{ State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
     Json,
 };
@@ -15,7 +15,9 @@ use serde_json::json;
 use std::sync::Arc;
 use utoipa::ToSchema;
 use crate::ownership::ownership_abi::Ownership;
+use crate::billing::gas_sponsorship::{reserve_gas, settle_gas, GasOperation, Sponsor};
 use crate::config::app_state::AppState;
+use crate::models::username_policy::{check_name, NameKind};
 
 // Define the input struct for the endpoint
 #[derive(Deserialize, ToSchema)]
@@ -44,16 +46,20 @@ struct ErrorResponse {
         (status = 200, description = "User registered successfully", body = UserRegisterResponse, example = json!({
             "transaction_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
         })),
-        (status = 400, description = "Invalid input (e.g., invalid username)", body = ErrorResponse, example = json!({"error": "Username cannot be empty"})),
+        (status = 400, description = "Username breaks the naming policy (format, reserved or offensive words, look-alike of a manufacturer)", body = ErrorResponse, example = json!({"error": "Invalid username: must be 3 to 32 characters"})),
+        (status = 401, description = "X-API-Key was sent but is not a registered, unrevoked key", body = ErrorResponse, example = json!({"error": "Invalid API key"})),
+        (status = 402, description = "Gas budget of the platform (or of the calling API key) is used up for this month", body = ErrorResponse, example = json!({"error": "Gas budget exhausted"})),
+        (status = 409, description = "Username is used by another user or manufacturer, ignoring case", body = ErrorResponse, example = json!({"error": "Username is already taken"})),
         (status = 500, description = "Internal server error (e.g., contract interaction failed)", body = ErrorResponse, example = json!({"error": "Internal server error: Failed to send transaction"}))
     ),
     tag = "Users"
 )]
 pub async fn user_register(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(request): Json<UserRegisterRequest>,
 ) -> impl IntoResponse {
-    match register_user_internal(&state, &request).await {
+    match register_user_internal(&state, &headers, &request).await {
         Ok(response) => (
             StatusCode::OK,
             Json(response),
@@ -61,8 +67,10 @@ pub async fn user_register(
         Err(e) => {
             eprintln!("Error registering user with username {}: {:?}", request.username, e);
             let (status, message) = match e.to_string().as_str() {
-                s if s.contains("Username cannot be empty") => (StatusCode::BAD_REQUEST, e.to_string()),
-                s if s.contains("Username too long") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.starts_with("Invalid username") => (StatusCode::BAD_REQUEST, e.to_string()),
+                "Username is already taken" => (StatusCode::CONFLICT, e.to_string()),
+                "Invalid API key" => (StatusCode::UNAUTHORIZED, e.to_string()),
+                "Gas budget exhausted" => (StatusCode::PAYMENT_REQUIRED, e.to_string()),
                 s if s.contains("ADDRESS_ZERO") => (StatusCode::BAD_REQUEST, "Caller address cannot be zero".to_string()),
                 s if s.contains("AUTHENTICITY_NOT_SET") => (StatusCode::INTERNAL_SERVER_ERROR, "Authenticity contract not set".to_string()),
                 _ => (
@@ -77,15 +85,18 @@ pub async fn user_register(
 
 async fn register_user_internal(
     state: &Arc<AppState>,
+    headers: &HeaderMap,
     request: &UserRegisterRequest,
 ) -> eyre::Result<UserRegisterResponse> {
-    // Validate username
-    if request.username.is_empty() {
-        return Err(eyre::eyre!("Username cannot be empty"));
-    }
-    if request.username.len() > 32 {
-        return Err(eyre::eyre!("Username too long (max 32 characters)"));
-    }
+    // Validate username against the shared policy (format, reserved words, uniqueness)
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+    check_name(conn, &request.username, NameKind::User, None)?;
+
+    // The name is registered to the backend wallet, so the platform pays unless an API key called
+    let sponsor = Sponsor::for_request(conn, headers, Sponsor::platform())?;
 
     // Get the contract and wallet details
     let contract = &state.ownership_contract;
@@ -122,25 +133,29 @@ async fn register_user_internal(
         ));
     }
 
+    // the buffered fee is held against the sponsor's budget until the receipt is in
+    let reservation = reserve_gas(conn, &sponsor, required_funds)?;
+
     // Prepare and send the transaction
     let call = contract
         .user_registers(request.username.clone())
         .gas(gas_limit)
         .gas_price(gas_price);
 
-    let pending_tx = call
-        .send()
-        .await
-        .map_err(|e| {
-            // Attempt to parse revert reason
-            eyre::eyre!("Failed to send transaction: {}", e.to_string())
-        })?;
-
-    // Await transaction confirmation
-    let receipt = pending_tx
-        .await
-        .map_err(|e| eyre::eyre!("Failed to confirm transaction: {}", e))?
-        .ok_or_else(|| eyre::eyre!("Transaction receipt not found"))?;
+    let sent = async {
+        call.send()
+            .await
+            .map_err(|e| {
+                // Attempt to parse revert reason
+                eyre::eyre!("Failed to send transaction: {}", e.to_string())
+            })?
+            // Await transaction confirmation
+            .await
+            .map_err(|e| eyre::eyre!("Failed to confirm transaction: {}", e))?
+            .ok_or_else(|| eyre::eyre!("Transaction receipt not found"))
+    }
+    .await;
+    let receipt = settle_gas(conn, reservation, GasOperation::RegisterUser, sent, gas_price)?;
 
     Ok(UserRegisterResponse {
         transaction_hash: format!("0x{}", hex::encode(receipt.transaction_hash)),
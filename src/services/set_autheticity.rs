@@ -14,6 +14,7 @@ use serde_json::json;
 use std::sync::Arc;
 use utoipa::ToSchema;
 use crate::ownership::ownership_abi::Ownership;
+use crate::billing::gas_sponsorship::{reserve_gas, settle_gas, GasOperation, Sponsor};
 use crate::config::app_state::AppState;
 
 // Define the input struct for the endpoint
@@ -44,6 +45,7 @@ struct ErrorResponse {
             "transaction_hash": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
         })),
         (status = 400, description = "Invalid input (e.g., invalid authenticity address)", body = ErrorResponse, example = json!({"error": "Invalid authenticity address"})),
+        (status = 402, description = "Gas budget of the platform is used up for this month", body = ErrorResponse, example = json!({"error": "Gas budget exhausted"})),
         (status = 403, description = "Caller is not the contract owner", body = ErrorResponse, example = json!({"error": "Caller is not the contract owner"})),
         (status = 500, description = "Internal server error (e.g., contract interaction failed)", body = ErrorResponse, example = json!({"error": "Internal server error: Failed to send transaction"}))
     ),
@@ -62,6 +64,7 @@ pub async fn set_authenticity(
             eprintln!("Error setting authenticity address {}: {:?}", request.authenticity_address, e);
             let (status, message) = match e.to_string().as_str() {
                 s if s.contains("Invalid authenticity address") => (StatusCode::BAD_REQUEST, e.to_string()),
+                s if s.contains("Gas budget exhausted") => (StatusCode::PAYMENT_REQUIRED, e.to_string()),
                 s if s.contains("ONLY_OWNER") => (StatusCode::FORBIDDEN, "Caller is not the contract owner".to_string()),
                 _ => (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -83,6 +86,13 @@ async fn set_authenticity_internal(
         .parse()
         .map_err(|_| eyre::eyre!("Invalid authenticity address"))?;
 
+    // Contract administration is paid by the platform itself
+    let sponsor = Sponsor::platform();
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        eyre::eyre!("Failed to get DB connection: {}", e)
+    })?;
+
     // Get the contract and wallet details
     let contract = &state.ownership_contract;
     let wallet_address = contract.client().address();
@@ -118,26 +128,30 @@ async fn set_authenticity_internal(
         ));
     }
 
+    // the buffered fee is held against the sponsor's budget until the receipt is in
+    let reservation = reserve_gas(conn, &sponsor, required_funds)?;
+
     // Prepare and send the transaction
     let call = contract
         .set_authenticity(authenticity_address)
         .gas(gas_limit)
         .gas_price(gas_price);
 
-    let pending_tx = call
-        .send()
-        .await
-        .map_err(|e| {
-            // Attempt to parse revert reason
-            let revert_reason = e.to_string();
-            eyre::eyre!("Failed to send transaction: {}", revert_reason)
-        })?;
-
-    // Await transaction confirmation
-    let receipt = pending_tx
-        .await
-        .map_err(|e| eyre::eyre!("Failed to confirm transaction: {}", e))?
-        .ok_or_else(|| eyre::eyre!("Transaction receipt not found"))?;
+    let sent = async {
+        call.send()
+            .await
+            .map_err(|e| {
+                // Attempt to parse revert reason
+                let revert_reason = e.to_string();
+                eyre::eyre!("Failed to send transaction: {}", revert_reason)
+            })?
+            // Await transaction confirmation
+            .await
+            .map_err(|e| eyre::eyre!("Failed to confirm transaction: {}", e))?
+            .ok_or_else(|| eyre::eyre!("Transaction receipt not found"))
+    }
+    .await;
+    let receipt = settle_gas(conn, reservation, GasOperation::SetAuthenticity, sent, gas_price)?;
 
     Ok(SetAuthenticityResponse {
         transaction_hash: format!("0x{}", hex::encode(receipt.transaction_hash)),
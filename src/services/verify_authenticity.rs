@@ -1,8 +1,21 @@
 use crate::models::certificate_model::{
     Certificate, SignedCertificate,
 };
+use crate::analytics::clone_detection::detect_clones;
+use crate::analytics::verification_log::{record_verification, VerificationAttempt, VerificationResult};
+use crate::authenticity::certificate_revocation::revocation;
+use crate::authenticity::manufacturer_profile::manufacturer_profile;
+use crate::authenticity::signing_keys::{authorized_key, chain_issued_at};
+use crate::billing::api_keys::is_active_key;
 use crate::config::app_state::AppState;
-use axum::{extract::State, http::StatusCode, Json};
+use crate::contract_models::{ItemFlag, ManufacturerProfile};
+use crate::ownership::item_status::{active_flag, ItemStatus};
+use crate::recalls::recall_campaign::{item_recalls, ItemRecall};
+use axum::{
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
 use ethers::types::transaction::eip712::Eip712;
 use ethers::{
     contract::EthEvent,
@@ -10,34 +23,137 @@ use ethers::{
     signers::Signer,
     types::Signature,
 };
+use serde::Serialize;
 use std::error::Error;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use utoipa::ToSchema;
 use validator::Validate;
 use crate::authenticity::authenticity_abi::{authenticity, Authenticity};
 
+#[derive(Serialize, ToSchema)]
+pub struct VerificationResponse {
+    // the signer when it is not the registered manufacturer
+    manufacturer_address: String,
+    manufacturer_name: String,
+    // the manufacturer's authorized key that signed, when it was not its own address
+    #[schema(nullable = true)]
+    signing_key: Option<String>,
+    // who made the item, as reviewed by admins; absent for counterfeits
+    #[schema(nullable = true)]
+    manufacturer_profile: Option<ManufacturerProfile>,
+    // anything other than `active` means the item was reported stolen, lost, recalled or destroyed
+    item_status: ItemStatus,
+    #[schema(nullable = true)]
+    flag: Option<ItemFlag>,
+    // open recall campaigns covering the item
+    recalls: Vec<ItemRecall>,
+}
 
 #[utoipa::path(
     post,
     path = "/verify_authenticity",
     request_body = SignedCertificate,
     responses(
-        (status = 200, description = "Signature verification result", body = String),
+        (status = 200, description = "Signature verification result", body = VerificationResponse, example = json!({
+            "manufacturer_address": "0x1234…5678",
+            "manufacturer_name": "Acme Corp",
+            "signing_key": null,
+            "manufacturer_profile": {
+                "legalName": "Acme Corporation Ltd.",
+                "logoUrl": "https://cdn.example.com/acme/logo.png",
+                "website": "https://acme.example.com",
+                "contactEmail": "support@acme.example.com",
+                "contactPhone": null,
+                "kybStatus": "verified",
+                "kybReviewedAt": "2025-09-30T09:00:00Z",
+                "profileUpdatedAt": "2025-09-29T16:20:00Z"
+            },
+            "item_status": "stolen",
+            "flag": {
+                "id": 3,
+                "item_id": "item123",
+                "status": "stolen",
+                "reason": "Taken from my car on 2025-09-14",
+                "evidence": ["https://police.example.com/reports/48213"],
+                "flagged_by": "0xabcdef1234567890abcdef1234567890abcdef12",
+                "created_at": "2025-09-15T10:00:00Z",
+                "cleared_by": null,
+                "cleared_at": null
+            },
+            "recalls": [{
+                "campaign_id": 1,
+                "title": "Battery overheating",
+                "reason": "Cells from one supplier batch may overheat while charging",
+                "remedy": "Free battery replacement at any service center",
+                "acknowledged": false
+            }]
+        })),
         (status = 400, description = "Invalid input"),
+        (status = 410, description = "Certificate is genuine but was revoked by its manufacturer"),
+        (status = 422, description = "Certificate was not signed by the address it names or by one of its keys valid when the certificate was issued"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn verify_authenticity(
     State(state): State<Arc<AppState>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(cert): Json<SignedCertificate>,
-) -> Result<Json<(String, String)>, StatusCode> {
+) -> Result<Json<VerificationResponse>, StatusCode> {
+    // every attempt is kept for the manufacturer's audit log and clone detection
+    let mut attempt = VerificationAttempt::new(
+        &cert.unique_id,
+        &cert.owner,
+        &headers,
+        remote,
+        &state.trusted_proxies,
+    );
+
+    let response = verify_certificate(&state, &cert, &mut attempt).await;
+
+    let result = attempt.result.unwrap_or(match response {
+        Err(StatusCode::BAD_REQUEST) => VerificationResult::Invalid,
+        _ => VerificationResult::Error,
+    });
+    let mut log = attempt.into_log(result);
+    match state.db_pool.get() {
+        Ok(mut conn) => {
+            // a key that was never issued says nothing about who scanned, so clone detection
+            // falls back to the client IP for it
+            if let Some(key_hash) = &log.api_key_hash
+                && !is_active_key(&mut conn, key_hash).unwrap_or(false)
+            {
+                log.api_key_hash = None;
+            }
+            if record_verification(&mut conn, &log)
+                && let Err(e) = detect_clones(&mut conn, &log)
+            {
+                eprintln!("Clone detection error for {}: {:?}", log.unique_id, e);
+            }
+        }
+        Err(e) => eprintln!("Failed to get DB connection: {:?}", e),
+    }
+
+    response
+}
+
+async fn verify_certificate(
+    state: &Arc<AppState>,
+    cert: &SignedCertificate,
+    attempt: &mut VerificationAttempt,
+) -> Result<Json<VerificationResponse>, StatusCode> {
     let certificate: Certificate = cert
         .clone()
         .try_into()
         .map_err(|_| StatusCode::BAD_REQUEST)?;
+    attempt.claimed_manufacturer = format!("{:?}", certificate.owner);
 
     // to validate input
-    cert.validate()
-        .expect(&*StatusCode::BAD_REQUEST.to_string());
+    cert.validate().map_err(|e| {
+        eprintln!("Invalid certificate: {:?}", e);
+        StatusCode::BAD_REQUEST
+    })?;
 
     // Parse the signature from hex string
     let signature_bytes = hex::decode(cert.signature.trim_start_matches("0x")).map_err(|e| {
@@ -59,6 +175,8 @@ pub async fn verify_authenticity(
         eprintln!("EIP-712 encoding error: {:?}", e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
+    let cert_hash = format!("0x{}", hex::encode(digest));
+    attempt.cert_hash = Some(cert_hash.clone());
 
     //this caused big issue until I removed it
     // let digest = hash_message(digest); // Prefix with \x19Ethereum Signed Message
@@ -70,16 +188,64 @@ pub async fn verify_authenticity(
         eprintln!("Signer recovery error: {:?}", e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
+    attempt.signer = Some(format!("{:?}", signer));
 
     eprintln!("Signer: {:?}", signer);
-    // very important: double check to make sure the certificate owner is the signer of the signature
-    assert_eq!(signer, certificate.owner);
+
+    // very important: double check to make sure the certificate owner is the signer of the signature,
+    // or one of its keys that was valid when the certificate was issued
+    let signing_key = if signer == certificate.owner {
+        None
+    } else {
+        let issued_at = chain_issued_at(state, &cert_hash).await.map_err(|e| {
+            eprintln!("Certificate issue time lookup error: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        let conn = &mut state.db_pool.get().map_err(|e| {
+            eprintln!("Failed to get DB connection: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        let key = authorized_key(
+            conn,
+            &format!("{:?}", certificate.owner),
+            &format!("{:?}", signer),
+            issued_at,
+        )
+        .map_err(|e| {
+            eprintln!("Signing key lookup error: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        match key {
+            Some(key) => Some(key.key_address),
+            None => {
+                attempt.result = Some(VerificationResult::Counterfeit);
+                return Err(StatusCode::UNPROCESSABLE_ENTITY);
+            }
+        }
+    };
+
+    // a genuine certificate the manufacturer revoked no longer vouches for anything
+    let conn = &mut state.db_pool.get().map_err(|e| {
+        eprintln!("Failed to get DB connection: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    if revocation(conn, &cert_hash)
+        .map_err(|e| {
+            eprintln!("Certificate revocation lookup error: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .is_some()
+    {
+        attempt.result = Some(VerificationResult::Revoked);
+        return Err(StatusCode::GONE);
+    }
+
     // Fetch the contract's owner
     // let contract = Authenticity::new(state.authenticity_contract, state.eth_client.clone());
     let contract = state.authenticity_contract.clone();
 
     let manufacturer: authenticity::Manufacturer = contract
-        .get_manufacturer(signer)
+        .get_manufacturer(certificate.owner)
         .call()
         .await
         .map_err(|e| {
@@ -88,20 +254,52 @@ pub async fn verify_authenticity(
         })?;
 
     eprintln!("Manufacturer Address: {:?}", manufacturer.manufacturer_address);
-    // Verify the signer matches the owner
-    if signer == manufacturer.manufacturer_address {
-        Ok(Json((manufacturer.manufacturer_address.to_string(), manufacturer.name)))
-        //     format!(
-        //     "Signature is valid! Signed by owner: {:?}",
-        //     signer
-        // ))
-        // )
+
+    // a genuine certificate can still belong to a stolen or recalled item
+    let flag = active_flag(conn, &certificate.unique_id).map_err(|e| {
+        eprintln!("Item flag lookup error: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let item_status = match &flag {
+        Some(flag) => ItemStatus::parse(&flag.status).map_err(|e| {
+            eprintln!("Item flag lookup error: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?,
+        None => ItemStatus::Active,
+    };
+    let recalls = item_recalls(conn, &certificate.unique_id).map_err(|e| {
+        eprintln!("Item recall lookup error: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    // Verify the owner is the registered manufacturer
+    let authentic = certificate.owner == manufacturer.manufacturer_address;
+    let manufacturer_address = if authentic {
+        manufacturer.manufacturer_address.to_string()
     } else {
-        Ok(Json( (signer.to_string(), manufacturer.name) ))
-        //     format!(
-        //     "Signature is invalid. Recovered signer: {:?}, expected owner: {:?}",
-        //     signer, manufacturer.manufacturer_address
-        // ))
-        // )
-    }
-}
\ No newline at end of file
+        signer.to_string()
+    };
+    let manufacturer_profile = if authentic {
+        manufacturer_profile(conn, &format!("{:?}", certificate.owner)).map_err(|e| {
+            eprintln!("Manufacturer profile lookup error: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+    } else {
+        None
+    };
+    attempt.result = Some(if authentic {
+        VerificationResult::Authentic
+    } else {
+        VerificationResult::Counterfeit
+    });
+
+    Ok(Json(VerificationResponse {
+        manufacturer_address,
+        manufacturer_name: manufacturer.name,
+        signing_key,
+        manufacturer_profile,
+        item_status,
+        flag,
+        recalls,
+    }))
+}
@@ -0,0 +1,277 @@
+// SPDX-License-Identifier: MIT
+pragma solidity 0.8.29;
+
+import {Authenticity} from "../contracts/Authenticity.sol";
+import {EriErrors} from "../contracts/EriErrors.sol";
+import {IEri} from "../contracts/IEri.sol";
+import {Ownership} from "../contracts/Ownership.sol";
+import {Test} from "forge-std/Test.sol";
+
+contract SigningKeysTest is Test {
+    Authenticity public authenticity;
+    Ownership public ownership;
+
+    string public constant CERTIFICATE_TYPE =
+        "Certificate(string name,string uniqueId,string serial,uint256 date,address owner,bytes32 metadataHash)";
+
+    address public owner = address(0x100);
+    address public user = address(0x456);
+    address public stranger = address(0x789);
+
+    uint256 public manufacturerPrivateKey = 0xA11CE;
+    address public manufacturer = vm.addr(manufacturerPrivateKey);
+
+    uint256 public keyPrivateKey = 0xB0B;
+    address public key = vm.addr(keyPrivateKey);
+
+    uint256 public newKeyPrivateKey = 0xC0FFEE;
+    address public newKey = vm.addr(newKeyPrivateKey);
+
+    event SigningKeyAuthorized(address indexed manufacturer, address indexed key, uint64 validFrom, uint64 validUntil);
+    event SigningKeyRotated(address indexed manufacturer, address indexed oldKey, address indexed newKey, uint64 rotatedAt);
+    event SigningKeyRevoked(address indexed manufacturer, address indexed key, uint64 revokedAt);
+
+    function setUp() public {
+        vm.warp(1_700_000_000);
+
+        ownership = new Ownership(owner);
+        authenticity = new Authenticity(address(ownership), CERTIFICATE_TYPE, "CertificateAuth", "1");
+
+        vm.prank(owner);
+        ownership.setAuthenticity(address(authenticity));
+
+        vm.prank(manufacturer);
+        authenticity.manufacturerRegisters("Xiaomi");
+
+        vm.prank(user);
+        ownership.userRegisters("alice");
+    }
+
+    function certificate(string memory uniqueId, uint256 date) internal view returns (IEri.Certificate memory) {
+        string[] memory metadata = new string[](2);
+        metadata[0] = "Xiaomi";
+        metadata[1] = "5G";
+
+        return IEri.Certificate({
+            name: "Redmi Note 14",
+            uniqueId: uniqueId,
+            serial: "SN7890",
+            date: date,
+            owner: manufacturer,
+            metadataHash: keccak256(abi.encode(metadata)),
+            metadata: metadata
+        });
+    }
+
+    function digest(IEri.Certificate memory cert) internal view returns (bytes32) {
+        bytes32 structHash = keccak256(
+            abi.encode(
+                keccak256(bytes(CERTIFICATE_TYPE)),
+                keccak256(bytes(cert.name)),
+                keccak256(bytes(cert.uniqueId)),
+                keccak256(bytes(cert.serial)),
+                cert.date,
+                cert.owner,
+                cert.metadataHash
+            )
+        );
+        return authenticity.hashTypedDataV4(structHash);
+    }
+
+    function sign(uint256 privateKey, IEri.Certificate memory cert) internal view returns (bytes memory) {
+        (uint8 v, bytes32 r, bytes32 s) = vm.sign(privateKey, digest(cert));
+        return abi.encodePacked(r, s, v);
+    }
+
+    function authorizeKey() internal {
+        vm.prank(manufacturer);
+        authenticity.authorizeSigningKey(key, uint64(block.timestamp), 0);
+    }
+
+    function testAuthorizeSigningKeyStoresKey() public {
+        vm.expectEmit(true, true, false, true);
+        emit SigningKeyAuthorized(manufacturer, key, uint64(block.timestamp), 0);
+        authorizeKey();
+
+        IEri.SigningKey memory signingKey = authenticity.getSigningKey(key);
+        assertEq(signingKey.manufacturer, manufacturer);
+        assertEq(signingKey.validFrom, uint64(block.timestamp));
+        assertEq(signingKey.validUntil, 0);
+        assertEq(signingKey.revokedAt, 0);
+    }
+
+    function testAuthorizedKeySignsForManufacturer() public {
+        authorizeKey();
+
+        IEri.Certificate memory cert = certificate("XM1", block.timestamp);
+        assertTrue(authenticity.verifySignature(cert, sign(keyPrivateKey, cert)));
+    }
+
+    function testAuthorizeSigningKeyRequiresManufacturer() public {
+        vm.prank(stranger);
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.NOT_REGISTERED.selector, stranger));
+        authenticity.authorizeSigningKey(key, uint64(block.timestamp), 0);
+    }
+
+    function testAuthorizeSigningKeyRejectsOwnAddress() public {
+        vm.prank(manufacturer);
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.INVALID_SIGNING_KEY.selector, manufacturer));
+        authenticity.authorizeSigningKey(manufacturer, uint64(block.timestamp), 0);
+    }
+
+    function testAuthorizeSigningKeyRejectsKnownKey() public {
+        authorizeKey();
+
+        vm.prank(manufacturer);
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.ALREADY_REGISTERED.selector, key));
+        authenticity.authorizeSigningKey(key, uint64(block.timestamp), 0);
+    }
+
+    function testAuthorizeSigningKeyRejectsEmptyWindow() public {
+        vm.prank(manufacturer);
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.INVALID_VALIDITY_WINDOW.selector));
+        authenticity.authorizeSigningKey(key, uint64(block.timestamp), uint64(block.timestamp));
+    }
+
+    function testRotateSigningKeyHandsOver() public {
+        authorizeKey();
+
+        vm.warp(block.timestamp + 1 days);
+        vm.expectEmit(true, true, true, true);
+        emit SigningKeyRotated(manufacturer, key, newKey, uint64(block.timestamp));
+        vm.prank(manufacturer);
+        authenticity.rotateSigningKey(key, newKey, 0);
+
+        assertEq(authenticity.getSigningKey(key).validUntil, uint64(block.timestamp));
+        assertEq(authenticity.getSigningKey(newKey).validFrom, uint64(block.timestamp));
+
+        IEri.Certificate memory late = certificate("XM2", block.timestamp);
+        assertTrue(authenticity.verifySignature(late, sign(newKeyPrivateKey, late)));
+
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.INVALID_SIGNATURE.selector));
+        authenticity.verifySignature(late, sign(keyPrivateKey, late));
+    }
+
+    function testRotateSigningKeyOnlyByItsManufacturer() public {
+        authorizeKey();
+
+        vm.prank(user);
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.NOT_REGISTERED.selector, user));
+        authenticity.rotateSigningKey(key, newKey, 0);
+    }
+
+    function testRotateRevokedKeyReverts() public {
+        authorizeKey();
+        vm.prank(manufacturer);
+        authenticity.revokeSigningKey(key, 0);
+
+        vm.prank(manufacturer);
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.SIGNING_KEY_REVOKED.selector, key));
+        authenticity.rotateSigningKey(key, newKey, 0);
+    }
+
+    function testRevokeSigningKeyClampsFutureCutOff() public {
+        authorizeKey();
+
+        vm.expectEmit(true, true, false, true);
+        emit SigningKeyRevoked(manufacturer, key, uint64(block.timestamp));
+        vm.prank(manufacturer);
+        authenticity.revokeSigningKey(key, uint64(block.timestamp + 1 days));
+
+        assertEq(authenticity.getSigningKey(key).revokedAt, uint64(block.timestamp));
+    }
+
+    function testRevokeSigningKeyOnlyByItsManufacturer() public {
+        authorizeKey();
+
+        vm.prank(stranger);
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.ONLY_OWNER.selector, stranger));
+        authenticity.revokeSigningKey(key, 0);
+    }
+
+    function testRevokeSigningKeyTwiceReverts() public {
+        authorizeKey();
+        vm.prank(manufacturer);
+        authenticity.revokeSigningKey(key, 0);
+
+        vm.prank(manufacturer);
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.SIGNING_KEY_REVOKED.selector, key));
+        authenticity.revokeSigningKey(key, 0);
+    }
+
+    // the leak: a certificate signed after revocation but dated before it
+    function testRevokedKeyCannotBackdateCertificates() public {
+        authorizeKey();
+        uint256 beforeLeak = block.timestamp;
+
+        vm.warp(block.timestamp + 1 days);
+        vm.prank(manufacturer);
+        authenticity.revokeSigningKey(key, 0);
+
+        IEri.Certificate memory backdated = certificate("XM3", beforeLeak);
+        bytes memory signature = sign(keyPrivateKey, backdated);
+
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.INVALID_SIGNATURE.selector));
+        authenticity.verifySignature(backdated, signature);
+
+        vm.prank(user);
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.INVALID_SIGNATURE.selector));
+        authenticity.userClaimOwnership(backdated, signature);
+    }
+
+    function testRecordedCertificatesSurviveRevocation() public {
+        authorizeKey();
+
+        IEri.Certificate memory recorded = certificate("XM4", block.timestamp);
+        bytes32[] memory digests = new bytes32[](1);
+        digests[0] = digest(recorded);
+        vm.prank(manufacturer);
+        authenticity.recordCertificates(digests);
+        assertEq(authenticity.getIssuedAt(digests[0]), uint64(block.timestamp));
+
+        vm.warp(block.timestamp + 1 days);
+        vm.prank(manufacturer);
+        authenticity.revokeSigningKey(key, 0);
+
+        vm.prank(user);
+        authenticity.userClaimOwnership(recorded, sign(keyPrivateKey, recorded));
+        assertEq(ownership.getItem("XM4").owner, user);
+    }
+
+    function testClaimRecordsIssuance() public {
+        authorizeKey();
+
+        IEri.Certificate memory claimed = certificate("XM5", block.timestamp);
+        bytes memory signature = sign(keyPrivateKey, claimed);
+        vm.prank(user);
+        authenticity.userClaimOwnership(claimed, signature);
+        assertEq(authenticity.getIssuedAt(digest(claimed)), uint64(block.timestamp));
+
+        vm.warp(block.timestamp + 1 days);
+        vm.prank(manufacturer);
+        authenticity.revokeSigningKey(key, 0);
+
+        assertTrue(authenticity.verifySignature(claimed, signature));
+    }
+
+    // a leak noticed late moves the cut-off back, past certificates recorded by the thief
+    function testRevocationCutOffPrecedesRecordsMadeWithLeakedKey() public {
+        authorizeKey();
+        uint256 leakedAt = block.timestamp + 1 hours;
+
+        vm.warp(leakedAt + 1 hours);
+        IEri.Certificate memory forged = certificate("XM6", block.timestamp);
+        bytes memory signature = sign(keyPrivateKey, forged);
+        vm.prank(user);
+        authenticity.userClaimOwnership(forged, signature);
+
+        vm.warp(block.timestamp + 1 days);
+        vm.prank(manufacturer);
+        authenticity.revokeSigningKey(key, uint64(leakedAt));
+
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.INVALID_SIGNATURE.selector));
+        authenticity.verifySignature(forged, signature);
+    }
+}
+
+//forge test --match-path test/SigningKeys.t.sol -vvv
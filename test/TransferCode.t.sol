@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: MIT
+pragma solidity 0.8.29;
+
+import {EriErrors} from "../contracts/EriErrors.sol";
+import {IEri} from "../contracts/IEri.sol";
+import {Ownership} from "../contracts/Ownership.sol";
+import {Test} from "forge-std/Test.sol";
+
+contract TransferCodeTest is Test {
+    Ownership public ownership;
+
+    address public owner = address(0x123);
+    address public authenticity = address(0x321);
+    address public manufacturer = address(0x456);
+    address public firstOwner = address(0x789);
+    address public secondOwner = address(0x112);
+    address public stranger = address(0x222);
+
+    string public constant ITEM_ID = "XM123456";
+    string public constant CODE = "7KQ2M9XD4TPRVH3C";
+    bytes32 public codeHash = keccak256(bytes(CODE));
+    uint64 public constant TTL = 1 days;
+
+    event OwnershipCode(string itemId, bytes32 indexed codeHash, address indexed owner, address indexed tempOwner);
+    event CodeRevoked(bytes32 indexed itemHash);
+    event OwnershipTransferred(string itemId, address indexed newOnwer, address indexed oldOnwer);
+
+    function setUp() public {
+        ownership = new Ownership(owner);
+
+        vm.prank(owner);
+        ownership.setAuthenticity(authenticity);
+
+        registerUser(firstOwner, "alice");
+        registerUser(secondOwner, "bob");
+        registerUser(stranger, "mallory");
+
+        string[] memory metadata = new string[](2);
+        metadata[0] = "Xiaomi";
+        metadata[1] = "5G";
+
+        IEri.Certificate memory certificate = IEri.Certificate({
+            name: "Redmi Note 14",
+            uniqueId: ITEM_ID,
+            serial: "SN7890",
+            date: block.timestamp,
+            owner: manufacturer,
+            metadataHash: keccak256(abi.encode(metadata)),
+            metadata: metadata
+        });
+
+        vm.prank(authenticity);
+        ownership.createItem(firstOwner, certificate, "Xiaomi");
+    }
+
+    function registerUser(address user, string memory username) internal {
+        vm.prank(user);
+        ownership.userRegisters(username);
+    }
+
+    function commitCode() internal {
+        vm.prank(firstOwner);
+        ownership.generateCode(ITEM_ID, codeHash, secondOwner, expiry());
+    }
+
+    function expiry() internal view returns (uint64) {
+        return uint64(block.timestamp) + TTL;
+    }
+
+    function testGenerateCodeStoresCommitment() public {
+        vm.expectEmit(true, true, true, true);
+        emit OwnershipCode(ITEM_ID, codeHash, firstOwner, secondOwner);
+        commitCode();
+
+        IEri.TransferCode memory transferCode = ownership.getTransferCode(ITEM_ID);
+        assertEq(transferCode.codeHash, codeHash);
+        assertEq(transferCode.tempOwner, secondOwner);
+        assertEq(transferCode.expiresAt, expiry());
+    }
+
+    function testGenerateCodeOnlyByOwner() public {
+        vm.prank(stranger);
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.ONLY_OWNER.selector, stranger));
+        ownership.generateCode(ITEM_ID, codeHash, secondOwner, expiry());
+    }
+
+    function testGenerateCodeRejectsPastExpiry() public {
+        vm.prank(firstOwner);
+        vm.expectRevert(EriErrors.INVALID_VALIDITY_WINDOW.selector);
+        ownership.generateCode(ITEM_ID, codeHash, secondOwner, uint64(block.timestamp));
+    }
+
+    function testGenerateCodeRejectsSecondCode() public {
+        commitCode();
+
+        vm.prank(firstOwner);
+        vm.expectRevert(EriErrors.CODE_ALREADY_GENERATED.selector);
+        ownership.generateCode(ITEM_ID, keccak256("another"), stranger, expiry());
+    }
+
+    function testGenerateCodeNotForYourself() public {
+        vm.prank(firstOwner);
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.CANNOT_GENERATE_CODE_FOR_YOURSELF.selector, firstOwner));
+        ownership.generateCode(ITEM_ID, codeHash, firstOwner, expiry());
+    }
+
+    function testClaimWithCodeTransfersOwnership() public {
+        commitCode();
+
+        vm.expectEmit(true, true, true, true);
+        emit OwnershipTransferred(ITEM_ID, secondOwner, firstOwner);
+        vm.prank(secondOwner);
+        ownership.claimWithCode(ITEM_ID, CODE);
+
+        assertTrue(ownership.isOwner(secondOwner, ITEM_ID));
+        assertEq(ownership.getTransferCode(ITEM_ID).codeHash, bytes32(0));
+    }
+
+    function testClaimWithWrongCodeReverts() public {
+        commitCode();
+
+        vm.prank(secondOwner);
+        vm.expectRevert(EriErrors.INVALID_CODE.selector);
+        ownership.claimWithCode(ITEM_ID, "0000000000000000");
+
+        assertTrue(ownership.isOwner(firstOwner, ITEM_ID));
+    }
+
+    function testClaimByNonTempOwnerReverts() public {
+        commitCode();
+
+        vm.prank(stranger);
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.UNAUTHORIZED.selector, stranger));
+        ownership.claimWithCode(ITEM_ID, CODE);
+    }
+
+    function testCodeCanOnlyBeUsedOnce() public {
+        commitCode();
+
+        vm.prank(secondOwner);
+        ownership.claimWithCode(ITEM_ID, CODE);
+
+        vm.prank(secondOwner);
+        vm.expectRevert(EriErrors.DOES_NOT_EXIST.selector);
+        ownership.claimWithCode(ITEM_ID, CODE);
+    }
+
+    function testRevokeCodeBlocksClaim() public {
+        commitCode();
+
+        vm.expectEmit(true, false, false, false);
+        emit CodeRevoked(codeHash);
+        vm.prank(firstOwner);
+        ownership.revokeCode(ITEM_ID);
+
+        vm.prank(secondOwner);
+        vm.expectRevert(EriErrors.DOES_NOT_EXIST.selector);
+        ownership.claimWithCode(ITEM_ID, CODE);
+    }
+
+    function testRevokeCodeOnlyByOwner() public {
+        commitCode();
+
+        vm.prank(secondOwner);
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.ONLY_OWNER.selector, secondOwner));
+        ownership.revokeCode(ITEM_ID);
+    }
+
+    function testRevokeWithoutCodeReverts() public {
+        vm.prank(firstOwner);
+        vm.expectRevert(EriErrors.DOES_NOT_EXIST.selector);
+        ownership.revokeCode(ITEM_ID);
+    }
+
+    function testExpiredCodeCannotBeClaimed() public {
+        commitCode();
+        vm.warp(block.timestamp + TTL);
+
+        vm.prank(secondOwner);
+        vm.expectRevert(EriErrors.CODE_EXPIRED.selector);
+        ownership.claimWithCode(ITEM_ID, CODE);
+
+        assertTrue(ownership.isOwner(firstOwner, ITEM_ID));
+    }
+
+    function testExpiredCodeCanBeReplacedWithoutRevoke() public {
+        commitCode();
+        vm.warp(block.timestamp + TTL);
+
+        bytes32 newHash = keccak256("NEWCODE");
+        vm.prank(firstOwner);
+        ownership.generateCode(ITEM_ID, newHash, secondOwner, expiry());
+
+        vm.prank(secondOwner);
+        vm.expectRevert(EriErrors.INVALID_CODE.selector);
+        ownership.claimWithCode(ITEM_ID, CODE);
+
+        vm.prank(secondOwner);
+        ownership.claimWithCode(ITEM_ID, "NEWCODE");
+        assertTrue(ownership.isOwner(secondOwner, ITEM_ID));
+    }
+}
@@ -98,4 +98,63 @@
 //        vm.expectRevert(abi.encodeWithSelector(EriErrors.USER_DOES_NOT_EXIST.selector, firstOwner));
 //        ownership.getUser(firstOwner); // Should revert
 //    }
-//}
\ No newline at end of file
+//}
+
+// SPDX-License-Identifier: MIT
+pragma solidity 0.8.29;
+
+import {EriErrors} from "../contracts/EriErrors.sol";
+import {Ownership} from "../contracts/Ownership.sol";
+import {Test} from "forge-std/Test.sol";
+
+contract ChangeUsernameTest is Test {
+    Ownership public ownership;
+
+    address public owner = address(0x123);
+    address public authenticity = address(0x321);
+    address public firstOwner = address(0x789);
+    address public stranger = address(0x222);
+
+    event UsernameChanged(address indexed userAddress, string oldUsername, string newUsername);
+
+    function setUp() public {
+        ownership = new Ownership(owner);
+
+        vm.prank(owner);
+        ownership.setAuthenticity(authenticity);
+
+        vm.prank(firstOwner);
+        ownership.userRegisters("alice");
+    }
+
+    function testChangeUsername() public {
+        vm.prank(firstOwner);
+        ownership.changeUsername("alicia");
+
+        assertEq(ownership.getUsername(firstOwner), "alicia");
+    }
+
+    function testChangeUsernameEmitsEvent() public {
+        vm.expectEmit(true, false, false, true);
+        emit UsernameChanged(firstOwner, "alice", "alicia");
+
+        vm.prank(firstOwner);
+        ownership.changeUsername("alicia");
+    }
+
+    function testChangeUsernameRequiresRegisteredCaller() public {
+        vm.prank(stranger);
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.NOT_REGISTERED.selector, stranger));
+        ownership.changeUsername("mallory");
+    }
+
+    function testChangeUsernameTooShort() public {
+        vm.prank(firstOwner);
+        vm.expectRevert(abi.encodeWithSelector(EriErrors.USERNAME_MUST_BE_AT_LEAST_3_LETTERS.selector));
+        ownership.changeUsername("al");
+
+        assertEq(ownership.getUsername(firstOwner), "alice");
+    }
+}
+
+//forge test --match-path test/UserRegistration.t.sol --match-contract ChangeUsernameTest -vvv